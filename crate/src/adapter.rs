@@ -6,9 +6,12 @@ use ed25519_dalek::Signature;
 use web_sys::{js_sys::Object, Document, Window};
 
 use crate::{
-    events::InitEvents, send_wallet_event, Cluster, SendOptions, SignInOutput, SignedMessageOutput,
-    SigninInput, Wallet, WalletAccount, WalletError, WalletEvent, WalletEventReceiver,
-    WalletEventSender, WalletResult, WalletStorage,
+    events::{track_request, InitEvents},
+    send_wallet_event, ChainSupport, Cluster, DiagnosticsStorage, FeatureSupport, Features,
+    KnownWallets, LagPolicy, SendOptions, SignInOutput, SignedMessageOutput, SigninInput, Wallet,
+    WalletAccount, WalletDiagnostics, WalletError, WalletEvent, WalletEventReceiver,
+    WalletEventSender, WalletFilter, WalletInstallState, WalletListing, WalletRequestKind,
+    WalletResult, WalletStorage, DEFAULT_EVENT_CAPACITY, USED_WALLETS_STORAGE_KEY,
 };
 
 /// Contains the connected wallet and account.
@@ -46,11 +49,11 @@ impl ConnectionInfo {
     pub async fn connect(&mut self, sender: WalletEventSender) -> WalletResult<WalletAccount> {
         let wallet = self.connected_wallet()?;
 
-        let connected_account = wallet.features.connect.call_connect().await?;
+        let connected_account = wallet.connect().await?;
 
         self.set_account(connected_account.clone());
 
-        send_wallet_event(WalletEvent::Connected(connected_account.clone()), sender).await;
+        send_wallet_event(WalletEvent::Connected(connected_account.clone()), sender);
 
         Ok(connected_account)
     }
@@ -61,7 +64,7 @@ impl ConnectionInfo {
         self.account.take();
         self.previous_accounts.clear();
 
-        send_wallet_event(WalletEvent::Disconnected, sender).await;
+        send_wallet_event(WalletEvent::Disconnected, sender);
 
         self
     }
@@ -147,17 +150,84 @@ impl ConnectionInfo {
                     }
                 };
 
-                send_wallet_event(event_outcome, sender).await
+                send_wallet_event(event_outcome, sender)
             }
             Err(error) => {
-                web_sys::console::log_2(
-                    &"ON EVENT EMITTED BUT NO CONNECTED WALLET FOUND: ".into(),
-                    &format!("{error:?}").into(),
+                wallet_log!(
+                    warn,
+                    "Wallet event emitted by `{wallet_name}` wallet but no connected wallet found: {error}"
                 );
             }
         }
     }
 
+    /// Update the chains of the registered wallet in the `storage` and of the connected wallet
+    /// after a `[standard:events].on` change event with a `chains` property
+    /// and emit [WalletEvent::ChainsChanged]
+    pub(crate) fn update_chains(
+        &mut self,
+        wallet_name: &str,
+        chains: Vec<Cluster>,
+        supported_chains: ChainSupport,
+        storage: Option<&WalletStorage>,
+        sender: WalletEventSender,
+    ) {
+        if let Some(storage) = storage {
+            storage.update_wallet(wallet_name, |wallet| {
+                wallet.set_chains(chains.clone(), supported_chains.clone());
+            });
+        }
+
+        if let Some(wallet) = self.connected_wallet_mut(wallet_name) {
+            wallet.set_chains(chains.clone(), supported_chains);
+
+            send_wallet_event(WalletEvent::ChainsChanged(chains), sender)
+        }
+    }
+
+    /// Update the features of the registered wallet in the `storage` and of the connected wallet
+    /// after a `[standard:events].on` change event with a `features` property
+    /// and emit [WalletEvent::FeaturesChanged]
+    pub(crate) fn update_features(
+        &mut self,
+        wallet_name: &str,
+        features: Features,
+        supported_features: FeatureSupport,
+        storage: Option<&WalletStorage>,
+        sender: WalletEventSender,
+    ) {
+        if let Some(storage) = storage {
+            storage.update_wallet(wallet_name, |wallet| {
+                wallet.set_features(features.clone(), supported_features.clone());
+            });
+        }
+
+        if let Some(wallet) = self.connected_wallet_mut(wallet_name) {
+            wallet.set_features(features, supported_features);
+
+            send_wallet_event(
+                WalletEvent::FeaturesChanged(wallet.feature_identifiers()),
+                sender,
+            )
+        }
+    }
+
+    fn connected_wallet_mut(&mut self, wallet_name: &str) -> Option<&mut Wallet> {
+        let wallet = self
+            .wallet
+            .as_mut()
+            .filter(|wallet| wallet.name().as_bytes() == wallet_name.as_bytes());
+
+        if wallet.is_none() {
+            wallet_log!(
+                debug,
+                "Wallet change event emitted by `{wallet_name}` wallet which is not connected"
+            );
+        }
+
+        wallet
+    }
+
     fn push_previous_account(&mut self) {
         let take_connected_account = self.account.take();
 
@@ -180,8 +250,8 @@ pub struct WalletAdapter {
     window: Window,
     document: Document,
     storage: WalletStorage,
+    diagnostics: DiagnosticsStorage,
     connection_info: ConnectionInfoInner,
-    wallet_events: WalletEventReceiver,
     wallet_events_sender: WalletEventSender,
     signal_receiver: Receiver<()>,
 }
@@ -189,8 +259,9 @@ pub struct WalletAdapter {
 impl WalletAdapter {
     /// Get the `Window` and `Document` object in the current browser window,
     /// initialize the `AppReady` and `Register` events of the wallet standard
-    /// and creates a bounded channel with capacity default of 5 messages before capacity is filled.
-    /// Use [WalletAdapter::init_with_channel_capacity] to initialize with a desired channel capacity.
+    /// and creates a [WalletEventSender] where every subscriber buffers up to
+    /// [DEFAULT_EVENT_CAPACITY] events before the oldest events are dropped.
+    /// Use [WalletAdapter::init_with_channel_capacity] to initialize with a desired capacity.
     pub fn init() -> WalletResult<Self> {
         let window = if let Some(window) = web_sys::window() {
             window
@@ -204,12 +275,17 @@ impl WalletAdapter {
             return Err(WalletError::MissingAccessToBrowserDocument);
         };
 
-        Self::init_with_channel_capacity_window_and_document(5, window, document)
+        Self::init_with_channel_capacity_window_and_document(
+            DEFAULT_EVENT_CAPACITY,
+            window,
+            document,
+        )
     }
 
     /// Get the `Window` and `Document` object in the current browser window,
     /// initialize the `AppReady` and `Register` events of the wallet standard
-    /// and creates a bounded channel with user-specified capacity.
+    /// and creates a [WalletEventSender] where every subscriber buffers
+    /// up to the user-specified capacity of events.
     pub fn init_with_channel_capacity(capacity: usize) -> WalletResult<Self> {
         let window = if let Some(window) = web_sys::window() {
             window
@@ -227,7 +303,7 @@ impl WalletAdapter {
     }
 
     /// Same as [WalletAdapter::init] but a `capacity` value
-    /// can be passed to create an channel with a desired capacity
+    /// can be passed to buffer a desired number of events for every subscriber
     #[allow(clippy::arc_with_non_send_sync)]
     pub fn init_with_channel_capacity_window_and_document(
         capacity: usize,
@@ -236,20 +312,21 @@ impl WalletAdapter {
     ) -> WalletResult<Self> {
         let storage = WalletStorage::default();
 
-        let (sender, receiver) = bounded::<WalletEvent>(capacity);
-        let (_, signal_receiver) = bounded::<()>(capacity);
+        let sender = WalletEventSender::new(capacity);
+        let (_, signal_receiver) = bounded::<()>(capacity.max(1));
 
         let mut new_self = Self {
             window: window.clone(),
             document,
             storage,
+            diagnostics: DiagnosticsStorage::default(),
             connection_info: Arc::new(RwLock::new(ConnectionInfo::default())),
-            wallet_events: receiver,
             wallet_events_sender: sender,
             signal_receiver,
         };
 
         InitEvents::new(&window).init(&mut new_self)?;
+        new_self.load_used_wallets();
 
         Ok(new_self)
     }
@@ -258,14 +335,27 @@ impl WalletAdapter {
     /// initialized elsewhere. For example some Rust frontend frameworks already
     /// expose the window and document objects, you could pass them here.
     pub fn init_custom(window: Window, document: Document) -> WalletResult<Self> {
-        Self::init_with_channel_capacity_window_and_document(5, window, document)
+        Self::init_with_channel_capacity_window_and_document(
+            DEFAULT_EVENT_CAPACITY,
+            window,
+            document,
+        )
     }
 
     /// Listen for [WalletEvent] to be notified when a wallet
     /// receives `connected`, `disconnected` and `accountChanged` events triggered
-    /// when the `change` event is dispatched by a connected browser extension
+    /// when the `change` event is dispatched by a connected browser extension.
+    /// Every call creates a new subscription that receives every event
+    /// emitted from now on, so multiple listeners do not miss any events.
     pub fn events(&self) -> WalletEventReceiver {
-        self.wallet_events.clone()
+        self.wallet_events_sender.subscribe()
+    }
+
+    /// Same as [WalletAdapter::events] but the subscription buffers up to `capacity`
+    /// events and handles a full buffer using the `lag_policy`
+    pub fn events_with(&self, capacity: usize, lag_policy: LagPolicy) -> WalletEventReceiver {
+        self.wallet_events_sender
+            .subscribe_with(capacity, lag_policy)
     }
 
     /// Send a connect request to the browser wallet
@@ -275,24 +365,30 @@ impl WalletAdapter {
         let signal_receiver = self.signal_receiver.clone();
 
         if self.connection_info().await.connected_wallet().is_ok() {
-            let capacity = signal_receiver.capacity().unwrap_or(5);
+            let capacity = signal_receiver.capacity().unwrap_or(DEFAULT_EVENT_CAPACITY);
             let (_, signal_receiver) = bounded::<()>(capacity);
             self.signal_receiver = signal_receiver;
         }
 
-        let wallet_account = self
-            .connection_info
-            .write()
-            .await
-            .set_wallet(wallet)
-            .connect(sender.clone())
-            .await?;
+        let wallet_account = track_request(&sender, WalletRequestKind::Connect, async {
+            self.connection_info
+                .write()
+                .await
+                .set_wallet(wallet)
+                .connect(sender.clone())
+                .await
+        })
+        .await?;
+
+        self.storage.mark_used(&wallet_name);
+        self.save_used_wallets();
 
         self.connection_info()
             .await
             .connected_wallet()?
-            .call_on_event(
+            .call_on_event_with_storage(
                 self.connection_info.clone(),
+                self.storage.clone(),
                 wallet_name,
                 sender,
                 signal_receiver,
@@ -328,11 +424,15 @@ impl WalletAdapter {
         signin_input: &SigninInput,
         public_key: [u8; 32],
     ) -> WalletResult<SignInOutput> {
-        self.connection_info()
-            .await
-            .connected_wallet()?
-            .sign_in(signin_input, public_key)
-            .await
+        let connection_info = self.connection_info().await;
+        let wallet = connection_info.connected_wallet()?;
+
+        track_request(
+            &self.wallet_events_sender,
+            WalletRequestKind::SignIn,
+            wallet.sign_in(signin_input, public_key),
+        )
+        .await
     }
 
     /// Send a sign and send transaction request to the browser wallet
@@ -342,18 +442,16 @@ impl WalletAdapter {
         cluster: Cluster,
         options: SendOptions,
     ) -> WalletResult<Signature> {
-        let connection_info = self.connection_info();
+        let connection_info = self.connection_info().await;
+        let wallet = connection_info.connected_wallet()?;
+        let account = connection_info.connected_account()?;
 
-        connection_info
-            .await
-            .connected_wallet()?
-            .sign_and_send_transaction(
-                transaction_bytes,
-                cluster,
-                options,
-                self.connection_info().await.connected_account()?,
-            )
-            .await
+        track_request(
+            &self.wallet_events_sender,
+            WalletRequestKind::SignAndSendTransaction,
+            wallet.sign_and_send_transaction(transaction_bytes, cluster, options, account),
+        )
+        .await
     }
 
     /// Send a connect request to the browser wallet
@@ -362,31 +460,30 @@ impl WalletAdapter {
         transaction_bytes: &[u8],
         cluster: Option<Cluster>,
     ) -> WalletResult<Vec<Vec<u8>>> {
-        let connection_info = self.connection_info();
+        let connection_info = self.connection_info().await;
+        let wallet = connection_info.connected_wallet()?;
+        let account = connection_info.connected_account()?;
 
-        connection_info
-            .await
-            .connected_wallet()?
-            .sign_transaction(
-                transaction_bytes,
-                cluster,
-                self.connection_info().await.connected_account()?,
-            )
-            .await
+        track_request(
+            &self.wallet_events_sender,
+            WalletRequestKind::SignTransaction,
+            wallet.sign_transaction(transaction_bytes, cluster, account),
+        )
+        .await
     }
 
     /// Send a sign message request to the browser wallet
-    pub async fn sign_message<'a>(
-        &self,
-        message: &'a [u8],
-    ) -> WalletResult<SignedMessageOutput<'a>> {
-        let connection_info = self.connection_info();
+    pub async fn sign_message(&self, message: &[u8]) -> WalletResult<SignedMessageOutput> {
+        let connection_info = self.connection_info().await;
+        let wallet = connection_info.connected_wallet()?;
+        let account = connection_info.connected_account()?;
 
-        self.connection_info()
-            .await
-            .connected_wallet()?
-            .sign_message(message, connection_info.await.connected_account()?)
-            .await
+        track_request(
+            &self.wallet_events_sender,
+            WalletRequestKind::SignMessage,
+            wallet.sign_message(message, account),
+        )
+        .await
     }
 
     /// Check if an [account](WalletAccount) is connected
@@ -425,6 +522,17 @@ impl WalletAdapter {
         self.storage.borrow()
     }
 
+    /// Get the storage where the adapter stores the registration reports
+    pub fn diagnostics_storage(&self) -> &DiagnosticsStorage {
+        &self.diagnostics
+    }
+
+    /// Get a [report](WalletDiagnostics) of every wallet registration attempt,
+    /// including the attempts that failed to parse the wallet
+    pub fn diagnostics(&self) -> Vec<WalletDiagnostics> {
+        self.diagnostics.get_reports()
+    }
+
     /// Get the clusters supported by the connected wallet
     pub async fn clusters(&self) -> WalletResult<Vec<Cluster>> {
         let mut clusters = Vec::<Cluster>::default();
@@ -450,6 +558,71 @@ impl WalletAdapter {
         self.storage.borrow().get_wallets()
     }
 
+    /// List the registered wallets matching the `filter`. Preferred wallets set using
+    /// [WalletAdapter::set_preferred_wallets] are listed first followed by the other
+    /// wallets in the order they were registered. Wallets that have been connected
+    /// before, including in previous sessions, are marked as used.
+    pub fn list_wallets(&self, filter: &WalletFilter) -> Vec<WalletListing> {
+        self.storage.list_wallets(filter)
+    }
+
+    /// Set the names of the wallets to list first in [WalletAdapter::list_wallets]
+    pub fn set_preferred_wallets(&mut self, wallet_names: &[&str]) -> &mut Self {
+        self.storage.set_preferred_wallets(wallet_names);
+
+        self
+    }
+
+    /// Merge the `known_wallets` with the registered wallets to get the install state of
+    /// each wallet, useful for offering download links for the wallets that were not detected
+    pub fn detect_wallets(&self, known_wallets: &KnownWallets) -> Vec<WalletInstallState> {
+        known_wallets.detect(&self.storage)
+    }
+
+    /// Get the names of the wallets that have been connected before
+    pub fn used_wallets(&self) -> Vec<String> {
+        self.storage.used_wallets()
+    }
+
+    // The used wallets are persisted in `localStorage` as names separated by a new line
+    fn load_used_wallets(&self) {
+        match self.window.local_storage() {
+            Ok(Some(local_storage)) => {
+                if let Ok(Some(used_wallets)) = local_storage.get_item(USED_WALLETS_STORAGE_KEY) {
+                    self.storage.set_used_wallets(
+                        used_wallets
+                            .lines()
+                            .filter(|wallet_name| !wallet_name.is_empty())
+                            .map(|wallet_name| wallet_name.to_string())
+                            .collect(),
+                    );
+                }
+            }
+            _ => wallet_log!(
+                debug,
+                "`localStorage` is not available to load the used wallets"
+            ),
+        }
+    }
+
+    fn save_used_wallets(&self) {
+        let used_wallets = self.storage.used_wallets().join("\n");
+
+        let outcome = self
+            .window
+            .local_storage()
+            .ok()
+            .flatten()
+            .map(|local_storage| local_storage.set_item(USED_WALLETS_STORAGE_KEY, &used_wallets));
+
+        if !matches!(outcome, Some(Ok(()))) {
+            wallet_log!(
+                debug,
+                "Unable to persist the used wallets to `localStorage`"
+            );
+        }
+    }
+
     /// Get a certain wallet by its name
     pub fn get_wallet(&self, wallet_name: &str) -> WalletResult<Wallet> {
         self.storage
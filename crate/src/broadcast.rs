@@ -0,0 +1,503 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+use futures_core::Stream;
+
+use crate::{WalletError, WalletEvent, WalletResult};
+
+/// The default number of [WalletEvent]s buffered for each subscriber
+pub const DEFAULT_EVENT_CAPACITY: usize = 32;
+
+/// How a [WalletEventStream] handles a new [WalletEvent] when its buffer is full
+/// because the subscriber is not receiving events as fast as they are broadcast.
+/// Broadcasting never waits for a slow subscriber.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LagPolicy {
+    /// Drop the oldest buffered event to make room for the new event
+    #[default]
+    DropOldest,
+    /// Drop the oldest buffered event to make room for the new event and report
+    /// the number of dropped events as [WalletError::EventStreamLagged].
+    /// [WalletEventStream::recv] returns the error while the [Stream]
+    /// implementation yields it as a [WalletEvent::BackgroundTaskError]
+    Error,
+}
+
+/// Broadcasts every [WalletEvent] to all the subscribed [WalletEventStream]s.
+/// Cloning the broadcaster creates another handle to the same subscribers.
+/// The subscribed streams end when all the handles are dropped.
+#[derive(Debug, Clone)]
+pub struct WalletEventBroadcaster {
+    shared: Rc<RefCell<BroadcastShared>>,
+}
+
+impl WalletEventBroadcaster {
+    /// Create a broadcaster where subscribers buffer up to `capacity` events
+    /// and handle a full buffer using the [LagPolicy::DropOldest] policy
+    pub fn new(capacity: usize) -> Self {
+        Self::new_with_lag_policy(capacity, LagPolicy::default())
+    }
+
+    /// Create a broadcaster where subscribers buffer up to `capacity` events
+    /// and handle a full buffer using the `lag_policy`
+    pub fn new_with_lag_policy(capacity: usize, lag_policy: LagPolicy) -> Self {
+        Self {
+            shared: Rc::new(RefCell::new(BroadcastShared {
+                subscribers: Vec::default(),
+                capacity: capacity.max(1),
+                lag_policy,
+            })),
+        }
+    }
+
+    /// Subscribe to all the events broadcast from now on using the default
+    /// capacity and [LagPolicy] of the broadcaster
+    pub fn subscribe(&self) -> WalletEventStream {
+        let (capacity, lag_policy) = {
+            let shared = self.shared.borrow();
+            (shared.capacity, shared.lag_policy)
+        };
+
+        self.subscribe_with(capacity, lag_policy)
+    }
+
+    /// Subscribe to all the events broadcast from now on, buffering up to
+    /// `capacity` events and handling a full buffer using the `lag_policy`
+    pub fn subscribe_with(&self, capacity: usize, lag_policy: LagPolicy) -> WalletEventStream {
+        let queue = Rc::new(RefCell::new(SubscriberQueue::new(capacity, lag_policy)));
+
+        self.shared
+            .borrow_mut()
+            .subscribers
+            .push(Rc::downgrade(&queue));
+
+        WalletEventStream {
+            queue,
+            shared: Rc::downgrade(&self.shared),
+        }
+    }
+
+    /// Send the `event` to every subscriber without waiting for any of them.
+    /// Returns the number of subscribers the event was sent to
+    pub fn send(&self, event: WalletEvent) -> usize {
+        let subscribers = {
+            let mut shared = self.shared.borrow_mut();
+            shared
+                .subscribers
+                .retain(|subscriber| subscriber.strong_count() > 0);
+
+            shared
+                .subscribers
+                .iter()
+                .filter_map(Weak::upgrade)
+                .collect::<Vec<_>>()
+        };
+
+        subscribers.iter().for_each(|subscriber| {
+            let waker = subscriber.borrow_mut().push(event.clone());
+
+            // Wake after the borrow is released since the waker may poll the subscriber
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+
+        subscribers.len()
+    }
+
+    /// The number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.shared
+            .borrow()
+            .subscribers
+            .iter()
+            .filter(|subscriber| subscriber.strong_count() > 0)
+            .count()
+    }
+}
+
+impl Default for WalletEventBroadcaster {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+/// A subscription to the [WalletEvent]s of a [WalletEventBroadcaster].
+/// Every subscription receives every event broadcast after it subscribed.
+/// Cloning a subscription creates a new subscription with the same capacity
+/// and [LagPolicy] which only receives events broadcast after the clone.
+#[derive(Debug)]
+pub struct WalletEventStream {
+    queue: Rc<RefCell<SubscriberQueue>>,
+    shared: Weak<RefCell<BroadcastShared>>,
+}
+
+impl WalletEventStream {
+    /// Receive the next event. Returns [WalletError::EventStreamLagged] if events were
+    /// dropped under the [LagPolicy::Error] policy and [WalletError::EventStreamClosed]
+    /// when the broadcaster has been dropped and all buffered events have been received
+    pub async fn recv(&self) -> WalletResult<WalletEvent> {
+        std::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Only yield the events for which the `predicate` returns `true`
+    pub fn filter_events<F>(self, predicate: F) -> FilteredWalletEvents
+    where
+        F: Fn(&WalletEvent) -> bool + 'static,
+    {
+        FilteredWalletEvents {
+            stream: self,
+            predicate: Rc::new(predicate),
+        }
+    }
+
+    /// Only yield the events emitted when an account is connected,
+    /// reconnected, disconnected or changed
+    pub fn account_events(self) -> FilteredWalletEvents {
+        self.filter_events(|event| {
+            matches!(
+                event,
+                WalletEvent::Connected(_)
+                    | WalletEvent::Reconnected(_)
+                    | WalletEvent::Disconnected
+                    | WalletEvent::AccountChanged(_)
+            )
+        })
+    }
+
+    /// Only yield the lifecycle events of requests sent to the wallet,
+    /// [WalletEvent::RequestStarted], [WalletEvent::RequestApproved],
+    /// [WalletEvent::RequestRejected] and [WalletEvent::RequestFailed]
+    pub fn request_events(self) -> FilteredWalletEvents {
+        self.filter_events(|event| {
+            matches!(
+                event,
+                WalletEvent::RequestStarted { .. }
+                    | WalletEvent::RequestApproved { .. }
+                    | WalletEvent::RequestRejected { .. }
+                    | WalletEvent::RequestFailed { .. }
+            )
+        })
+    }
+
+    /// Only yield the [WalletEvent::BackgroundTaskError] events
+    pub fn errors(self) -> FilteredWalletEvents {
+        self.filter_events(|event| matches!(event, WalletEvent::BackgroundTaskError(_)))
+    }
+
+    /// The number of buffered events
+    pub fn len(&self) -> usize {
+        self.queue.borrow().events.len()
+    }
+
+    /// Whether there are no buffered events
+    pub fn is_empty(&self) -> bool {
+        self.queue.borrow().events.is_empty()
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<WalletResult<WalletEvent>> {
+        let mut queue = self.queue.borrow_mut();
+
+        if queue.lagged > 0 {
+            let lagged = core::mem::take(&mut queue.lagged);
+
+            return Poll::Ready(Err(WalletError::EventStreamLagged(lagged)));
+        }
+
+        if let Some(event) = queue.events.pop_front() {
+            return Poll::Ready(Ok(event));
+        }
+
+        if self.shared.strong_count() == 0 {
+            return Poll::Ready(Err(WalletError::EventStreamClosed));
+        }
+
+        queue.waker = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+impl Clone for WalletEventStream {
+    fn clone(&self) -> Self {
+        let (capacity, lag_policy) = {
+            let queue = self.queue.borrow();
+            (queue.capacity, queue.lag_policy)
+        };
+
+        match self.shared.upgrade() {
+            Some(shared) => WalletEventBroadcaster { shared }.subscribe_with(capacity, lag_policy),
+            None => Self {
+                queue: Rc::new(RefCell::new(SubscriberQueue::new(capacity, lag_policy))),
+                shared: Weak::new(),
+            },
+        }
+    }
+}
+
+impl Stream for WalletEventStream {
+    type Item = WalletEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv(cx).map(|outcome| match outcome {
+            Ok(event) => Some(event),
+            Err(WalletError::EventStreamClosed) => None,
+            Err(error) => Some(WalletEvent::BackgroundTaskError(error)),
+        })
+    }
+}
+
+/// A [WalletEventStream] that only yields the events matching a predicate
+#[derive(Clone)]
+pub struct FilteredWalletEvents {
+    stream: WalletEventStream,
+    predicate: Rc<dyn Fn(&WalletEvent) -> bool>,
+}
+
+impl FilteredWalletEvents {
+    /// Receive the next event matching the predicate.
+    /// Errors are returned the same way as [WalletEventStream::recv]
+    pub async fn recv(&self) -> WalletResult<WalletEvent> {
+        loop {
+            let event = self.stream.recv().await?;
+
+            if (self.predicate)(&event) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+impl Stream for FilteredWalletEvents {
+    type Item = WalletEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(event)) if !(this.predicate)(&event) => continue,
+                outcome => return outcome,
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for FilteredWalletEvents {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FilteredWalletEvents")
+            .field("stream", &self.stream)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct BroadcastShared {
+    subscribers: Vec<Weak<RefCell<SubscriberQueue>>>,
+    capacity: usize,
+    lag_policy: LagPolicy,
+}
+
+impl Drop for BroadcastShared {
+    // Wake the subscribers so that they observe that the broadcaster was dropped
+    fn drop(&mut self) {
+        self.subscribers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .for_each(|subscriber| {
+                let waker = subscriber.borrow_mut().waker.take();
+
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            });
+    }
+}
+
+#[derive(Debug)]
+struct SubscriberQueue {
+    events: VecDeque<WalletEvent>,
+    capacity: usize,
+    lag_policy: LagPolicy,
+    lagged: usize,
+    waker: Option<Waker>,
+}
+
+impl SubscriberQueue {
+    fn new(capacity: usize, lag_policy: LagPolicy) -> Self {
+        let capacity = capacity.max(1);
+
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+            lag_policy,
+            lagged: 0,
+            waker: Option::None,
+        }
+    }
+
+    /// Buffer the event and return the waker of a pending receiver
+    fn push(&mut self, event: WalletEvent) -> Option<Waker> {
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+
+            wallet_log!(
+                debug,
+                "Wallet event subscriber lagged, dropped the oldest buffered event"
+            );
+
+            if self.lag_policy == LagPolicy::Error {
+                self.lagged += 1;
+            }
+        }
+
+        self.events.push_back(event);
+
+        self.waker.take()
+    }
+}
+
+#[cfg(test)]
+mod broadcast_tests {
+    use std::{
+        future::Future,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::Wake,
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+        let waker = Waker::from(Arc::new(CountWakes::default()));
+        let mut future = std::pin::pin!(future);
+
+        future.as_mut().poll(&mut Context::from_waker(&waker))
+    }
+
+    fn error_event(message: &str) -> WalletEvent {
+        WalletEvent::BackgroundTaskError(WalletError::Op(message.to_string()))
+    }
+
+    #[test]
+    fn every_subscriber_receives_every_event() {
+        let broadcaster = WalletEventBroadcaster::default();
+        let first = broadcaster.subscribe();
+        let second = first.clone();
+
+        assert_eq!(broadcaster.subscriber_count(), 2);
+        assert_eq!(broadcaster.send(WalletEvent::Disconnected), 2);
+        assert_eq!(broadcaster.send(error_event("foo")), 2);
+
+        for subscriber in [&first, &second] {
+            assert_eq!(
+                poll_once(subscriber.recv()),
+                Poll::Ready(Ok(WalletEvent::Disconnected))
+            );
+            assert_eq!(
+                poll_once(subscriber.recv()),
+                Poll::Ready(Ok(error_event("foo")))
+            );
+            assert_eq!(poll_once(subscriber.recv()), Poll::Pending);
+        }
+
+        drop(second);
+        assert_eq!(broadcaster.send(WalletEvent::Skip), 1);
+
+        drop(broadcaster);
+        assert_eq!(poll_once(first.recv()), Poll::Ready(Ok(WalletEvent::Skip)));
+        assert_eq!(
+            poll_once(first.recv()),
+            Poll::Ready(Err(WalletError::EventStreamClosed))
+        );
+    }
+
+    #[test]
+    fn wakes_pending_subscriber() {
+        let broadcaster = WalletEventBroadcaster::default();
+        let subscriber = broadcaster.subscribe();
+
+        let wakes = Arc::new(CountWakes::default());
+        let waker = Waker::from(wakes.clone());
+        let mut recv = std::pin::pin!(subscriber.recv());
+
+        assert!(recv
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending());
+        broadcaster.send(WalletEvent::Disconnected);
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            recv.as_mut().poll(&mut Context::from_waker(&waker)),
+            Poll::Ready(Ok(WalletEvent::Disconnected))
+        );
+    }
+
+    #[test]
+    fn lag_policies() {
+        let broadcaster = WalletEventBroadcaster::new(2);
+        let drop_oldest = broadcaster.subscribe();
+        let error = broadcaster.subscribe_with(2, LagPolicy::Error);
+
+        for message in ["1", "2", "3", "4"] {
+            broadcaster.send(error_event(message));
+        }
+
+        assert_eq!(drop_oldest.len(), 2);
+        assert_eq!(
+            poll_once(drop_oldest.recv()),
+            Poll::Ready(Ok(error_event("3")))
+        );
+
+        assert_eq!(
+            poll_once(error.recv()),
+            Poll::Ready(Err(WalletError::EventStreamLagged(2)))
+        );
+        assert_eq!(poll_once(error.recv()), Poll::Ready(Ok(error_event("3"))));
+        assert_eq!(poll_once(error.recv()), Poll::Ready(Ok(error_event("4"))));
+    }
+
+    #[test]
+    fn filters() {
+        let broadcaster = WalletEventBroadcaster::default();
+        let account_events = broadcaster.subscribe().account_events();
+        let mut errors = broadcaster.subscribe().errors();
+
+        broadcaster.send(WalletEvent::Skip);
+        broadcaster.send(error_event("foo"));
+        broadcaster.send(WalletEvent::Disconnected);
+
+        assert_eq!(
+            poll_once(account_events.recv()),
+            Poll::Ready(Ok(WalletEvent::Disconnected))
+        );
+        assert_eq!(poll_once(account_events.recv()), Poll::Pending);
+
+        let waker = Waker::from(Arc::new(CountWakes::default()));
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(
+            Pin::new(&mut errors).poll_next(&mut cx),
+            Poll::Ready(Some(error_event("foo")))
+        );
+        assert_eq!(Pin::new(&mut errors).poll_next(&mut cx), Poll::Pending);
+
+        drop(broadcaster);
+        assert_eq!(Pin::new(&mut errors).poll_next(&mut cx), Poll::Ready(None));
+    }
+}
@@ -0,0 +1,305 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{prelude::BASE64_STANDARD_NO_PAD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+};
+
+use crate::{Utils, WalletError, WalletResult};
+
+const KEYSTORE_VERSION: &str = "burner1";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEYPAIR_LENGTH: usize = 64;
+
+/// The Argon2id cost parameters used to derive the encryption key from the password
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BurnerKdfParams {
+    /// The memory cost in KiB
+    pub memory_cost: u32,
+    /// The number of iterations
+    pub time_cost: u32,
+    /// The degree of parallelism
+    pub parallelism: u32,
+}
+
+impl Default for BurnerKdfParams {
+    /// The parameters recommended by OWASP, 19 MiB of memory, 2 iterations and 1 lane
+    fn default() -> Self {
+        Self {
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// The keypair of the burner wallet encrypted with a password.
+/// The encryption key is derived from the password with Argon2id
+/// and the keypair is encrypted with ChaCha20-Poly1305.
+/// It is stored as the text `burner1$<m>,<t>,<p>$<salt>$<nonce>$<ciphertext>`
+/// where the salt, nonce and ciphertext are Base64 encoded.
+#[derive(PartialEq, Eq, Clone)]
+pub struct BurnerKeystore {
+    params: BurnerKdfParams,
+    salt: [u8; SALT_LENGTH],
+    nonce: [u8; NONCE_LENGTH],
+    ciphertext: Vec<u8>,
+}
+
+impl BurnerKeystore {
+    /// Encrypt the 64 bytes of the secret key followed by the public key with the `password`
+    pub fn encrypt(keypair_bytes: &[u8; 64], password: &str) -> WalletResult<Self> {
+        Self::encrypt_with_params(keypair_bytes, password, BurnerKdfParams::default())
+    }
+
+    /// Same as [Self::encrypt] with custom Argon2id cost parameters
+    pub fn encrypt_with_params(
+        keypair_bytes: &[u8; 64],
+        password: &str,
+        params: BurnerKdfParams,
+    ) -> WalletResult<Self> {
+        let random = Utils::rand_32bytes();
+
+        let mut keystore = Self {
+            params,
+            salt: [0u8; SALT_LENGTH],
+            nonce: [0u8; NONCE_LENGTH],
+            ciphertext: Vec::default(),
+        };
+        keystore.salt.copy_from_slice(&random[..SALT_LENGTH]);
+        keystore
+            .nonce
+            .copy_from_slice(&random[SALT_LENGTH..SALT_LENGTH + NONCE_LENGTH]);
+
+        keystore.ciphertext = keystore
+            .cipher(password)?
+            .encrypt(
+                Nonce::from_slice(&keystore.nonce),
+                Payload {
+                    msg: keypair_bytes,
+                    aad: KEYSTORE_VERSION.as_bytes(),
+                },
+            )
+            .or(Err(WalletError::InvalidBurnerKeystore(
+                "Unable to encrypt the keypair".to_string(),
+            )))?;
+
+        Ok(keystore)
+    }
+
+    /// Decrypt the keypair with the `password`, returning the 64 bytes
+    /// of the secret key followed by the public key
+    pub fn decrypt(&self, password: &str) -> WalletResult<[u8; 64]> {
+        let mut plaintext = self
+            .cipher(password)?
+            .decrypt(
+                Nonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: KEYSTORE_VERSION.as_bytes(),
+                },
+            )
+            .or(Err(WalletError::BurnerDecryptionFailed))?;
+
+        let keypair_bytes = Utils::to64byte_array(&plaintext);
+        plaintext.fill(0);
+
+        keypair_bytes
+    }
+
+    /// Get the Argon2id cost parameters
+    pub fn params(&self) -> BurnerKdfParams {
+        self.params
+    }
+
+    /// Parse a keystore from its text encoding
+    pub fn parse(keystore: &str) -> WalletResult<Self> {
+        let invalid = |reason: &str| WalletError::InvalidBurnerKeystore(reason.to_string());
+        let decode = |value: &str| {
+            BASE64_STANDARD_NO_PAD
+                .decode(value)
+                .or(Err(invalid("Invalid Base64")))
+        };
+
+        let parts = keystore.trim().split('$').collect::<Vec<&str>>();
+        let [version, params, salt, nonce, ciphertext] = parts.as_slice() else {
+            return Err(invalid("Expected 5 parts separated by `$`"));
+        };
+
+        if *version != KEYSTORE_VERSION {
+            return Err(invalid("Unsupported keystore version"));
+        }
+
+        let params = params
+            .split(',')
+            .map(|param| param.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .or(Err(invalid("Invalid Argon2id parameters")))?;
+        let [memory_cost, time_cost, parallelism] = params.as_slice() else {
+            return Err(invalid("Expected 3 Argon2id parameters"));
+        };
+
+        let ciphertext = decode(ciphertext)?;
+        // The ciphertext is the keypair followed by the 16 byte Poly1305 tag
+        if ciphertext.len() != KEYPAIR_LENGTH + 16 {
+            return Err(invalid("The ciphertext has an invalid length"));
+        }
+
+        Ok(Self {
+            params: BurnerKdfParams {
+                memory_cost: *memory_cost,
+                time_cost: *time_cost,
+                parallelism: *parallelism,
+            },
+            salt: decode(salt)?
+                .try_into()
+                .or(Err(invalid("The salt must be 16 bytes")))?,
+            nonce: decode(nonce)?
+                .try_into()
+                .or(Err(invalid("The nonce must be 12 bytes")))?,
+            ciphertext,
+        })
+    }
+
+    fn cipher(&self, password: &str) -> WalletResult<ChaCha20Poly1305> {
+        if password.is_empty() {
+            return Err(WalletError::BurnerPasswordRequired);
+        }
+
+        let params = Params::new(
+            self.params.memory_cost,
+            self.params.time_cost,
+            self.params.parallelism,
+            Some(32),
+        )
+        .map_err(|error| WalletError::InvalidBurnerKeystore(error.to_string()))?;
+
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &self.salt, &mut key)
+            .map_err(|error| WalletError::InvalidBurnerKeystore(error.to_string()))?;
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        key.fill(0);
+
+        Ok(cipher)
+    }
+}
+
+impl core::fmt::Display for BurnerKeystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{KEYSTORE_VERSION}${},{},{}${}${}${}",
+            self.params.memory_cost,
+            self.params.time_cost,
+            self.params.parallelism,
+            BASE64_STANDARD_NO_PAD.encode(self.salt),
+            BASE64_STANDARD_NO_PAD.encode(self.nonce),
+            BASE64_STANDARD_NO_PAD.encode(&self.ciphertext),
+        )
+    }
+}
+
+impl core::fmt::Debug for BurnerKeystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BurnerKeystore")
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod burner_keystore_tests {
+    use super::*;
+    use crate::KeypairSigner;
+
+    // Cheap parameters so the tests do not spend seconds hashing
+    const TEST_PARAMS: BurnerKdfParams = BurnerKdfParams {
+        memory_cost: 64,
+        time_cost: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn round_trip() {
+        let keypair_bytes = KeypairSigner::generate().to_bytes();
+
+        let keystore =
+            BurnerKeystore::encrypt_with_params(&keypair_bytes, "correct horse", TEST_PARAMS)
+                .unwrap();
+        assert_eq!(keystore.decrypt("correct horse").unwrap(), keypair_bytes);
+
+        let encoded = keystore.to_string();
+        assert!(encoded.starts_with("burner1$64,1,1$"));
+        let parsed = BurnerKeystore::parse(&encoded).unwrap();
+        assert_eq!(parsed, keystore);
+        assert_eq!(parsed.params(), TEST_PARAMS);
+        assert_eq!(parsed.decrypt("correct horse").unwrap(), keypair_bytes);
+
+        // A new salt and nonce are used for each encryption
+        assert_ne!(
+            BurnerKeystore::encrypt_with_params(&keypair_bytes, "correct horse", TEST_PARAMS)
+                .unwrap()
+                .to_string(),
+            encoded
+        );
+    }
+
+    #[test]
+    fn reject_wrong_password_and_tampering() {
+        let keypair_bytes = KeypairSigner::generate().to_bytes();
+        let keystore =
+            BurnerKeystore::encrypt_with_params(&keypair_bytes, "correct horse", TEST_PARAMS)
+                .unwrap();
+
+        assert_eq!(
+            keystore.decrypt("battery staple").unwrap_err(),
+            WalletError::BurnerDecryptionFailed
+        );
+        assert_eq!(
+            keystore.decrypt("").unwrap_err(),
+            WalletError::BurnerPasswordRequired
+        );
+
+        let mut tampered = keystore.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert_eq!(
+            tampered.decrypt("correct horse").unwrap_err(),
+            WalletError::BurnerDecryptionFailed
+        );
+
+        let mut weaker = keystore.clone();
+        weaker.params.time_cost = 2;
+        assert_eq!(
+            weaker.decrypt("correct horse").unwrap_err(),
+            WalletError::BurnerDecryptionFailed
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let encoded = BurnerKeystore::encrypt_with_params(
+            &KeypairSigner::generate().to_bytes(),
+            "password",
+            TEST_PARAMS,
+        )
+        .unwrap()
+        .to_string();
+
+        let invalid = |keystore: &str| {
+            matches!(
+                BurnerKeystore::parse(keystore),
+                Err(WalletError::InvalidBurnerKeystore(_))
+            )
+        };
+
+        assert!(invalid(""));
+        assert!(invalid(&encoded.replace("burner1", "burner2")));
+        assert!(invalid(&encoded.replace("$64,1,1$", "$64,1$")));
+        assert!(invalid(&encoded.replace("$64,1,1$", "$64,one,1$")));
+        assert!(invalid(&(encoded.clone() + "$extra")));
+        assert!(invalid(&(encoded.clone() + "AAAA")));
+    }
+}
@@ -0,0 +1,5 @@
+mod keystore;
+pub use keystore::*;
+
+mod wallet;
+pub use wallet::*;
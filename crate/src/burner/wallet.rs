@@ -0,0 +1,254 @@
+use web_sys::{Storage, Window};
+
+use crate::{
+    BurnerKeystore, Cluster, KeypairSigner, Reflection, StandardWalletProvider,
+    StandardWalletSigner, WalletError, WalletIcon, WalletResult, BURNER_WALLET_STORAGE_KEY,
+    STANDARD_WALLET_VERSION,
+};
+
+/// The name the burner wallet registers with
+pub const BURNER_WALLET_NAME: &str = "Burner Wallet";
+/// The non-standard feature the burner wallet registers with so that apps can
+/// detect it, using [crate::Wallet::feature_identifiers], and display [BURNER_WALLET_WARNING]
+pub const BURNER_WALLET_FEATURE: &str = "wallet-adapter:burner";
+/// The warning shown to the user of a burner wallet
+pub const BURNER_WALLET_WARNING: &str = "The burner wallet keeps its keypair in the storage of the browser. Only use it for demos and onboarding and never hold funds you cannot afford to lose.";
+/// The icon of the burner wallet
+pub const BURNER_WALLET_ICON: &str = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciIHZpZXdCb3g9IjAgMCAzMiAzMiI+PHJlY3Qgd2lkdGg9IjMyIiBoZWlnaHQ9IjMyIiByeD0iOCIgZmlsbD0iIzFjMWMxYyIvPjxwYXRoIGQ9Ik0xNiA0YzEgNSA3IDggNyAxNWE3IDcgMCAwIDEtMTQgMGMwLTMgMi02IDQtNyAwIDIgMSA0IDMgNS0xLTUgMC05IDAtMTN6IiBmaWxsPSIjZmY2YTAwIi8+PC9zdmc+";
+
+/// When the burner wallet logs [BURNER_WALLET_WARNING] at the `warn` level.
+/// There is no silent option, the app must explicitly choose how it is warned.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum BurnerWarning {
+    /// Log the warning when the wallet is registered
+    OnRegister,
+    /// Log the warning when the wallet is registered and each time it signs
+    OnEverySignature,
+}
+
+/// The signer of the burner wallet, logging [BURNER_WALLET_WARNING] on every signature
+/// if [BurnerWarning::OnEverySignature] was chosen
+struct BurnerSigner {
+    signer: KeypairSigner,
+    warning: BurnerWarning,
+}
+
+impl BurnerSigner {
+    fn warn_on_signature(&self) {
+        if self.warning == BurnerWarning::OnEverySignature {
+            wallet_log!(
+                warn,
+                "`{}` burner wallet is signing a request. {BURNER_WALLET_WARNING}",
+                self.signer.address()
+            );
+        }
+    }
+}
+
+impl StandardWalletSigner for BurnerSigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.signer.public_key()
+    }
+
+    /// The burner wallet connects without prompting the user
+    async fn approve_connection(&self) -> WalletResult<()> {
+        Ok(())
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<[u8; 64]> {
+        self.warn_on_signature();
+
+        StandardWalletSigner::sign_message(&self.signer, message).await
+    }
+
+    async fn sign_transaction(&self, transaction_bytes: &[u8]) -> WalletResult<Vec<u8>> {
+        self.warn_on_signature();
+
+        StandardWalletSigner::sign_transaction(&self.signer, transaction_bytes).await
+    }
+}
+
+/// A wallet for demos and onboarding that keeps an Ed25519 keypair in `localStorage`,
+/// encrypted with a password as a [BurnerKeystore]. Once [registered](Self::register)
+/// it is a wallet standard wallet like a browser extension, so it is listed by
+/// [crate::WalletAdapter::wallets] and used through the [crate::WalletAdapter].
+/// The wallet connects without prompting the user and signs every request.
+#[derive(Clone)]
+pub struct BurnerWallet {
+    provider: StandardWalletProvider<BurnerSigner>,
+}
+
+impl BurnerWallet {
+    /// Generate a new keypair, encrypt it with the `password` and store it,
+    /// replacing any burner wallet stored before
+    pub fn create(password: &str, warning: BurnerWarning) -> WalletResult<Self> {
+        Self::store(KeypairSigner::generate(), password, warning)
+    }
+
+    /// Import a keypair exported by [Self::export] or by `solana-keygen`,
+    /// encrypt it with the `password` and store it, replacing any burner wallet stored before
+    pub fn import(
+        keypair_json: &str,
+        password: &str,
+        warning: BurnerWarning,
+    ) -> WalletResult<Self> {
+        Self::store(KeypairSigner::from_json(keypair_json)?, password, warning)
+    }
+
+    /// Decrypt the stored burner wallet with the `password`
+    pub fn unlock(password: &str, warning: BurnerWarning) -> WalletResult<Self> {
+        let keypair_bytes = Self::stored_keystore()?.decrypt(password)?;
+
+        Self::new(KeypairSigner::from_bytes(&keypair_bytes)?, warning)
+    }
+
+    /// Check whether a burner wallet is stored in the browser
+    pub fn exists() -> WalletResult<bool> {
+        Ok(Self::local_storage()?
+            .get_item(BURNER_WALLET_STORAGE_KEY)?
+            .is_some())
+    }
+
+    /// Export the keypair as a `solana-keygen` JSON array of 64 bytes.
+    /// The `password` must decrypt the stored burner wallet.
+    pub fn export(&self, password: &str) -> WalletResult<String> {
+        let keypair_bytes = Self::stored_keystore()?.decrypt(password)?;
+
+        if keypair_bytes != self.provider.signer().signer.to_bytes() {
+            return Err(WalletError::BurnerDecryptionFailed);
+        }
+
+        Ok(keypair_json(&keypair_bytes))
+    }
+
+    /// Delete the stored burner wallet
+    pub fn forget(self) -> WalletResult<()> {
+        Ok(Self::local_storage()?.remove_item(BURNER_WALLET_STORAGE_KEY)?)
+    }
+
+    /// Set the cluster of the account, [Cluster::DevNet] by default.
+    /// The connected apps are notified of the change.
+    pub fn set_cluster(&mut self, cluster: Cluster) -> &mut Self {
+        self.provider.set_chains(&[cluster]);
+
+        self
+    }
+
+    /// Get the Base58 address of the account
+    pub fn address(&self) -> String {
+        self.provider.address()
+    }
+
+    /// Get the public key of the account
+    pub fn public_key(&self) -> [u8; 32] {
+        self.provider.signer().public_key()
+    }
+
+    /// Get when the wallet logs [BURNER_WALLET_WARNING]
+    pub fn warning(&self) -> BurnerWarning {
+        self.provider.signer().warning
+    }
+
+    /// Check whether an app connected to the wallet
+    pub fn is_connected(&self) -> bool {
+        self.provider.is_connected()
+    }
+
+    /// Register the wallet with the apps on the page using the wallet standard
+    /// `wallet-standard:register-wallet` event, and with the apps that are initialized
+    /// later using the `wallet-standard:app-ready` event
+    pub fn register(&self, window: &Window) -> WalletResult<()> {
+        wallet_log!(
+            warn,
+            "`{}` burner wallet registered. {BURNER_WALLET_WARNING}",
+            self.address()
+        );
+
+        self.provider.register(window)
+    }
+
+    fn new(signer: KeypairSigner, warning: BurnerWarning) -> WalletResult<Self> {
+        let mut provider = StandardWalletProvider::new(
+            BURNER_WALLET_NAME,
+            WalletIcon::new(BURNER_WALLET_ICON)?,
+            BurnerSigner { signer, warning },
+        );
+
+        let mut burner_feature = Reflection::new_object();
+        burner_feature.set_object_str("version", STANDARD_WALLET_VERSION)?;
+        burner_feature.set_object_str("warning", BURNER_WALLET_WARNING)?;
+        provider.add_extension(BURNER_WALLET_FEATURE, burner_feature.take());
+
+        Ok(Self { provider })
+    }
+
+    fn store(signer: KeypairSigner, password: &str, warning: BurnerWarning) -> WalletResult<Self> {
+        let keystore = BurnerKeystore::encrypt(&signer.to_bytes(), password)?;
+        Self::local_storage()?.set_item(BURNER_WALLET_STORAGE_KEY, &keystore.to_string())?;
+
+        Self::new(signer, warning)
+    }
+
+    fn stored_keystore() -> WalletResult<BurnerKeystore> {
+        let keystore = Self::local_storage()?
+            .get_item(BURNER_WALLET_STORAGE_KEY)?
+            .ok_or(WalletError::BurnerWalletNotFound)?;
+
+        BurnerKeystore::parse(&keystore)
+    }
+
+    fn local_storage() -> WalletResult<Storage> {
+        web_sys::window()
+            .ok_or(WalletError::MissingAccessToBrowserWindow)?
+            .local_storage()?
+            .ok_or(WalletError::ValueNotFound)
+    }
+}
+
+impl core::fmt::Debug for BurnerWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BurnerWallet")
+            .field("signer", &self.provider.signer().signer)
+            .field("warning", &self.warning())
+            .field("chains", &self.provider.chains())
+            .field("connected", &self.is_connected())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encode the keypair as a `solana-keygen` JSON array
+fn keypair_json(keypair_bytes: &[u8; 64]) -> String {
+    format!(
+        "[{}]",
+        keypair_bytes
+            .iter()
+            .map(|byte| byte.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    )
+}
+
+#[cfg(test)]
+mod burner_wallet_tests {
+    use super::*;
+    use crate::WalletIcon;
+
+    #[test]
+    fn icon_is_valid() {
+        let icon = WalletIcon::new(BURNER_WALLET_ICON).unwrap();
+
+        assert_eq!(icon.sanitize().unwrap(), icon);
+    }
+
+    #[test]
+    fn export_format() {
+        let signer = KeypairSigner::generate();
+        let exported = keypair_json(&signer.to_bytes());
+
+        assert!(exported.starts_with('[') && !exported.contains(' '));
+        assert_eq!(
+            KeypairSigner::from_json(&exported).unwrap().public_key(),
+            signer.public_key()
+        );
+    }
+}
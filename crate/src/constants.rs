@@ -2,6 +2,16 @@
 pub const WINDOW_APP_READY_EVENT_TYPE: &str = "wallet-standard:app-ready";
 /// Standard Register Wallet Event Identifier
 pub const WINDOW_REGISTER_WALLET_EVENT_TYPE: &str = "wallet-standard:register-wallet";
+/// The `localStorage` key where the names of the wallets that have been used before are persisted
+pub const USED_WALLETS_STORAGE_KEY: &str = "wallet-adapter:used-wallets";
+/// The key where the session of a deeplink wallet is persisted
+pub const DEEPLINK_SESSION_STORAGE_KEY: &str = "wallet-adapter:deeplink-session";
+/// The `localStorage` key where the encrypted keystore of the burner wallet is persisted
+pub const BURNER_WALLET_STORAGE_KEY: &str = "wallet-adapter:burner-wallet";
+
+/// The [SemVer requirement](crate::SemverRequirement) the versions of the `standard` and `solana`
+/// features of a wallet must match. Features with other versions are not used
+pub const SUPPORTED_FEATURE_VERSIONS: &str = "^1";
 
 /// Identifier for `standard:events`
 pub const STANDARD_EVENTS_IDENTIFIER: &str = "standard:events";
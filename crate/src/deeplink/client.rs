@@ -0,0 +1,635 @@
+use ed25519_dalek::Signature;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    decode_32bytes, decode_base58, Cluster, DeeplinkRequest, DeeplinkSession, DeeplinkStorage,
+    SignedMessageOutput, Utils, WalletAccount, WalletError, WalletResult,
+    DEEPLINK_SESSION_STORAGE_KEY, SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+    SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+    STANDARD_CONNECT_IDENTIFIER, STANDARD_DISCONNECT_IDENTIFIER,
+};
+
+/// A wallet that supports the Phantom-style deeplink protocol
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DeeplinkWallet {
+    name: String,
+    base_url: String,
+    encryption_public_key_param: String,
+}
+
+impl DeeplinkWallet {
+    /// Instantiate a wallet from its name and the base URL of its deeplinks,
+    /// example `https://phantom.app/ul/v1/`. The wallet is expected to redirect
+    /// with its encryption public key in the `<lowercase name>_encryption_public_key`
+    /// query parameter
+    pub fn new(name: &str, base_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string() + "/",
+            encryption_public_key_param: name.to_lowercase() + "_encryption_public_key",
+        }
+    }
+
+    /// The Phantom wallet
+    pub fn phantom() -> Self {
+        Self::new("Phantom", "https://phantom.app/ul/v1/")
+    }
+
+    /// The Solflare wallet
+    pub fn solflare() -> Self {
+        Self::new("Solflare", "https://solflare.com/ul/v1/")
+    }
+
+    /// Get the name of the wallet
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Get the base URL of the deeplinks of the wallet
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+}
+
+/// The outcome of a request decrypted from the redirect of the wallet
+#[derive(Debug)]
+pub enum DeeplinkResponse {
+    /// The wallet approved the connection
+    Connected(Box<WalletAccount>),
+    /// The wallet disconnected
+    Disconnected,
+    /// The serialized transaction signed by the wallet
+    SignedTransaction(Vec<u8>),
+    /// The signature of the transaction signed and sent by the wallet
+    SentTransaction(Signature),
+    /// The message signed by the wallet, with a verified signature
+    SignedMessage(SignedMessageOutput),
+}
+
+#[derive(Deserialize)]
+struct ConnectData {
+    public_key: String,
+    session: String,
+}
+
+#[derive(Deserialize)]
+struct TransactionData {
+    transaction: String,
+}
+
+#[derive(Deserialize)]
+struct SignatureData {
+    signature: String,
+}
+
+/// A client for wallets that support the Phantom-style deeplink protocol, used by
+/// mobile browsers without an injected wallet. The app opens the URLs built by the
+/// client, the wallet handles the request and redirects to the `redirect_link`
+/// with the encrypted outcome which is decrypted by [Self::handle_redirect].
+/// The payloads are encrypted using NaCl box with a key derived from an x25519 key exchange.
+///
+/// #### Example
+/// ```rust,no_run
+/// # fn example() -> wallet_adapter::WalletResult<()> {
+/// use wallet_adapter::{BrowserDeeplinkStorage, DeeplinkClient, DeeplinkResponse, DeeplinkWallet};
+///
+/// let client = DeeplinkClient::new(
+///     DeeplinkWallet::phantom(),
+///     "https://example.com",
+///     "https://example.com/wallet-redirect",
+///     BrowserDeeplinkStorage::local()?,
+/// );
+///
+/// // Open this URL to request the connection
+/// let connect_url = client.connect_url()?;
+///
+/// // When the wallet redirects back to the app
+/// # let redirect_url = String::new();
+/// if let DeeplinkResponse::Connected(account) = client.handle_redirect(&redirect_url)? {
+///     let address = account.address();
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DeeplinkClient<S: DeeplinkStorage> {
+    wallet: DeeplinkWallet,
+    app_url: String,
+    redirect_link: String,
+    cluster: Cluster,
+    storage: S,
+}
+
+impl<S: DeeplinkStorage> DeeplinkClient<S> {
+    /// Instantiate the client with the URL of the app, used by the wallet to fetch
+    /// the app metadata, the `redirect_link` the wallet redirects to after handling
+    /// a request and the `storage` where the session is persisted
+    pub fn new(wallet: DeeplinkWallet, app_url: &str, redirect_link: &str, storage: S) -> Self {
+        Self {
+            wallet,
+            app_url: app_url.to_string(),
+            redirect_link: redirect_link.to_string(),
+            cluster: Cluster::default(),
+            storage,
+        }
+    }
+
+    /// Set the cluster requested when connecting. Defaults to [Cluster::DevNet]
+    pub fn set_cluster(&mut self, cluster: Cluster) -> &mut Self {
+        self.cluster = cluster;
+
+        self
+    }
+
+    /// Start a new session and build the URL requesting the connection
+    pub fn connect_url(&self) -> WalletResult<String> {
+        let cluster = match self.cluster {
+            Cluster::MainNet => "mainnet-beta",
+            Cluster::DevNet => "devnet",
+            Cluster::TestNet => "testnet",
+            Cluster::LocalNet => {
+                return Err(WalletError::UnsupportedChain(
+                    self.cluster.chain().to_string(),
+                ))
+            }
+        };
+
+        let mut session = DeeplinkSession::new(self.cluster);
+        session.set_pending(DeeplinkRequest::Connect);
+        self.save(&session)?;
+
+        Ok(self.url(
+            "connect",
+            &[
+                ("app_url", &self.app_url),
+                ("dapp_encryption_public_key", &session.app_public_key()?),
+                ("redirect_link", &self.redirect_link),
+                ("cluster", cluster),
+            ],
+        ))
+    }
+
+    /// Build the URL requesting the wallet to disconnect
+    pub fn disconnect_url(&self) -> WalletResult<String> {
+        self.request_url(DeeplinkRequest::Disconnect, json!({}))
+    }
+
+    /// Build the URL requesting the wallet to sign a serialized transaction
+    pub fn sign_transaction_url(&self, transaction_bytes: &[u8]) -> WalletResult<String> {
+        let transaction = bs58::encode(transaction_bytes).into_string();
+
+        self.request_url(
+            DeeplinkRequest::SignTransaction,
+            json!({ "transaction": transaction }),
+        )
+    }
+
+    /// Build the URL requesting the wallet to sign and send a serialized transaction
+    pub fn sign_and_send_transaction_url(&self, transaction_bytes: &[u8]) -> WalletResult<String> {
+        let transaction = bs58::encode(transaction_bytes).into_string();
+
+        self.request_url(
+            DeeplinkRequest::SignAndSendTransaction,
+            json!({ "transaction": transaction }),
+        )
+    }
+
+    /// Build the URL requesting the wallet to sign a message. Messages that are
+    /// not UTF-8 encoded are displayed by the wallet as hex
+    pub fn sign_message_url(&self, message: &[u8]) -> WalletResult<String> {
+        let display = if core::str::from_utf8(message).is_ok() {
+            "utf8"
+        } else {
+            "hex"
+        };
+
+        let message = bs58::encode(message).into_string();
+
+        self.request_url(
+            DeeplinkRequest::SignMessage {
+                message: message.clone(),
+            },
+            json!({ "message": message, "display": display }),
+        )
+    }
+
+    /// Decrypt the redirect of the wallet for the pending request. The `redirect_url`
+    /// can be the full URL or its query. An error returned by the wallet,
+    /// example the user rejecting the request, is returned as
+    /// [WalletError::WalletRequestError].
+    /// The pending request is only cleared once the wallet returns an error or a
+    /// response that is decrypted and verified, so an invalid redirect can not
+    /// cancel the request
+    pub fn handle_redirect(&self, redirect_url: &str) -> WalletResult<DeeplinkResponse> {
+        let mut session = self.session()?.ok_or(WalletError::DeeplinkNotConnected)?;
+        let pending = session
+            .pending()
+            .cloned()
+            .ok_or(WalletError::DeeplinkNoPendingRequest)?;
+
+        let query = Utils::parse_query(redirect_url)?;
+        let param = |key: &str| {
+            query
+                .iter()
+                .find_map(|(name, value)| (name == key).then_some(value.as_str()))
+        };
+
+        if let Some(code) = param("errorCode") {
+            session.take_pending();
+            self.save(&session)?;
+
+            return Err(WalletError::WalletRequestError {
+                code: code.parse().unwrap_or_default(),
+                message: param("errorMessage").unwrap_or_default().to_string(),
+            });
+        }
+
+        if pending == DeeplinkRequest::Disconnect {
+            self.clear()?;
+
+            return Ok(DeeplinkResponse::Disconnected);
+        }
+
+        let missing = |key: &str| WalletError::ExpectedValueNotFound(key.to_string());
+        let nonce = param("nonce").ok_or(missing("nonce"))?;
+        let data = param("data").ok_or(missing("data"))?;
+
+        let response = match pending {
+            DeeplinkRequest::Connect => {
+                let wallet_public_key = param(&self.wallet.encryption_public_key_param)
+                    .ok_or(missing(&self.wallet.encryption_public_key_param))?;
+
+                let connected = parse_data::<ConnectData>(&session.decrypt(
+                    data,
+                    nonce,
+                    Some(wallet_public_key),
+                )?)?;
+                decode_32bytes(&connected.public_key)?;
+
+                session.set_connected(wallet_public_key, &connected.public_key, connected.session);
+
+                DeeplinkResponse::Connected(Box::new(self.account(&session)?))
+            }
+            DeeplinkRequest::SignTransaction => {
+                let signed = parse_data::<TransactionData>(&session.decrypt(data, nonce, None)?)?;
+
+                DeeplinkResponse::SignedTransaction(decode_base58(&signed.transaction)?)
+            }
+            DeeplinkRequest::SignAndSendTransaction => {
+                let sent = parse_data::<SignatureData>(&session.decrypt(data, nonce, None)?)?;
+
+                DeeplinkResponse::SentTransaction(Utils::signature(Utils::to64byte_array(
+                    &decode_base58(&sent.signature)?,
+                )?))
+            }
+            DeeplinkRequest::SignMessage { message } => {
+                let signed = parse_data::<SignatureData>(&session.decrypt(data, nonce, None)?)?;
+
+                let message = decode_base58(&message)?;
+                let public_key = session.account()?.ok_or(WalletError::AccountNotFound)?;
+                let signature = Utils::to64byte_array(&decode_base58(&signed.signature)?)?;
+
+                Utils::verify_signature(
+                    Utils::public_key(public_key)?,
+                    &message,
+                    Utils::signature(signature),
+                )?;
+
+                DeeplinkResponse::SignedMessage(SignedMessageOutput::new(
+                    &message, public_key, signature,
+                ))
+            }
+            DeeplinkRequest::Disconnect => DeeplinkResponse::Disconnected,
+        };
+
+        session.take_pending();
+        self.save(&session)?;
+
+        Ok(response)
+    }
+
+    /// Get the connected account from the persisted session
+    pub fn connected_account(&self) -> WalletResult<Option<WalletAccount>> {
+        self.session()?
+            .filter(|session| session.is_connected())
+            .map(|session| self.account(&session))
+            .transpose()
+    }
+
+    /// Get the persisted session
+    pub fn session(&self) -> WalletResult<Option<DeeplinkSession>> {
+        self.storage
+            .get(DEEPLINK_SESSION_STORAGE_KEY)?
+            .map(|session| {
+                serde_json::from_str(&session)
+                    .map_err(|error| WalletError::InvalidDeeplinkSession(error.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Remove the persisted session
+    pub fn clear(&self) -> WalletResult<()> {
+        self.storage.remove(DEEPLINK_SESSION_STORAGE_KEY)
+    }
+
+    /// Get the wallet
+    pub fn wallet(&self) -> &DeeplinkWallet {
+        &self.wallet
+    }
+
+    /// Get the storage where the session is persisted
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    fn request_url(
+        &self,
+        request: DeeplinkRequest,
+        mut payload: serde_json::Value,
+    ) -> WalletResult<String> {
+        let mut session = self
+            .session()?
+            .filter(|session| session.is_connected())
+            .ok_or(WalletError::DeeplinkNotConnected)?;
+
+        payload["session"] = json!(session.session());
+
+        let (nonce, payload) = session.encrypt(payload.to_string().as_bytes())?;
+
+        let method = request.method().to_string();
+        session.set_pending(request);
+        self.save(&session)?;
+
+        Ok(self.url(
+            &method,
+            &[
+                ("dapp_encryption_public_key", &session.app_public_key()?),
+                ("nonce", &nonce),
+                ("redirect_link", &self.redirect_link),
+                ("payload", &payload),
+            ],
+        ))
+    }
+
+    fn url(&self, method: &str, params: &[(&str, &str)]) -> String {
+        let query = params
+            .iter()
+            .map(|(key, value)| format!("{key}={}", Utils::percent_encode(value)))
+            .collect::<Vec<String>>()
+            .join("&");
+
+        format!("{}{method}?{query}", self.wallet.base_url)
+    }
+
+    fn account(&self, session: &DeeplinkSession) -> WalletResult<WalletAccount> {
+        let public_key = session.account()?.ok_or(WalletError::AccountNotFound)?;
+
+        WalletAccount::from_parts(
+            bs58::encode(public_key).into_string(),
+            public_key,
+            vec![session.cluster().to_string()],
+            [
+                STANDARD_CONNECT_IDENTIFIER,
+                STANDARD_DISCONNECT_IDENTIFIER,
+                SOLANA_SIGN_MESSAGE_IDENTIFIER,
+                SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+                SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+            ]
+            .map(String::from)
+            .to_vec(),
+            Option::None,
+            Option::None,
+        )
+    }
+
+    fn save(&self, session: &DeeplinkSession) -> WalletResult<()> {
+        let session = serde_json::to_string(session)
+            .map_err(|error| WalletError::InvalidDeeplinkSession(error.to_string()))?;
+
+        self.storage.set(DEEPLINK_SESSION_STORAGE_KEY, &session)
+    }
+}
+
+fn parse_data<'a, T: Deserialize<'a>>(data: &'a [u8]) -> WalletResult<T> {
+    serde_json::from_slice(data)
+        .map_err(|error| WalletError::DeeplinkDecryptionFailed(error.to_string()))
+}
+
+#[cfg(test)]
+mod deeplink_tests {
+    use crypto_box::{aead::Aead, Nonce, PublicKey, SalsaBox, SecretKey};
+    use ed25519_dalek::{Signer, SigningKey};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::InMemoryDeeplinkStorage;
+
+    const REDIRECT_LINK: &str = "https://example.com/wallet-redirect";
+
+    /// The wallet side of the deeplink protocol
+    struct StandInWallet {
+        secret_key: SecretKey,
+        keypair: SigningKey,
+    }
+
+    impl StandInWallet {
+        fn new() -> Self {
+            Self {
+                secret_key: SecretKey::from_bytes([9u8; 32]),
+                keypair: SigningKey::from_bytes(&[7u8; 32]),
+            }
+        }
+
+        fn param(url: &str, key: &str) -> String {
+            Utils::parse_query(url)
+                .unwrap()
+                .into_iter()
+                .find_map(|(name, value)| (name == key).then_some(value))
+                .unwrap()
+        }
+
+        fn shared_box(&self, url: &str) -> SalsaBox {
+            let app_public_key =
+                decode_32bytes(&Self::param(url, "dapp_encryption_public_key")).unwrap();
+
+            SalsaBox::new(&PublicKey::from_bytes(app_public_key), &self.secret_key)
+        }
+
+        fn redirect(&self, url: &str, data: Value) -> String {
+            let nonce = [1u8; 24];
+            let data = self
+                .shared_box(url)
+                .encrypt(Nonce::from_slice(&nonce), data.to_string().as_bytes())
+                .unwrap();
+
+            format!(
+                "{REDIRECT_LINK}?phantom_encryption_public_key={}&nonce={}&data={}",
+                bs58::encode(self.secret_key.public_key().as_bytes()).into_string(),
+                bs58::encode(nonce).into_string(),
+                bs58::encode(data).into_string(),
+            )
+        }
+
+        fn payload(&self, url: &str) -> Value {
+            let payload = self
+                .shared_box(url)
+                .decrypt(
+                    Nonce::from_slice(&decode_base58(&Self::param(url, "nonce")).unwrap()),
+                    decode_base58(&Self::param(url, "payload"))
+                        .unwrap()
+                        .as_slice(),
+                )
+                .unwrap();
+
+            serde_json::from_slice(&payload).unwrap()
+        }
+
+        fn connect(&self, url: &str) -> String {
+            self.redirect(
+                url,
+                json!({
+                    "public_key": bs58::encode(self.keypair.verifying_key().as_bytes()).into_string(),
+                    "session": "stand-in-session",
+                }),
+            )
+        }
+    }
+
+    fn client() -> DeeplinkClient<InMemoryDeeplinkStorage> {
+        let mut client = DeeplinkClient::new(
+            DeeplinkWallet::phantom(),
+            "https://example.com",
+            REDIRECT_LINK,
+            InMemoryDeeplinkStorage::new(),
+        );
+        client.set_cluster(Cluster::MainNet);
+
+        client
+    }
+
+    #[test]
+    fn connect_and_sign_message() {
+        let wallet = StandInWallet::new();
+        let client = client();
+
+        assert_eq!(
+            client.sign_message_url(b"Hello").unwrap_err(),
+            WalletError::DeeplinkNotConnected
+        );
+
+        let connect_url = client.connect_url().unwrap();
+        assert!(connect_url
+            .starts_with("https://phantom.app/ul/v1/connect?app_url=https%3A%2F%2Fexample.com&"));
+        assert_eq!(
+            StandInWallet::param(&connect_url, "cluster"),
+            "mainnet-beta"
+        );
+        assert_eq!(
+            StandInWallet::param(&connect_url, "redirect_link"),
+            REDIRECT_LINK
+        );
+
+        let DeeplinkResponse::Connected(account) = client
+            .handle_redirect(&wallet.connect(&connect_url))
+            .unwrap()
+        else {
+            panic!("Expected the account to be connected");
+        };
+        assert_eq!(
+            account.public_key(),
+            wallet.keypair.verifying_key().to_bytes()
+        );
+        assert_eq!(account.chains(), ["solana:mainnet".to_string()]);
+        assert_eq!(
+            client.connected_account().unwrap().unwrap().address(),
+            account.address()
+        );
+        assert_eq!(
+            client.handle_redirect(REDIRECT_LINK).unwrap_err(),
+            WalletError::DeeplinkNoPendingRequest
+        );
+
+        let sign_message_url = client.sign_message_url(b"Hello").unwrap();
+        assert!(sign_message_url.starts_with("https://phantom.app/ul/v1/signMessage?"));
+        let payload = wallet.payload(&sign_message_url);
+        assert_eq!(payload["session"], "stand-in-session");
+        assert_eq!(payload["display"], "utf8");
+
+        let message = decode_base58(payload["message"].as_str().unwrap()).unwrap();
+        let redirect = wallet.redirect(
+            &sign_message_url,
+            json!({
+                "signature": bs58::encode(wallet.keypair.sign(&message).to_bytes()).into_string(),
+            }),
+        );
+        let DeeplinkResponse::SignedMessage(signed) = client.handle_redirect(&redirect).unwrap()
+        else {
+            panic!("Expected a signed message");
+        };
+        assert_eq!(signed.message_bytes(), b"Hello");
+
+        let sign_transaction_url = client.sign_transaction_url(b"transaction").unwrap();
+        let transaction = wallet.payload(&sign_transaction_url)["transaction"].clone();
+        let redirect =
+            wallet.redirect(&sign_transaction_url, json!({ "transaction": transaction }));
+        let DeeplinkResponse::SignedTransaction(signed) =
+            client.handle_redirect(&redirect).unwrap()
+        else {
+            panic!("Expected a signed transaction");
+        };
+        assert_eq!(signed, b"transaction");
+
+        client.disconnect_url().unwrap();
+        assert!(matches!(
+            client.handle_redirect(REDIRECT_LINK).unwrap(),
+            DeeplinkResponse::Disconnected
+        ));
+        assert!(client.session().unwrap().is_none());
+    }
+
+    #[test]
+    fn rejected_and_tampered_redirects() {
+        let wallet = StandInWallet::new();
+        let client = client();
+
+        client.connect_url().unwrap();
+        assert_eq!(
+            client
+                .handle_redirect("?errorCode=4001&errorMessage=User+rejected+the+request")
+                .unwrap_err(),
+            WalletError::WalletRequestError {
+                code: 4001,
+                message: "User rejected the request".to_string()
+            }
+        );
+
+        assert!(client.session().unwrap().unwrap().pending().is_none());
+
+        // An invalid redirect keeps the request pending for the redirect of the wallet
+        let connect_url = client.connect_url().unwrap();
+        let redirect = wallet.connect(&connect_url);
+        let tampered = redirect.replace("&nonce=", "&nonce=2");
+        assert!(client.handle_redirect(&tampered).is_err());
+        assert!(client.handle_redirect("?nonce=1").is_err());
+        assert!(client.connected_account().unwrap().is_none());
+        assert_eq!(
+            client.session().unwrap().unwrap().pending(),
+            Some(&DeeplinkRequest::Connect)
+        );
+
+        assert!(matches!(
+            client.handle_redirect(&redirect).unwrap(),
+            DeeplinkResponse::Connected(_)
+        ));
+        assert!(client.connected_account().unwrap().is_some());
+        assert_eq!(
+            client.handle_redirect(&redirect).unwrap_err(),
+            WalletError::DeeplinkNoPendingRequest
+        );
+
+        let mut client = client;
+        client.set_cluster(Cluster::LocalNet);
+        assert!(client.connect_url().is_err());
+    }
+}
@@ -0,0 +1,8 @@
+mod storage;
+pub use storage::*;
+
+mod session;
+pub use session::*;
+
+mod client;
+pub use client::*;
@@ -0,0 +1,197 @@
+use crypto_box::{aead::Aead, Nonce, PublicKey, SalsaBox, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use crate::{Cluster, PublicKeyBytes, Utils, WalletError, WalletResult};
+
+const NONCE_LENGTH: usize = 24;
+
+/// A request sent to a deeplink wallet which is waiting for the wallet to redirect back
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+pub enum DeeplinkRequest {
+    /// Connect to the wallet
+    Connect,
+    /// Disconnect from the wallet
+    Disconnect,
+    /// Sign a transaction
+    SignTransaction,
+    /// Sign and send a transaction
+    SignAndSendTransaction,
+    /// Sign a message
+    SignMessage {
+        /// The Base58 encoded message, kept to verify the signature
+        message: String,
+    },
+}
+
+impl DeeplinkRequest {
+    /// The method of the request used in the path of the deeplink, example `signMessage`
+    pub fn method(&self) -> &str {
+        match self {
+            Self::Connect => "connect",
+            Self::Disconnect => "disconnect",
+            Self::SignTransaction => "signTransaction",
+            Self::SignAndSendTransaction => "signAndSendTransaction",
+            Self::SignMessage { .. } => "signMessage",
+        }
+    }
+}
+
+/// The session with a deeplink wallet. It holds the x25519 keypair of the app,
+/// the encryption public key of the wallet and the session token returned
+/// when the app connected, and the [DeeplinkRequest] waiting for a redirect.
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct DeeplinkSession {
+    secret_key: String,
+    cluster: String,
+    wallet_public_key: Option<String>,
+    session: Option<String>,
+    account: Option<String>,
+    pending: Option<DeeplinkRequest>,
+}
+
+impl DeeplinkSession {
+    /// Start a session for the `cluster` with a new x25519 keypair
+    pub fn new(cluster: Cluster) -> Self {
+        let mut secret_key = Utils::rand_32bytes();
+        let encoded = bs58::encode(secret_key).into_string();
+        secret_key.fill(0);
+
+        Self {
+            secret_key: encoded,
+            cluster: cluster.chain().to_string(),
+            wallet_public_key: Option::None,
+            session: Option::None,
+            account: Option::None,
+            pending: Option::None,
+        }
+    }
+
+    /// The Base58 encoded x25519 public key of the app, `dapp_encryption_public_key`
+    pub fn app_public_key(&self) -> WalletResult<String> {
+        Ok(bs58::encode(self.secret_key()?.public_key().as_bytes()).into_string())
+    }
+
+    /// The chain identifier of the cluster, example `solana:mainnet`
+    pub fn cluster(&self) -> &str {
+        self.cluster.as_str()
+    }
+
+    /// The session token returned by the wallet when the app connected
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// The public key of the connected account
+    pub fn account(&self) -> WalletResult<Option<PublicKeyBytes>> {
+        self.account.as_deref().map(decode_32bytes).transpose()
+    }
+
+    /// The request waiting for the wallet to redirect back
+    pub fn pending(&self) -> Option<&DeeplinkRequest> {
+        self.pending.as_ref()
+    }
+
+    /// Whether the wallet approved the connection
+    pub fn is_connected(&self) -> bool {
+        self.session.is_some()
+    }
+
+    pub(crate) fn set_pending(&mut self, request: DeeplinkRequest) -> &mut Self {
+        self.pending.replace(request);
+
+        self
+    }
+
+    pub(crate) fn take_pending(&mut self) -> Option<DeeplinkRequest> {
+        self.pending.take()
+    }
+
+    pub(crate) fn set_connected(
+        &mut self,
+        wallet_public_key: &str,
+        account: &str,
+        session: String,
+    ) -> &mut Self {
+        self.wallet_public_key
+            .replace(wallet_public_key.to_string());
+        self.account.replace(account.to_string());
+        self.session.replace(session);
+
+        self
+    }
+
+    /// Encrypt the JSON `payload` using a random nonce,
+    /// returning the Base58 encoded nonce and ciphertext
+    pub(crate) fn encrypt(&self, payload: &[u8]) -> WalletResult<(String, String)> {
+        let random = Utils::rand_32bytes();
+        let nonce = Nonce::from_slice(&random[..NONCE_LENGTH]);
+
+        let ciphertext = self
+            .shared_box(self.wallet_public_key.as_deref())?
+            .encrypt(nonce, payload)
+            .or(Err(WalletError::DeeplinkDecryptionFailed(
+                "Unable to encrypt the payload".to_string(),
+            )))?;
+
+        Ok((
+            bs58::encode(nonce).into_string(),
+            bs58::encode(ciphertext).into_string(),
+        ))
+    }
+
+    /// Decrypt the Base58 encoded `data` and `nonce` of a redirect. The
+    /// `wallet_public_key` is required when the wallet redirects after connecting
+    pub(crate) fn decrypt(
+        &self,
+        data: &str,
+        nonce: &str,
+        wallet_public_key: Option<&str>,
+    ) -> WalletResult<Vec<u8>> {
+        let nonce = decode_base58(nonce)?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(WalletError::DeeplinkDecryptionFailed(
+                "The nonce must be 24 bytes".to_string(),
+            ));
+        }
+
+        self.shared_box(wallet_public_key.or(self.wallet_public_key.as_deref()))?
+            .decrypt(Nonce::from_slice(&nonce), decode_base58(data)?.as_slice())
+            .or(Err(WalletError::DeeplinkDecryptionFailed(
+                "The payload is not authentic".to_string(),
+            )))
+    }
+
+    fn secret_key(&self) -> WalletResult<SecretKey> {
+        Ok(SecretKey::from_bytes(decode_32bytes(&self.secret_key)?))
+    }
+
+    fn shared_box(&self, wallet_public_key: Option<&str>) -> WalletResult<SalsaBox> {
+        let wallet_public_key = wallet_public_key.ok_or(WalletError::DeeplinkNotConnected)?;
+
+        Ok(SalsaBox::new(
+            &PublicKey::from_bytes(decode_32bytes(wallet_public_key)?),
+            &self.secret_key()?,
+        ))
+    }
+}
+
+impl core::fmt::Debug for DeeplinkSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeeplinkSession")
+            .field("cluster", &self.cluster)
+            .field("account", &self.account)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn decode_base58(value: &str) -> WalletResult<Vec<u8>> {
+    bs58::decode(value)
+        .into_vec()
+        .or(Err(WalletError::InvalidBase58Address))
+}
+
+pub(crate) fn decode_32bytes(value: &str) -> WalletResult<[u8; 32]> {
+    Utils::to32byte_array(&decode_base58(value)?)
+}
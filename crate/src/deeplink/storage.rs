@@ -0,0 +1,91 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{WalletError, WalletResult};
+
+/// Persists the [crate::DeeplinkSession] across the redirects to and from the wallet.
+/// The app is reloaded when the wallet redirects back to it so the storage
+/// must outlive the page, example [BrowserDeeplinkStorage]
+pub trait DeeplinkStorage {
+    /// Get the value stored for the `key`
+    fn get(&self, key: &str) -> WalletResult<Option<String>>;
+
+    /// Store the `value` for the `key`, replacing any previous value
+    fn set(&self, key: &str, value: &str) -> WalletResult<()>;
+
+    /// Remove the value stored for the `key`
+    fn remove(&self, key: &str) -> WalletResult<()>;
+}
+
+/// A [DeeplinkStorage] that keeps the values in memory, useful for tests
+/// and for apps that handle the redirect without reloading the page
+#[derive(Debug, Default)]
+pub struct InMemoryDeeplinkStorage {
+    values: RefCell<HashMap<String, String>>,
+}
+
+impl InMemoryDeeplinkStorage {
+    /// Same as `Self::default()`
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DeeplinkStorage for InMemoryDeeplinkStorage {
+    fn get(&self, key: &str) -> WalletResult<Option<String>> {
+        Ok(self.values.borrow().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &str) -> WalletResult<()> {
+        self.values
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> WalletResult<()> {
+        self.values.borrow_mut().remove(key);
+
+        Ok(())
+    }
+}
+
+/// A [DeeplinkStorage] backed by the `localStorage` or `sessionStorage` of the browser
+#[derive(Debug, Clone)]
+pub struct BrowserDeeplinkStorage(web_sys::Storage);
+
+impl BrowserDeeplinkStorage {
+    /// Use the `localStorage` of the browser window
+    pub fn local() -> WalletResult<Self> {
+        let window = web_sys::window().ok_or(WalletError::MissingAccessToBrowserWindow)?;
+
+        Ok(Self(
+            window.local_storage()?.ok_or(WalletError::ValueNotFound)?,
+        ))
+    }
+
+    /// Use the `sessionStorage` of the browser window
+    pub fn session() -> WalletResult<Self> {
+        let window = web_sys::window().ok_or(WalletError::MissingAccessToBrowserWindow)?;
+
+        Ok(Self(
+            window
+                .session_storage()?
+                .ok_or(WalletError::ValueNotFound)?,
+        ))
+    }
+}
+
+impl DeeplinkStorage for BrowserDeeplinkStorage {
+    fn get(&self, key: &str) -> WalletResult<Option<String>> {
+        Ok(self.0.get_item(key)?)
+    }
+
+    fn set(&self, key: &str, value: &str) -> WalletResult<()> {
+        Ok(self.0.set_item(key, value)?)
+    }
+
+    fn remove(&self, key: &str) -> WalletResult<()> {
+        Ok(self.0.remove_item(key)?)
+    }
+}
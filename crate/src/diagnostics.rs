@@ -0,0 +1,333 @@
+use std::{cell::RefCell, rc::Rc};
+
+use web_sys::wasm_bindgen::JsValue;
+
+use crate::{
+    FeatureSupport, Features, Reflection, Wallet, WalletError, WalletResult,
+    SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+};
+
+/// Convenience type for `Rc<RefCell<Vec<WalletDiagnostics>>>;`
+pub type DiagnosticsType = Rc<RefCell<Vec<WalletDiagnostics>>>;
+
+/// Storage for the [WalletDiagnostics] reports of every wallet
+/// registration attempt made to the [crate::WalletAdapter]
+#[derive(Default, PartialEq, Eq, Clone)]
+pub struct DiagnosticsStorage(DiagnosticsType);
+
+impl DiagnosticsStorage {
+    /// Clone the inner field  as `Rc<RefCell<Vec<WalletDiagnostics>>>`
+    pub fn clone_inner(&self) -> DiagnosticsType {
+        Rc::clone(&self.0)
+    }
+
+    /// Get all the reports in the order the registration attempts were made
+    pub fn get_reports(&self) -> Vec<WalletDiagnostics> {
+        self.0.borrow().clone()
+    }
+
+    /// Get the latest report of a wallet by name
+    pub fn get_report(&self, wallet_name: &str) -> Option<WalletDiagnostics> {
+        self.0
+            .borrow()
+            .iter()
+            .rev()
+            .find(|report| {
+                report
+                    .name
+                    .as_ref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(wallet_name))
+            })
+            .cloned()
+    }
+
+    /// Remove all the reports
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+impl core::fmt::Debug for DiagnosticsStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", &*self.0.borrow())
+    }
+}
+
+/// The outcome of parsing a single feature of a wallet during registration
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeatureDiagnostics {
+    name: String,
+    version: Option<String>,
+    error: Option<WalletError>,
+}
+
+impl FeatureDiagnostics {
+    /// The feature identifier, e.g. `solana:signIn`
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The raw version string of the feature if the wallet provided one
+    pub fn version(&self) -> Option<&String> {
+        self.version.as_ref()
+    }
+
+    /// The error encountered while parsing the feature,
+    /// `None` if the feature was parsed successfully
+    pub fn error(&self) -> Option<&WalletError> {
+        self.error.as_ref()
+    }
+
+    /// Whether the feature was parsed successfully
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A report of a wallet registration attempt. The report is collected
+/// from the raw wallet object before parsing so that wallets that
+/// fail to register can still be inspected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WalletDiagnostics {
+    name: Option<String>,
+    version: Option<String>,
+    chains: Vec<String>,
+    features: Vec<FeatureDiagnostics>,
+    supported_transaction_versions: Vec<String>,
+    error: Option<WalletError>,
+}
+
+impl WalletDiagnostics {
+    /// Collect the report from the raw wallet [JsValue] and the outcome of parsing it
+    pub fn new(value: &JsValue, outcome: &WalletResult<Wallet>) -> Self {
+        let mut diagnostics = Self {
+            error: outcome.as_ref().err().cloned(),
+            ..Default::default()
+        };
+
+        let reflection = match Reflection::new(value.clone()) {
+            Ok(reflection) => reflection,
+            Err(_) => return diagnostics,
+        };
+
+        diagnostics.name = reflection.string("name").ok();
+        diagnostics.version = reflection.string("version").ok();
+        diagnostics.chains = reflection.vec_string("chains").unwrap_or_default();
+
+        let features_keys = reflection
+            .object_to_vec_string("features")
+            .unwrap_or_default();
+
+        if let Ok(features_object) = Reflection::new_from_str(reflection.get_inner(), "features") {
+            diagnostics.features = features_keys
+                .into_iter()
+                .map(|feature| Self::feature(&features_object, feature))
+                .collect();
+
+            diagnostics.supported_transaction_versions = Self::tx_versions(&features_object);
+        }
+
+        diagnostics
+    }
+
+    fn feature(features_object: &Reflection, feature: String) -> FeatureDiagnostics {
+        let version = Reflection::new_from_str(features_object.get_inner(), &feature)
+            .and_then(|inner| inner.string("version"))
+            .ok();
+
+        let error = Features::default()
+            .parse_feature(
+                features_object,
+                feature.clone(),
+                &mut FeatureSupport::default(),
+            )
+            .err();
+
+        FeatureDiagnostics {
+            name: feature,
+            version,
+            error,
+        }
+    }
+
+    fn tx_versions(features_object: &Reflection) -> Vec<String> {
+        let mut tx_versions = Vec::<String>::new();
+
+        [
+            SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+            SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+        ]
+        .iter()
+        .filter_map(|feature| Reflection::new_from_str(features_object.get_inner(), feature).ok())
+        .filter_map(|inner| inner.reflect_js_array("supportedTransactionVersions").ok())
+        .for_each(|versions| {
+            versions.iter().for_each(|value| {
+                let value = value
+                    .as_string()
+                    .or(value.as_f64().map(|number| number.to_string()))
+                    .unwrap_or(format!("{value:?}"));
+
+                if !tx_versions.contains(&value) {
+                    tx_versions.push(value);
+                }
+            })
+        });
+
+        tx_versions
+    }
+
+    /// The raw name of the wallet if the wallet provided one
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    /// The raw version string of the wallet if the wallet provided one
+    pub fn version(&self) -> Option<&String> {
+        self.version.as_ref()
+    }
+
+    /// The raw chain identifiers of the wallet
+    pub fn chains(&self) -> &[String] {
+        self.chains.as_slice()
+    }
+
+    /// The parse outcome of each feature of the wallet
+    pub fn features(&self) -> &[FeatureDiagnostics] {
+        self.features.as_slice()
+    }
+
+    /// The transaction versions supported by `solana:signTransaction`
+    /// and `solana:signAndSendTransaction`
+    pub fn supported_transaction_versions(&self) -> &[String] {
+        self.supported_transaction_versions.as_slice()
+    }
+
+    /// The error that caused the registration to fail,
+    /// `None` if the wallet was registered
+    pub fn error(&self) -> Option<&WalletError> {
+        self.error.as_ref()
+    }
+
+    /// Whether the wallet was registered
+    pub fn is_registered(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl core::fmt::Display for WalletDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let or_missing =
+            |value: Option<&String>| value.cloned().unwrap_or_else(|| "<missing>".to_string());
+
+        writeln!(f, "Wallet: {}", or_missing(self.name.as_ref()))?;
+        writeln!(f, "Version: {}", or_missing(self.version.as_ref()))?;
+        writeln!(f, "Chains: {}", self.chains.join(", "))?;
+        writeln!(
+            f,
+            "Supported Transaction Versions: {}",
+            self.supported_transaction_versions.join(", ")
+        )?;
+        writeln!(f, "Features:")?;
+
+        for feature in &self.features {
+            write!(
+                f,
+                "  {}@{}",
+                feature.name,
+                or_missing(feature.version.as_ref())
+            )?;
+
+            match feature.error.as_ref() {
+                Some(error) => writeln!(f, " - Error: {error}")?,
+                None => writeln!(f, " - OK")?,
+            }
+        }
+
+        match self.error.as_ref() {
+            Some(error) => write!(f, "Outcome: Failed - {error:?}"),
+            None => write!(f, "Outcome: Registered"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+
+    fn report(name: Option<&str>, error: Option<WalletError>) -> WalletDiagnostics {
+        WalletDiagnostics {
+            name: name.map(|name| name.to_string()),
+            version: Some("1.0.0".to_string()),
+            chains: vec!["solana:devnet".to_string()],
+            features: vec![
+                FeatureDiagnostics {
+                    name: "standard:connect".to_string(),
+                    version: Some("1.0.0".to_string()),
+                    error: None,
+                },
+                FeatureDiagnostics {
+                    name: "solana:signIn".to_string(),
+                    version: None,
+                    error: Some(WalletError::ExpectedValueNotFound("version".to_string())),
+                },
+            ],
+            supported_transaction_versions: vec!["legacy".to_string(), "0".to_string()],
+            error,
+        }
+    }
+
+    #[test]
+    fn record_and_read_reports() {
+        let storage = DiagnosticsStorage::default();
+        assert!(storage.get_reports().is_empty());
+        assert!(storage.get_report("Phantom").is_none());
+
+        // Reports are recorded through the inner storage like `InitEvents` does
+        let inner = storage.clone_inner();
+        inner
+            .borrow_mut()
+            .push(report(Some("Phantom"), Some(WalletError::ValueNotFound)));
+        inner
+            .borrow_mut()
+            .push(report(None, Some(WalletError::ValueNotFound)));
+        inner.borrow_mut().push(report(Some("Phantom"), None));
+
+        let reports = storage.get_reports();
+        assert_eq!(reports.len(), 3);
+        assert!(!reports[0].is_registered());
+        assert_eq!(reports[0].error(), Some(&WalletError::ValueNotFound));
+        assert!(reports[1].name().is_none());
+
+        // The latest report of a wallet is returned and names are case insensitive
+        let latest = storage.get_report("phantom").unwrap();
+        assert!(latest.is_registered());
+        assert_eq!(latest, reports[2]);
+        assert!(storage.get_report("Solflare").is_none());
+
+        assert!(latest.features()[0].is_ok());
+        assert_eq!(latest.features()[0].version(), Some(&"1.0.0".to_string()));
+        assert!(!latest.features()[1].is_ok());
+        assert_eq!(latest.chains(), ["solana:devnet".to_string()]);
+        assert_eq!(
+            latest.supported_transaction_versions(),
+            ["legacy".to_string(), "0".to_string()]
+        );
+
+        storage.clear();
+        assert!(storage.get_reports().is_empty());
+        assert!(inner.borrow().is_empty());
+    }
+
+    #[test]
+    fn display_report() {
+        let registered = report(Some("Phantom"), None).to_string();
+        assert!(registered.starts_with("Wallet: Phantom\nVersion: 1.0.0\n"));
+        assert!(registered.contains("  standard:connect@1.0.0 - OK\n"));
+        assert!(registered.contains("  solana:signIn@<missing> - Error: "));
+        assert!(registered.ends_with("Outcome: Registered"));
+
+        let failed = report(None, Some(WalletError::ValueNotFound)).to_string();
+        assert!(failed.starts_with("Wallet: <missing>\n"));
+        assert!(failed.ends_with("Outcome: Failed - ValueNotFound"));
+    }
+}
@@ -16,10 +16,27 @@ impl From<async_channel::SendError<WalletEvent>> for WalletError {
 
 /// Error handling enum
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Error)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "data", rename_all = "camelCase")
+)]
 pub enum WalletError {
     /// Unable to send the a [WalletEvent] via the [crate::WalletEventSender]
     #[error("Unable to send the a `WalletEvent` variant via the WalletEventSender channel")]
     ChannelError,
+    /// A [crate::WalletEventStream] subscriber did not receive events fast enough
+    /// and the number of events in the error were dropped
+    #[error("The wallet event subscriber lagged behind and `{0}` events were dropped")]
+    EventStreamLagged(usize),
+    /// A wallet request was dropped before the wallet responded,
+    /// example when the future of the request was cancelled
+    #[error("The wallet request was cancelled before the wallet responded")]
+    RequestCancelled,
+    /// The [crate::WalletEventBroadcaster] of a [crate::WalletEventStream] was dropped
+    /// and all the buffered events have been received
+    #[error("The wallet event stream is closed")]
+    EventStreamClosed,
     /// An JavaScript Error corresponding to a [JsValue] .
     /// It contains the error type represented by `name`,
     /// the error message `message`
@@ -34,6 +51,32 @@ pub enum WalletError {
         /// The stack from the JavaScript error message
         stack: String,
     },
+    /// An error returned by a browser wallet which carries a numeric `code`,
+    /// example `{ code: 4001, message: "User rejected the request." }`.
+    /// Use [WalletErrorCode::from] on the `code` to get the meaning of the code.
+    #[error("{message} (code `{code}`)")]
+    WalletRequestError {
+        /// The numeric code of the error returned by the wallet
+        code: i64,
+        /// The message of the error returned by the wallet
+        message: String,
+    },
+    /// An error that occurred while a browser wallet handled a request,
+    /// with the `wallet` name, the wallet standard `feature` and the `operation` that failed.
+    /// The error is rendered as a chain, example
+    /// `Phantom > solana:signMessage > response: User rejected the request. (code `4001`)`.
+    /// Use [WalletError::root] to get the error that caused it
+    #[error("{wallet} > {feature} > {operation}: {source}")]
+    Context {
+        /// The name of the wallet
+        wallet: String,
+        /// The wallet standard feature identifier, example `solana:signMessage`
+        feature: String,
+        /// The operation that failed, example `response` when the wallet rejected the request
+        operation: String,
+        /// The error that caused this error
+        source: Box<WalletError>,
+    },
     /// An internal error that occurs when casting a JavaScript types or DOM types to a Rust type using [web_sys::wasm_bindgen::JsCast] trait
     /// and `foo.dyn_ref::<T>()` or `foo.dyn_into::<T>()` where `foo`
     /// is a variable of type [JsValue]. These error can also occur when trying to parse a browser error from JavaScript value
@@ -59,9 +102,13 @@ pub enum WalletError {
     /// The wallet version is invalid, expected SemVer version
     #[error("The wallet version `{0}` is invalid, expected SemVer version")]
     InvalidWalletVersion(String),
-    /// Unexpected SemVer number to parse to a `u8`
-    #[error("Unexpected SemVer number `{0}` to parse to a `u8`")]
+    /// Unexpected SemVer number to parse to a `u64`.
+    /// Numbers must not be empty or contain leading zeroes
+    #[error("Unexpected SemVer number `{0}` to parse to a `u64`")]
     InvalidSemVerNumber(String),
+    /// The SemVer requirement is invalid, expected comparators like `^1.0` or `>=1.0.0, <2.0.0`
+    #[error("The SemVer requirement `{0}` is invalid, expected comparators like `^1.0` or `>=1.0.0, <2.0.0`")]
+    InvalidSemVerRequirement(String),
     /// The byte length should be equal to 32 bytes in length
     #[error("The byte length should be equal to 32 bytes in length")]
     Expected32ByteLength,
@@ -74,6 +121,17 @@ pub enum WalletError {
     /// This feature is not supported as a standard  or solana namespace feature
     #[error("The feature `{0}` is not supported as a standard  or solana namespace feature")]
     UnsupportedWalletFeature(String),
+    /// The version of a `standard` or `solana` feature does not match
+    /// [crate::SUPPORTED_FEATURE_VERSIONS]
+    #[error("The version `{version}` of the feature `{feature}` is not supported, expected `{requirement}`")]
+    UnsupportedFeatureVersion {
+        /// The feature identifier, example `solana:signIn`
+        feature: String,
+        /// The version of the feature advertised by the wallet
+        version: String,
+        /// The supported versions
+        requirement: String,
+    },
     /// Encountered an unsupported transaction version.
     /// Only `legacy` and `version zero` transactions are supported.
     #[error("Encountered an unsupported transaction version. Only `legacy` and `version zero` transactions are supported.")]
@@ -96,6 +154,9 @@ pub enum WalletError {
     )]
     AccountNotFound,
     /// Unable to connect to a wallet. The user may have rejected the request
+    #[deprecated(
+        note = "connect errors are returned as `WalletError::Context` with the feature `standard:connect`, use `WalletError::root()` to get the error of the wallet"
+    )]
     #[error("Unable to connect to a wallet. Error `{0}` request")]
     WalletConnectError(String),
     /// The connect method did not return any accounts
@@ -108,6 +169,9 @@ pub enum WalletError {
     #[error("The `accounts` method to get the accounts connected to a wallet is missing from wallet `{0}`")]
     MissingGetAccountsFunction(String),
     /// Unable to disconnect wallet.
+    #[deprecated(
+        note = "disconnect errors are returned as `WalletError::Context` with the feature `standard:disconnect`, use `WalletError::root()` to get the error of the wallet"
+    )]
     #[error("Wallet Disconnect error - `{0}`")]
     WalletDisconnectError(String),
     /// Encountered an error while calling `standard:events` function
@@ -137,15 +201,90 @@ pub enum WalletError {
     /// Invalid Base58 Address
     #[error("Invalid Base58 Address")]
     InvalidBase58Address,
-    /// The nonce is required to be at least 8 characters long
-    #[error("The nonce is required to be at least 8 characters long")]
+    /// The nonce is required to be at least 8 alphanumeric characters long
+    #[error("The nonce is required to be at least 8 alphanumeric characters long")]
     NonceMustBeAtLeast8Characters,
     ///Expected a timestamp in the format specified by ISO8601
     #[error("Invalid ISO 8601 timestamp `{0}. Only timestamps in the format specified by ISO8601 are supported.")]
     InvalidISO8601Timestamp(String),
+    /// The Sign In With Solana (SIWS) message does not follow the SIWS grammar
+    #[error("Invalid Sign In With Solana message at line {line}: {reason}")]
+    InvalidSigninMessage {
+        /// The line number, starting from 1, where the error was encountered
+        line: usize,
+        /// The reason the line is invalid
+        reason: String,
+    },
+    /// A field required to create a Sign In With Solana (SIWS) message is missing
+    #[error("The `{0}` field is required to create a Sign In With Solana message")]
+    MissingSigninField(String),
     /// The message signed by the wallet is not the same as the message sent to the wallet for signing
     #[error("The message signed by the wallet is not the same as the message sent to the wallet for signing")]
     MessageResponseMismatch,
+    /// The domain in the signed Sign In With Solana (SIWS) message is not the expected domain
+    #[error("The domain `{0}` in the signed message is not the expected domain")]
+    SigninDomainMismatch(String),
+    /// The URI in the signed Sign In With Solana (SIWS) message is not the expected URI
+    #[error("The URI `{0}` in the signed message is not the expected URI")]
+    SigninUriMismatch(String),
+    /// The chain in the signed Sign In With Solana (SIWS) message is not the expected chain
+    #[error("The chain `{0}` in the signed message is not the expected chain")]
+    SigninChainMismatch(String),
+    /// The address in the signed Sign In With Solana (SIWS) message does not belong to the
+    /// public key that signed the message
+    #[error("The address in the signed message does not belong to the public key that signed the message")]
+    SigninAddressMismatch,
+    /// The `Issued At` time of the signed Sign In With Solana (SIWS) message is in the future
+    #[error("The `Issued At` time of the signed message is in the future")]
+    SigninIssuedInTheFuture,
+    /// The `Issued At` time of the signed Sign In With Solana (SIWS) message is older than the maximum age allowed
+    #[error("The `Issued At` time of the signed message is older than the maximum age allowed")]
+    SigninIssuedTooLongAgo,
+    /// The `Expiration Time` of the signed Sign In With Solana (SIWS) message has passed
+    #[error("The signed message has expired")]
+    SigninExpired,
+    /// The `Not Before` time of the signed Sign In With Solana (SIWS) message has not been reached
+    #[error("The signed message is not valid yet")]
+    SigninNotYetValid,
+    /// The nonce of the signed Sign In With Solana (SIWS) message has already been used
+    #[error("The nonce `{0}` of the signed message has already been used")]
+    SigninNonceAlreadyUsed(String),
+    /// The nonce of the signed Sign In With Solana (SIWS) message was issued but has expired
+    #[error("The nonce `{0}` of the signed message has expired")]
+    SigninNonceExpired(String),
+    /// The nonce of the signed Sign In With Solana (SIWS) message was not issued by the nonce store
+    #[error("The nonce `{0}` of the signed message was not issued")]
+    SigninUnknownNonce(String),
+    /// The nonce of the signed Sign In With Solana (SIWS) message was issued for a different domain or address
+    #[error("The nonce `{0}` of the signed message was issued for a different domain or address")]
+    SigninNonceBindingMismatch(String),
+    /// The signed message is not UTF-8 encoded
+    #[error("The signed message is not UTF-8 encoded: {0}")]
+    InvalidUtf8Message(String),
+    /// The signed message proof could not be decoded
+    #[error("Invalid signed message proof: {0}")]
+    InvalidSignedMessageProof(String),
+    /// The bytes are not a valid Solana off-chain message
+    #[error("Invalid Solana off-chain message: {0}")]
+    InvalidOffchainMessage(String),
+    /// The message body is too long for the Solana off-chain message format
+    #[error("The Solana off-chain message of `{0}` bytes is too long for the message format")]
+    OffchainMessageTooLong(usize),
+    /// The public key is not in the list of signers of the Solana off-chain message
+    #[error("The public key is not a signer of the Solana off-chain message")]
+    OffchainMessageSignerNotFound,
+    /// An input of a request to a [crate::StandardWalletProvider] is not an object
+    #[error("The request input at index `{0}` is not an object")]
+    InvalidRequestInput(usize),
+    /// The session token is malformed or its signature is invalid
+    #[error("Invalid session token: {0}")]
+    InvalidSessionToken(String),
+    /// The session token has expired
+    #[error("The session token has expired")]
+    SessionExpired,
+    /// The session token has been revoked
+    #[error("The session `{0}` has been revoked")]
+    SessionRevoked(String),
     /// The Ed25519 Signature is invalid for the signed message and public key")]
     #[error("The Ed25519 Signature is invalid for the signed message and public key")]
     InvalidSignature,
@@ -170,6 +309,67 @@ pub enum WalletError {
     /// The `sendAndSignTransaction` method did not return any signature
     #[error("The `sendAndSignTransaction` method did not return any signature")]
     SendAndSignTransactionSignatureEmpty,
+    /// The wallet icon is not a valid base64 encoded data URI of a supported image
+    #[error("Invalid wallet icon. {0}")]
+    InvalidWalletIcon(String),
+    /// The MIME type of the wallet icon is not supported
+    #[error("The wallet icon MIME type `{0}` is not supported. Only `image/svg+xml`, `image/webp`, `image/png` and `image/gif` are supported")]
+    UnsupportedWalletIconMimeType(String),
+    /// The size of the decoded wallet icon exceeds the size limit
+    #[error("The wallet icon of `{0}` bytes exceeds the size limit")]
+    WalletIconTooLarge(usize),
+    /// The value is not a valid percent-encoded URL component
+    #[error("The value `{0}` is not a valid percent-encoded URL component")]
+    InvalidPercentEncoding(String),
+    /// The request requires a deeplink wallet session that has been approved by the wallet
+    #[error("The deeplink wallet is not connected")]
+    DeeplinkNotConnected,
+    /// The deeplink wallet redirected back to the app yet no request was sent to the wallet
+    #[error("The deeplink wallet redirected yet there is no pending request")]
+    DeeplinkNoPendingRequest,
+    /// The payload of a deeplink could not be encrypted, decrypted or parsed
+    #[error("Invalid deeplink payload. {0}")]
+    DeeplinkDecryptionFailed(String),
+    /// The persisted deeplink session could not be parsed
+    #[error("Invalid deeplink session. {0}")]
+    InvalidDeeplinkSession(String),
+    /// The Mobile Wallet Adapter session handshake with the wallet failed
+    #[error("The Mobile Wallet Adapter session handshake failed. {0}")]
+    MwaHandshakeFailed(String),
+    /// A message of the Mobile Wallet Adapter session could not be encrypted, decrypted or parsed
+    #[error("Invalid Mobile Wallet Adapter message. {0}")]
+    MwaInvalidMessage(String),
+    /// The connection to the Mobile Wallet Adapter wallet failed or was closed
+    #[error("Mobile Wallet Adapter transport error. {0}")]
+    MwaTransportError(String),
+    /// The keypair of the [crate::KeypairSigner] could not be loaded
+    #[error("Invalid keypair. {0}")]
+    InvalidKeypair(String),
+    /// The serialized transaction could not be parsed
+    #[error("Invalid transaction. {0}")]
+    InvalidTransaction(String),
+    /// The public key of the signer is not one of the required signers of the transaction
+    #[error("The public key of the signer is not one of the required signers of the transaction")]
+    NotATransactionSigner,
+    /// The encrypted keystore of the burner wallet could not be parsed
+    #[error("Invalid burner wallet keystore. {0}")]
+    InvalidBurnerKeystore(String),
+    /// The password is incorrect or the keystore of the burner wallet was modified
+    #[error("Unable to decrypt the burner wallet. The password is incorrect or the keystore was modified")]
+    BurnerDecryptionFailed,
+    /// No burner wallet is stored in the browser
+    #[error("No burner wallet is stored in the browser")]
+    BurnerWalletNotFound,
+    /// The password used to encrypt the burner wallet must not be empty
+    #[error("The password used to encrypt the burner wallet must not be empty")]
+    BurnerPasswordRequired,
+    /// The Solana Pay transfer request URL is malformed
+    #[error("Invalid Solana Pay transfer request URL. {0}")]
+    InvalidSolanaPayUrl(String),
+    /// The amount of a Solana Pay transfer request is not a non-negative decimal number
+    /// or has more decimal places than the token supports
+    #[error("Invalid Solana Pay amount `{0}`")]
+    InvalidSolanaPayAmount(String),
     /// An operation resulted in an error. This is a convenience error that you can use to return any error
     /// that was not caused by the wallet adapter, example, parsing a recipient address or the result of parsing
     /// the body of a HTTP response using serde resulted in an error. Remember, this error is not from the [crate::WalletAdapter]
@@ -178,35 +378,311 @@ pub enum WalletError {
     Op(String),
 }
 
+impl WalletError {
+    /// Wrap the error in [WalletError::Context] with the `wallet` name,
+    /// the wallet standard `feature` and the `operation` that failed
+    pub fn context(self, wallet: &str, feature: &str, operation: &str) -> Self {
+        Self::Context {
+            wallet: wallet.to_string(),
+            feature: feature.to_string(),
+            operation: operation.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The error that caused this error, skipping any [WalletError::Context]
+    pub fn root(&self) -> &Self {
+        let mut error = self;
+
+        while let Self::Context { source, .. } = error {
+            error = source;
+        }
+
+        error
+    }
+
+    /// The numeric code of an error returned by a browser wallet, if any
+    pub fn code(&self) -> Option<i64> {
+        match self.root() {
+            Self::WalletRequestError { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// The [WalletErrorCode] of an error returned by a browser wallet, if any
+    pub fn error_code(&self) -> Option<WalletErrorCode> {
+        self.code().map(WalletErrorCode::from)
+    }
+
+    /// The user cancelled or rejected the request in the browser wallet.
+    /// This is true for [WalletErrorCode::UserRejectedRequest] and for
+    /// wallets that throw an error without a code whose name or message
+    /// says the user rejected the request.
+    pub fn is_user_rejected(&self) -> bool {
+        match self.root() {
+            Self::WalletRequestError { code, .. } => {
+                WalletErrorCode::from(*code) == WalletErrorCode::UserRejectedRequest
+            }
+            Self::JsError { name, message, .. } => {
+                name.contains("UserRejected") || message.to_lowercase().contains("user rejected")
+            }
+            _ => false,
+        }
+    }
+
+    /// The browser wallet does not support the requested method, feature or chain
+    pub fn is_unsupported(&self) -> bool {
+        match self.root() {
+            Self::WalletRequestError { code, .. } => matches!(
+                WalletErrorCode::from(*code),
+                WalletErrorCode::UnsupportedMethod
+                    | WalletErrorCode::MethodNotFound
+                    | WalletErrorCode::MethodNotSupported
+            ),
+            Self::UnsupportedWalletFeature(_)
+            | Self::UnsupportedFeatureVersion { .. }
+            | Self::UnsupportedChain(_)
+            | Self::UnsupportedTransactionVersion
+            | Self::LegacyTransactionSupportRequired
+            | Self::MissingConnectFunction
+            | Self::MissingDisconnectFunction
+            | Self::MissingStandardEventsFunction
+            | Self::MissingSignInFunction
+            | Self::MissingSignMessageFunction
+            | Self::MissingSignTransactionFunction => true,
+            _ => false,
+        }
+    }
+
+    /// The request failed because of a transient condition in the browser wallet,
+    /// example the wallet is disconnected, busy or rate limited,
+    /// so sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self.root() {
+            Self::WalletRequestError { code, .. } => matches!(
+                WalletErrorCode::from(*code),
+                WalletErrorCode::Disconnected
+                    | WalletErrorCode::ChainDisconnected
+                    | WalletErrorCode::ResourceUnavailable
+                    | WalletErrorCode::LimitExceeded
+                    | WalletErrorCode::InternalError
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// The common error codes returned by browser wallets
+/// as specified by EIP-1193 and JSON-RPC 2.0 (EIP-1474)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum WalletErrorCode {
+    /// `4001` The user rejected the request
+    UserRejectedRequest,
+    /// `4100` The requested method and/or account has not been authorized by the user
+    Unauthorized,
+    /// `4200` The wallet does not support the requested method
+    UnsupportedMethod,
+    /// `4900` The wallet is disconnected from all chains
+    Disconnected,
+    /// `4901` The wallet is not connected to the requested chain
+    ChainDisconnected,
+    /// `-32000` Missing or invalid parameters
+    InvalidInput,
+    /// `-32001` The requested resource was not found
+    ResourceNotFound,
+    /// `-32002` The requested resource is not available
+    ResourceUnavailable,
+    /// `-32003` The transaction was rejected
+    TransactionRejected,
+    /// `-32004` The requested method is not supported
+    MethodNotSupported,
+    /// `-32005` The request exceeds a defined limit
+    LimitExceeded,
+    /// `-32600` The request is not a valid JSON-RPC request
+    InvalidRequest,
+    /// `-32601` The requested method does not exist
+    MethodNotFound,
+    /// `-32602` Invalid method parameters
+    InvalidParams,
+    /// `-32603` An internal error occurred in the wallet
+    InternalError,
+    /// `-32700` The wallet was unable to parse the request
+    ParseError,
+    /// Any other error code
+    Other(i64),
+}
+
+impl WalletErrorCode {
+    /// The numeric code
+    pub fn code(&self) -> i64 {
+        match self {
+            Self::UserRejectedRequest => 4001,
+            Self::Unauthorized => 4100,
+            Self::UnsupportedMethod => 4200,
+            Self::Disconnected => 4900,
+            Self::ChainDisconnected => 4901,
+            Self::InvalidInput => -32000,
+            Self::ResourceNotFound => -32001,
+            Self::ResourceUnavailable => -32002,
+            Self::TransactionRejected => -32003,
+            Self::MethodNotSupported => -32004,
+            Self::LimitExceeded => -32005,
+            Self::InvalidRequest => -32600,
+            Self::MethodNotFound => -32601,
+            Self::InvalidParams => -32602,
+            Self::InternalError => -32603,
+            Self::ParseError => -32700,
+            Self::Other(code) => *code,
+        }
+    }
+}
+
+impl From<i64> for WalletErrorCode {
+    fn from(value: i64) -> Self {
+        match value {
+            4001 => Self::UserRejectedRequest,
+            4100 => Self::Unauthorized,
+            4200 => Self::UnsupportedMethod,
+            4900 => Self::Disconnected,
+            4901 => Self::ChainDisconnected,
+            -32000 => Self::InvalidInput,
+            -32001 => Self::ResourceNotFound,
+            -32002 => Self::ResourceUnavailable,
+            -32003 => Self::TransactionRejected,
+            -32004 => Self::MethodNotSupported,
+            -32005 => Self::LimitExceeded,
+            -32600 => Self::InvalidRequest,
+            -32601 => Self::MethodNotFound,
+            -32602 => Self::InvalidParams,
+            -32603 => Self::InternalError,
+            -32700 => Self::ParseError,
+            _ => Self::Other(value),
+        }
+    }
+}
+
 impl From<JsValue> for WalletError {
     fn from(value: JsValue) -> Self {
-        let reflect = |key: &str| -> Result<String, Self> {
+        // Wallets commonly reject with a plain string
+        if let Some(message) = value.as_string() {
+            return Self::JsError {
+                name: String::default(),
+                message,
+                stack: String::default(),
+            };
+        }
+
+        let reflect = |key: &str| -> Option<JsValue> {
             Reflect::get(&value, &key.into())
-                .map_err(|error: JsValue| WalletError::InternalError(format!("{:?}", &error)))?
-                .as_string()
-                .ok_or(WalletError::InternalError(format!(
-                    "Reflecting `{key}` in `{value:?}` did not yield a JsString"
-                )))
+                .ok()
+                .filter(|inner| !inner.is_undefined() && !inner.is_null())
         };
+        let reflect_string = |key: &str| reflect(key).and_then(|inner| inner.as_string());
 
-        let name = match reflect("name") {
-            Ok(inner) => inner,
-            Err(error) => return error,
-        };
+        let message = reflect_string("message");
 
-        let stack = match reflect("stack") {
-            Ok(inner) => inner,
-            Err(error) => return error,
-        };
-        let message = match reflect("message") {
-            Ok(inner) => inner,
-            Err(error) => return error,
+        // Wallets commonly reject with a `{code, message}` object
+        // or an `Error` with a `code` property
+        let code = reflect("code").and_then(|code| {
+            code.as_f64()
+                .filter(|code| code.fract() == 0.0)
+                .map(|code| code as i64)
+                .or_else(|| code.as_string()?.parse::<i64>().ok())
+        });
+
+        if let Some(code) = code {
+            return Self::WalletRequestError {
+                code,
+                message: message.unwrap_or_default(),
+            };
+        }
+
+        match message {
+            Some(message) => Self::JsError {
+                name: reflect_string("name").unwrap_or_default(),
+                message,
+                stack: reflect_string("stack").unwrap_or_default(),
+            },
+            None => Self::InternalError(format!(
+                "Unable to parse the error `{value:?}` returned by the browser wallet"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod error_classification_tests {
+    use super::*;
+
+    #[test]
+    fn error_codes() {
+        for code in [
+            4001, 4100, 4200, 4900, 4901, -32000, -32001, -32002, -32003, -32004, -32005, -32600,
+            -32601, -32602, -32603, -32700, 42,
+        ] {
+            assert_eq!(WalletErrorCode::from(code).code(), code);
+        }
+        assert_eq!(WalletErrorCode::from(42), WalletErrorCode::Other(42));
+    }
+
+    #[test]
+    fn context_chain() {
+        let error = WalletError::WalletRequestError {
+            code: 4001,
+            message: "User rejected the request.".to_string(),
+        }
+        .context("Phantom", "solana:signMessage", "response");
+
+        assert_eq!(
+            error.to_string(),
+            "Phantom > solana:signMessage > response: User rejected the request. (code `4001`)"
+        );
+        assert_eq!(
+            std::error::Error::source(&error).map(|source| source.to_string()),
+            Some("User rejected the request. (code `4001`)".to_string())
+        );
+    }
+
+    #[test]
+    fn classification() {
+        let wallet_error = |code: i64| WalletError::WalletRequestError {
+            code,
+            message: String::default(),
         };
 
-        Self::JsError {
-            message,
-            name,
-            stack,
+        assert!(wallet_error(4001).is_user_rejected());
+        assert!(!wallet_error(4001).is_retryable());
+        assert_eq!(
+            wallet_error(4001).error_code(),
+            Some(WalletErrorCode::UserRejectedRequest)
+        );
+        assert!(WalletError::JsError {
+            name: "WalletSignMessageError".to_string(),
+            message: "User rejected the request.".to_string(),
+            stack: String::default(),
         }
+        .is_user_rejected());
+
+        assert!(wallet_error(4200).is_unsupported());
+        assert!(wallet_error(-32601).is_unsupported());
+        assert!(WalletError::MissingSignInFunction.is_unsupported());
+        assert!(!wallet_error(4100).is_unsupported());
+
+        assert!(wallet_error(4900).is_retryable());
+        assert!(wallet_error(-32603).is_retryable());
+        assert!(!wallet_error(-32003).is_retryable());
+
+        let with_context = wallet_error(4001).context("Phantom", "solana:signMessage", "response");
+        assert!(with_context.is_user_rejected());
+        assert_eq!(with_context.code(), Some(4001));
+        assert_eq!(with_context.root(), &wallet_error(4001));
+        assert!(WalletError::MissingSignInFunction
+            .context("Phantom", "solana:signIn", "request")
+            .is_unsupported());
+
+        assert_eq!(WalletError::InvalidSignature.code(), None);
+        assert!(!WalletError::InvalidSignature.is_user_rejected());
+        assert!(!WalletError::InvalidSignature.is_unsupported());
+        assert!(!WalletError::InvalidSignature.is_retryable());
     }
 }
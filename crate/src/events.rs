@@ -1,6 +1,9 @@
-use std::rc::Rc;
+use std::{
+    future::Future,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use async_channel::{Receiver, Sender};
 use web_sys::{
     js_sys::{Object, Reflect},
     wasm_bindgen::{prelude::Closure, JsValue},
@@ -8,15 +11,18 @@ use web_sys::{
 };
 
 use crate::{
-    Reflection, StorageType, Utils, Wallet, WalletAccount, WalletAdapter, WalletError,
-    WalletResult, WINDOW_APP_READY_EVENT_TYPE,
+    send_wallet_event, Cluster, DiagnosticsType, Reflection, StorageType, Utils, Wallet,
+    WalletAccount, WalletAdapter, WalletDiagnostics, WalletError, WalletEventBroadcaster,
+    WalletEventStream, WalletResult, SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+    SOLANA_SIGN_IN_IDENTIFIER, SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+    STANDARD_CONNECT_IDENTIFIER, WINDOW_APP_READY_EVENT_TYPE,
 };
 
-/// The `Sender` part of an [async_channel::bounded] channel
-pub type WalletEventSender = Sender<WalletEvent>;
+/// Broadcasts every [WalletEvent] to all the [WalletEventReceiver]s
+pub type WalletEventSender = WalletEventBroadcaster;
 
-/// The `Receiver` part of an [async_channel::bounded] channel
-pub type WalletEventReceiver = Receiver<WalletEvent>;
+/// A subscription that receives every [WalletEvent] broadcast by a [WalletEventSender]
+pub type WalletEventReceiver = WalletEventStream;
 
 /// Used to initialize the `Register` and `AppReady` events to the browser window
 #[derive(Debug, PartialEq, Eq)]
@@ -31,22 +37,24 @@ impl<'a> InitEvents<'a> {
     }
 
     /// Register events by providing a [crate::WalletStorage] that is used to store
-    /// all registered wallets
+    /// all registered wallets and a [crate::DiagnosticsStorage] that is used to store
+    /// a report of every registration attempt
     pub fn init(&self, adapter: &mut WalletAdapter) -> WalletResult<()> {
         let storage = adapter.storage();
-        self.register_wallet_event(storage.clone_inner())?;
-        self.dispatch_app_event(storage.clone_inner());
+        let diagnostics = adapter.diagnostics_storage();
+        self.register_wallet_event(storage.clone_inner(), diagnostics.clone_inner())?;
+        self.dispatch_app_event(storage.clone_inner(), diagnostics.clone_inner());
 
         Ok(())
     }
 
     /// An App Ready event registered to the browser window
-    pub fn dispatch_app_event(&self, storage: StorageType) {
+    pub fn dispatch_app_event(&self, storage: StorageType, diagnostics: DiagnosticsType) {
         let app_ready_init = CustomEventInit::new();
         app_ready_init.set_bubbles(false);
         app_ready_init.set_cancelable(false);
         app_ready_init.set_composed(false);
-        app_ready_init.set_detail(&Self::register_object(storage));
+        app_ready_init.set_detail(&Self::register_object(storage, diagnostics));
 
         let app_ready_ev =
             CustomEvent::new_with_event_init_dict(WINDOW_APP_READY_EVENT_TYPE, &app_ready_init)
@@ -56,8 +64,13 @@ impl<'a> InitEvents<'a> {
     }
 
     /// The register wallet event registered to the browser window
-    pub fn register_wallet_event(&self, storage: StorageType) -> WalletResult<()> {
+    pub fn register_wallet_event(
+        &self,
+        storage: StorageType,
+        diagnostics: DiagnosticsType,
+    ) -> WalletResult<()> {
         let inner_storage = Rc::clone(&storage);
+        let inner_diagnostics = Rc::clone(&diagnostics);
 
         let listener_closure = Closure::wrap(Box::new(move |custom_event: CustomEvent| {
             let detail = Reflection::new(custom_event
@@ -66,7 +79,7 @@ impl<'a> InitEvents<'a> {
 
             Utils::jsvalue_to_error(detail.call1(
                 &JsValue::null(),
-                &Self::register_object(inner_storage.clone()),
+                &Self::register_object(inner_storage.clone(), inner_diagnostics.clone()),
             ))
             .unwrap()
         }) as Box<dyn Fn(_)>);
@@ -84,32 +97,36 @@ impl<'a> InitEvents<'a> {
         Ok(())
     }
 
-    /// Sets the object to be passed to the register function
-    pub fn register_object(storage: StorageType) -> Object {
+    /// Sets the object to be passed to the register function.
+    /// Every registration attempt is recorded as a [WalletDiagnostics] report
+    pub fn register_object(storage: StorageType, diagnostics: DiagnosticsType) -> Object {
         // The `register` function that logs and returns a closure like in your JS code
-        let register =
-            Closure::wrap(
-                Box::new(move |value: JsValue| match Wallet::from_jsvalue(value) {
-                    Ok(wallet) => {
-                        let inner_outcome = storage.clone();
-
-                        inner_outcome.borrow_mut().insert(
-                            blake3::hash(wallet.name().to_lowercase().as_bytes()),
-                            wallet,
-                        );
-                    }
-                    Err(error) => {
-                        let error = error.to_string();
-                        if error.contains("is not supported") {
-                        } else {
-                            web_sys::console::error_2(
-                                &"REGISTER EVENT ERROR".into(),
-                                &error.into(),
-                            );
-                        }
+        let register = Closure::wrap(Box::new(move |value: JsValue| {
+            let outcome = Wallet::from_jsvalue(value.clone());
+
+            diagnostics
+                .borrow_mut()
+                .push(WalletDiagnostics::new(&value, &outcome));
+
+            match outcome {
+                Ok(wallet) => {
+                    let inner_outcome = storage.clone();
+
+                    inner_outcome.borrow_mut().insert(
+                        blake3::hash(wallet.name().to_lowercase().as_bytes()),
+                        wallet,
+                    );
+                }
+                Err(error) => {
+                    let error = error.to_string();
+                    if error.contains("is not supported") {
+                        wallet_log!(debug, "Skipped registering wallet: {error}");
+                    } else {
+                        wallet_log!(error, "Unable to register wallet: {error}");
                     }
-                }) as Box<dyn Fn(_)>,
-            );
+                }
+            }
+        }) as Box<dyn Fn(_)>);
 
         // Create an object and set the `register` property
         let register_object = Object::new();
@@ -119,7 +136,10 @@ impl<'a> InitEvents<'a> {
             &JsValue::from("register"),
             &register.into_js_value(),
         ) {
-            web_sys::console::error_2(&"REGISTER EVENT ERROR".into(), &error);
+            wallet_log!(
+                error,
+                "Unable to set the `register` property on the register object: {error:?}"
+            );
         }
 
         register_object
@@ -132,6 +152,11 @@ impl<'a> InitEvents<'a> {
 /// from the `standard:events` events namespace specifically,
 /// `wallet.features[standard:events].on`
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "data", rename_all = "camelCase")
+)]
 pub enum WalletEvent {
     /// An account has been connected and an event `change` emitted.
     Connected(WalletAccount),
@@ -142,6 +167,46 @@ pub enum WalletEvent {
     /// An account has been connected and an event `change` emitted.
     /// The wallet adapter then updates the connected [WalletAccount].
     AccountChanged(WalletAccount),
+    /// The connected wallet changed the chains it supports and an event `change` emitted.
+    /// The wallet adapter then updates the chains of the connected [Wallet].
+    ChainsChanged(Vec<Cluster>),
+    /// The connected wallet changed the features it supports and an event `change` emitted.
+    /// The wallet adapter then updates the features of the connected [Wallet].
+    /// Contains the identifiers of all the features now supported by the wallet.
+    FeaturesChanged(Vec<String>),
+    /// A request was sent to the connected wallet, example the wallet
+    /// is now prompting the user to approve the request
+    RequestStarted {
+        /// A unique identifier of the request
+        id: u64,
+        /// The kind of request
+        kind: WalletRequestKind,
+    },
+    /// The wallet completed the request successfully
+    RequestApproved {
+        /// The identifier of the request
+        id: u64,
+        /// The kind of request
+        kind: WalletRequestKind,
+    },
+    /// The user rejected the request in the wallet
+    RequestRejected {
+        /// The identifier of the request
+        id: u64,
+        /// The kind of request
+        kind: WalletRequestKind,
+    },
+    /// The request failed for a reason other than the user rejecting it.
+    /// The error is [WalletError::RequestCancelled] if the request
+    /// was dropped before the wallet responded
+    RequestFailed {
+        /// The identifier of the request
+        id: u64,
+        /// The kind of request
+        kind: WalletRequestKind,
+        /// The error that caused the request to fail
+        error: WalletError,
+    },
     /// An error occurred when a background task was executed.
     /// This type of event is encountered mostly from the
     /// `on` method from the `[standard:events]` namespace
@@ -159,9 +224,240 @@ impl core::fmt::Display for WalletEvent {
             Self::Reconnected(_) => "Reconnected",
             Self::Disconnected => "Disconnected",
             Self::AccountChanged(_) => "Account Changed",
+            Self::ChainsChanged(_) => "Chains Changed",
+            Self::FeaturesChanged(_) => "Features Changed",
+            Self::RequestStarted { kind, .. } => &format!("{kind} Request Started"),
+            Self::RequestApproved { kind, .. } => &format!("{kind} Request Approved"),
+            Self::RequestRejected { kind, .. } => &format!("{kind} Request Rejected"),
+            Self::RequestFailed { kind, error, .. } => &format!("{kind} Request Failed: {error}"),
             Self::BackgroundTaskError(error) => &format!("Task error: {error:?}"),
             Self::Skip => "Skipped",
         };
         write!(f, "{}", as_str)
     }
 }
+
+/// The kind of request sent to a wallet which is reported by the
+/// [WalletEvent::RequestStarted], [WalletEvent::RequestApproved],
+/// [WalletEvent::RequestRejected] and [WalletEvent::RequestFailed] events
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum WalletRequestKind {
+    /// A `standard:connect` request
+    Connect,
+    /// A `solana:signIn` request
+    SignIn,
+    /// A `solana:signMessage` request
+    SignMessage,
+    /// A `solana:signTransaction` request
+    SignTransaction,
+    /// A `solana:signAndSendTransaction` request
+    SignAndSendTransaction,
+}
+
+impl WalletRequestKind {
+    /// The identifier of the wallet standard feature that handles the request
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            Self::Connect => STANDARD_CONNECT_IDENTIFIER,
+            Self::SignIn => SOLANA_SIGN_IN_IDENTIFIER,
+            Self::SignMessage => SOLANA_SIGN_MESSAGE_IDENTIFIER,
+            Self::SignTransaction => SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+            Self::SignAndSendTransaction => SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+        }
+    }
+}
+
+impl core::fmt::Display for WalletRequestKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let as_str = match self {
+            Self::Connect => "Connect",
+            Self::SignIn => "Sign In",
+            Self::SignMessage => "Sign Message",
+            Self::SignTransaction => "Sign Transaction",
+            Self::SignAndSendTransaction => "Sign And Send Transaction",
+        };
+        write!(f, "{}", as_str)
+    }
+}
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Broadcast the lifecycle events of the `request` of the given `kind`
+/// sent to a wallet, [WalletEvent::RequestStarted] before the request
+/// and then one of [WalletEvent::RequestApproved], [WalletEvent::RequestRejected]
+/// or [WalletEvent::RequestFailed] depending on the outcome.
+/// The wallet must be looked up before calling this so that
+/// a request is only started if there is a wallet to send it to.
+pub(crate) async fn track_request<T, F>(
+    sender: &WalletEventSender,
+    kind: WalletRequestKind,
+    request: F,
+) -> WalletResult<T>
+where
+    F: Future<Output = WalletResult<T>>,
+{
+    let mut tracker = RequestTracker {
+        id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+        kind,
+        sender,
+        finished: false,
+    };
+
+    send_wallet_event(
+        WalletEvent::RequestStarted {
+            id: tracker.id,
+            kind,
+        },
+        sender.clone(),
+    );
+
+    let outcome = request.await;
+    tracker.finish(&outcome);
+
+    outcome
+}
+
+// Emits [WalletEvent::RequestFailed] with [WalletError::RequestCancelled]
+// if the request future is dropped before it completes
+struct RequestTracker<'a> {
+    id: u64,
+    kind: WalletRequestKind,
+    sender: &'a WalletEventSender,
+    finished: bool,
+}
+
+impl RequestTracker<'_> {
+    fn finish<T>(&mut self, outcome: &WalletResult<T>) {
+        let (id, kind) = (self.id, self.kind);
+
+        let event = match outcome.as_ref() {
+            Ok(_) => WalletEvent::RequestApproved { id, kind },
+            Err(error) if error.is_user_rejected() => WalletEvent::RequestRejected { id, kind },
+            Err(error) => WalletEvent::RequestFailed {
+                id,
+                kind,
+                error: error.clone(),
+            },
+        };
+        send_wallet_event(event, self.sender.clone());
+
+        self.finished = true;
+    }
+}
+
+impl Drop for RequestTracker<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.finish::<()>(&Err(WalletError::RequestCancelled));
+        }
+    }
+}
+
+#[cfg(test)]
+mod request_events_tests {
+    use std::task::Poll;
+
+    use super::*;
+    use crate::test_utils::poll_once;
+
+    fn next_event(events: &WalletEventReceiver) -> WalletEvent {
+        match poll_once(events.recv()) {
+            Poll::Ready(Ok(event)) => event,
+            outcome => panic!("Expected an event, found {outcome:?}"),
+        }
+    }
+
+    #[test]
+    fn lifecycle() {
+        let sender = WalletEventSender::default();
+        let events = sender.subscribe();
+
+        let outcome = poll_once(track_request(
+            &sender,
+            WalletRequestKind::SignMessage,
+            async { Ok(()) },
+        ));
+        assert_eq!(outcome, Poll::Ready(Ok(())));
+
+        let WalletEvent::RequestStarted { id, kind } = next_event(&events) else {
+            panic!("Expected `RequestStarted`")
+        };
+        assert_eq!(kind, WalletRequestKind::SignMessage);
+        assert_eq!(
+            next_event(&events),
+            WalletEvent::RequestApproved { id, kind }
+        );
+
+        let rejected = WalletError::WalletRequestError {
+            code: 4001,
+            message: "User rejected the request.".to_string(),
+        };
+        let _ = poll_once(track_request(&sender, WalletRequestKind::Connect, async {
+            Err::<(), _>(rejected)
+        }));
+        let WalletEvent::RequestStarted {
+            id: rejected_id, ..
+        } = next_event(&events)
+        else {
+            panic!("Expected `RequestStarted`")
+        };
+        assert!(rejected_id > id);
+        assert_eq!(
+            next_event(&events),
+            WalletEvent::RequestRejected {
+                id: rejected_id,
+                kind: WalletRequestKind::Connect
+            }
+        );
+
+        let _ = poll_once(track_request(&sender, WalletRequestKind::SignIn, async {
+            Err::<(), _>(WalletError::MissingSignInFunction)
+        }));
+        let WalletEvent::RequestStarted { id, .. } = next_event(&events) else {
+            panic!("Expected `RequestStarted`")
+        };
+        assert_eq!(
+            next_event(&events),
+            WalletEvent::RequestFailed {
+                id,
+                kind: WalletRequestKind::SignIn,
+                error: WalletError::MissingSignInFunction
+            }
+        );
+        assert_eq!(WalletRequestKind::SignIn.identifier(), "solana:signIn");
+    }
+
+    #[test]
+    fn cancelled() {
+        let sender = WalletEventSender::default();
+        let events = sender.subscribe();
+
+        let mut request = Box::pin(track_request(
+            &sender,
+            WalletRequestKind::SignTransaction,
+            std::future::pending::<WalletResult<()>>(),
+        ));
+        assert!(poll_once(request.as_mut()).is_pending());
+
+        let WalletEvent::RequestStarted { id, kind } = next_event(&events) else {
+            panic!("Expected `RequestStarted`")
+        };
+        assert_eq!(poll_once(events.recv()), Poll::Pending);
+
+        drop(request);
+        assert_eq!(
+            next_event(&events),
+            WalletEvent::RequestFailed {
+                id,
+                kind,
+                error: WalletError::RequestCancelled
+            }
+        );
+        assert_eq!(poll_once(events.recv()), Poll::Pending);
+    }
+}
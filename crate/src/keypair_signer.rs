@@ -0,0 +1,674 @@
+use std::path::Path;
+
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::{
+    Cluster, SignInOutput, SignedMessageOutput, SigninInput, StandardWalletSigner, Utils,
+    WalletAccount, WalletError, WalletResult, SOLANA_SIGN_IN_IDENTIFIER,
+    SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+    STANDARD_CONNECT_IDENTIFIER, STANDARD_DISCONNECT_IDENTIFIER,
+};
+
+/// The name of the account returned by [KeypairSigner::connect]
+pub const KEYPAIR_SIGNER_LABEL: &str = "Keypair Signer";
+
+const SIGNATURE_LENGTH: usize = 64;
+const PUBLIC_KEY_LENGTH: usize = 32;
+const VERSIONED_MESSAGE_PREFIX: u8 = 0x80;
+
+/// A signer backed by an Ed25519 keypair held in memory, for CLI tools and headless tests
+/// where there is no browser wallet. It mirrors the `connect`, `disconnect`, `sign_message`,
+/// `sign_in` and `sign_transaction` methods of [crate::WalletAdapter], returning the same types,
+/// so that the code written against the adapter can run natively.
+pub struct KeypairSigner {
+    keypair: SigningKey,
+    cluster: Cluster,
+    connected: bool,
+}
+
+impl KeypairSigner {
+    /// Load the keypair from the 64 bytes of the secret key followed by the public key,
+    /// the layout used by `solana-keygen`. The public key must belong to the secret key.
+    /// The cluster defaults to [Cluster::DevNet]
+    pub fn from_bytes(bytes: &[u8]) -> WalletResult<Self> {
+        let bytes: &[u8; 64] = bytes.try_into().or(Err(WalletError::InvalidKeypair(
+            "The keypair must be 64 bytes in length".to_string(),
+        )))?;
+
+        let keypair =
+            SigningKey::from_keypair_bytes(bytes).or(Err(WalletError::InvalidKeypair(
+                "The public key does not belong to the secret key".to_string(),
+            )))?;
+
+        Ok(Self {
+            keypair,
+            cluster: Cluster::DevNet,
+            connected: false,
+        })
+    }
+
+    /// Generate a new random keypair. The cluster defaults to [Cluster::DevNet]
+    pub fn generate() -> Self {
+        let mut secret_key = Utils::rand_32bytes();
+        let keypair = SigningKey::from_bytes(&secret_key);
+        secret_key.fill(0);
+
+        Self {
+            keypair,
+            cluster: Cluster::DevNet,
+            connected: false,
+        }
+    }
+
+    /// Load the keypair from the contents of a `solana-keygen` keypair file,
+    /// a JSON array of the 64 bytes, example `[12,201,...,87]`
+    pub fn from_json(json: &str) -> WalletResult<Self> {
+        let invalid = || WalletError::InvalidKeypair("Expected a JSON array of bytes".to_string());
+
+        let bytes = json
+            .trim()
+            .strip_prefix('[')
+            .and_then(|json| json.strip_suffix(']'))
+            .ok_or_else(invalid)?
+            .split(',')
+            .map(|byte| byte.trim().parse::<u8>().or(Err(invalid())))
+            .collect::<WalletResult<Vec<u8>>>()?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Load the keypair from a `solana-keygen` keypair file, example `~/.config/solana/id.json`
+    pub fn from_file(path: impl AsRef<Path>) -> WalletResult<Self> {
+        let json = std::fs::read_to_string(path.as_ref()).map_err(|error| {
+            WalletError::InvalidKeypair(format!(
+                "Unable to read `{}`. {error}",
+                path.as_ref().display()
+            ))
+        })?;
+
+        Self::from_json(&json)
+    }
+
+    /// Set the cluster the account is connected to. Disconnects the signer
+    /// since the connected account was created for the previous cluster
+    pub fn set_cluster(&mut self, cluster: Cluster) -> &mut Self {
+        self.cluster = cluster;
+        self.connected = false;
+
+        self
+    }
+
+    /// Get the cluster the account is connected to
+    pub fn cluster(&self) -> Cluster {
+        self.cluster
+    }
+
+    /// Get the public key of the keypair
+    pub fn public_key(&self) -> [u8; 32] {
+        self.keypair.verifying_key().to_bytes()
+    }
+
+    /// Get the Base58 address of the keypair
+    pub fn address(&self) -> String {
+        bs58::encode(self.public_key()).into_string()
+    }
+
+    /// Connect the account of the keypair, returning the [WalletAccount]
+    /// for the cluster of the signer
+    pub async fn connect(&mut self) -> WalletResult<WalletAccount> {
+        let account = self.account()?;
+        self.connected = true;
+
+        Ok(account)
+    }
+
+    /// Disconnect the account
+    pub async fn disconnect(&mut self) -> WalletResult<()> {
+        self.connected = false;
+
+        Ok(())
+    }
+
+    /// Check whether the account is connected
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Get the connected [account](WalletAccount)
+    pub fn connected_account(&self) -> WalletResult<WalletAccount> {
+        self.ensure_connected()?;
+
+        self.account()
+    }
+
+    /// Sign a message with the keypair
+    pub async fn sign_message(&self, message: &[u8]) -> WalletResult<SignedMessageOutput> {
+        self.ensure_connected()?;
+
+        Ok(self.signed_message(message))
+    }
+
+    /// Sign In With Solana. The signer renders the SIWS message from the `signin_input`
+    /// and signs it, filling in the address of the keypair if it is not set.
+    /// The `public_key` must be the public key of the keypair.
+    pub async fn sign_in(
+        &self,
+        signin_input: &SigninInput,
+        public_key: [u8; 32],
+    ) -> WalletResult<SignInOutput> {
+        self.ensure_connected()?;
+
+        if public_key != self.public_key() {
+            return Err(WalletError::SigninAddressMismatch);
+        }
+
+        self.signed_in(signin_input)
+    }
+
+    /// Sign a serialized legacy or version zero transaction, returning the transaction
+    /// with the signature of the keypair in its signer slot. The keypair must be
+    /// one of the required signers of the transaction.
+    /// The `cluster` if set must be the cluster of the signer.
+    pub async fn sign_transaction(
+        &self,
+        transaction_bytes: &[u8],
+        cluster: Option<Cluster>,
+    ) -> WalletResult<Vec<Vec<u8>>> {
+        self.ensure_connected()?;
+
+        if let Some(cluster) = cluster {
+            if cluster != self.cluster {
+                return Err(WalletError::UnsupportedChain(cluster.chain().to_string()));
+            }
+        }
+
+        Ok(vec![self.signed_transaction(transaction_bytes)?])
+    }
+
+    /// Get the 64 bytes of the secret key followed by the public key
+    #[cfg(feature = "burner")]
+    pub(crate) fn to_bytes(&self) -> [u8; 64] {
+        self.keypair.to_keypair_bytes()
+    }
+
+    /// Sign the `message` without checking that the account is connected
+    pub(crate) fn signed_message(&self, message: &[u8]) -> SignedMessageOutput {
+        SignedMessageOutput::new(
+            message,
+            self.public_key(),
+            self.keypair.sign(message).to_bytes(),
+        )
+    }
+
+    /// Render and sign the SIWS message without checking that the account is connected
+    pub(crate) fn signed_in(&self, signin_input: &SigninInput) -> WalletResult<SignInOutput> {
+        let account = self.account()?;
+
+        let mut signin_input = signin_input.clone();
+        match signin_input.address() {
+            Some(address) if *address != account.address => {
+                return Err(WalletError::SigninAddressMismatch)
+            }
+            Some(_) => (),
+            None => {
+                signin_input.set_address(&account.address)?;
+            }
+        }
+
+        let message = signin_input.to_message()?;
+        let signature = self.keypair.sign(message.as_bytes()).to_bytes();
+
+        Ok(SignInOutput {
+            account,
+            message,
+            signature,
+            public_key: self.public_key(),
+        })
+    }
+
+    /// Sign a serialized transaction without checking that the account is connected
+    pub(crate) fn signed_transaction(&self, transaction_bytes: &[u8]) -> WalletResult<Vec<u8>> {
+        let (signatures_offset, signatures_count) = decode_compact_u16(transaction_bytes, 0)?;
+        let message_offset = signatures_offset + signatures_count * SIGNATURE_LENGTH;
+        let message = transaction_bytes
+            .get(message_offset..)
+            .filter(|message| !message.is_empty())
+            .ok_or(WalletError::InvalidTransaction(
+                "The transaction has no message".to_string(),
+            ))?;
+
+        let signer_index = signer_index(message, signatures_count, &self.public_key())?;
+        let signature_offset = signatures_offset + signer_index * SIGNATURE_LENGTH;
+
+        let mut signed = transaction_bytes.to_vec();
+        signed[signature_offset..signature_offset + SIGNATURE_LENGTH]
+            .copy_from_slice(&self.keypair.sign(message).to_bytes());
+
+        Ok(signed)
+    }
+
+    /// Build the [WalletAccount] of the keypair. The account is built each time
+    /// instead of being cloned since it holds a [web_sys::wasm_bindgen::JsValue]
+    /// which can only be cloned in the browser
+    pub(crate) fn account(&self) -> WalletResult<WalletAccount> {
+        WalletAccount::from_parts(
+            self.address(),
+            self.public_key(),
+            vec![self.cluster.chain().to_string()],
+            [
+                STANDARD_CONNECT_IDENTIFIER,
+                STANDARD_DISCONNECT_IDENTIFIER,
+                SOLANA_SIGN_IN_IDENTIFIER,
+                SOLANA_SIGN_MESSAGE_IDENTIFIER,
+                SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+            ]
+            .iter()
+            .map(|feature| feature.to_string())
+            .collect(),
+            Some(KEYPAIR_SIGNER_LABEL.to_string()),
+            Option::None,
+        )
+    }
+
+    fn ensure_connected(&self) -> WalletResult<()> {
+        if self.connected {
+            Ok(())
+        } else {
+            Err(WalletError::AccountNotFound)
+        }
+    }
+}
+
+impl core::fmt::Debug for KeypairSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeypairSigner")
+            .field("address", &self.address())
+            .field("cluster", &self.cluster)
+            .field("connected", &self.is_connected())
+            .finish_non_exhaustive()
+    }
+}
+
+impl StandardWalletSigner for KeypairSigner {
+    fn public_key(&self) -> [u8; 32] {
+        self.keypair.verifying_key().to_bytes()
+    }
+
+    /// Approves the connection only if the signer was [connected](KeypairSigner::connect)
+    /// before it was handed to the provider
+    async fn approve_connection(&self) -> WalletResult<()> {
+        self.ensure_connected()
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<[u8; 64]> {
+        Ok(self.keypair.sign(message).to_bytes())
+    }
+
+    async fn sign_transaction(&self, transaction_bytes: &[u8]) -> WalletResult<Vec<u8>> {
+        self.signed_transaction(transaction_bytes)
+    }
+}
+
+/// Find the position of the `public_key` among the required signers of the transaction `message`
+fn signer_index(
+    message: &[u8],
+    signatures_count: usize,
+    public_key: &[u8; 32],
+) -> WalletResult<usize> {
+    let truncated = || WalletError::InvalidTransaction("The message is truncated".to_string());
+
+    let mut offset = 0usize;
+    let first_byte = *message.first().ok_or_else(truncated)?;
+    if first_byte & VERSIONED_MESSAGE_PREFIX != 0 {
+        if first_byte != VERSIONED_MESSAGE_PREFIX {
+            return Err(WalletError::UnsupportedTransactionVersion);
+        }
+
+        offset += 1;
+    }
+
+    // The header is the number of required signatures followed by the numbers
+    // of read-only signed and read-only unsigned accounts
+    let required_signatures = *message.get(offset).ok_or_else(truncated)? as usize;
+    if required_signatures != signatures_count {
+        return Err(WalletError::InvalidTransaction(format!(
+            "The transaction has {signatures_count} signatures but the message requires {required_signatures}"
+        )));
+    }
+
+    let (keys_offset, keys_count) = decode_compact_u16(message, offset + 3)?;
+    if keys_count < required_signatures {
+        return Err(WalletError::InvalidTransaction(
+            "The message has fewer accounts than required signatures".to_string(),
+        ));
+    }
+
+    (0..required_signatures)
+        .find(|index| {
+            let key_offset = keys_offset + index * PUBLIC_KEY_LENGTH;
+
+            message.get(key_offset..key_offset + PUBLIC_KEY_LENGTH) == Some(public_key.as_slice())
+        })
+        .ok_or(WalletError::NotATransactionSigner)
+}
+
+/// Decode the compact-u16 length at `offset`,
+/// returning the offset after the length and the length
+fn decode_compact_u16(bytes: &[u8], offset: usize) -> WalletResult<(usize, usize)> {
+    let mut value = 0usize;
+
+    for position in 0..3 {
+        let byte = *bytes
+            .get(offset + position)
+            .ok_or(WalletError::InvalidTransaction(
+                "The compact length is truncated".to_string(),
+            ))?;
+
+        value |= ((byte & 0x7f) as usize) << (position * 7);
+
+        if byte & 0x80 == 0 {
+            return Ok((offset + position + 1, value));
+        }
+    }
+
+    Err(WalletError::InvalidTransaction(
+        "The compact length is longer than 3 bytes".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod keypair_signer_tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::Signer as _,
+        transaction::{Transaction, VersionedTransaction},
+    };
+
+    use super::*;
+    use crate::{test_utils::ready, InMemoryNonceStore, NonceStore, SiwsVerifier, Utils};
+
+    // An instruction that needs the signature of `from`, the system program is not needed
+    fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+        Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &lamports.to_le_bytes(),
+            vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        )
+    }
+
+    fn keypair_json(keypair: &Keypair) -> String {
+        format!(
+            "[{}]\n",
+            keypair
+                .to_bytes()
+                .iter()
+                .map(|byte| byte.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    fn connected(keypair: &Keypair) -> KeypairSigner {
+        let mut signer = KeypairSigner::from_json(&keypair_json(keypair)).unwrap();
+        ready(signer.connect()).unwrap();
+
+        signer
+    }
+
+    #[test]
+    fn load_keypair() {
+        let keypair = Keypair::new();
+
+        let signer = KeypairSigner::from_json(&keypair_json(&keypair)).unwrap();
+        assert_eq!(signer.public_key(), keypair.pubkey().to_bytes());
+        assert_eq!(signer.address(), keypair.pubkey().to_string());
+
+        let path = std::env::temp_dir().join(format!("{}.json", keypair.pubkey()));
+        std::fs::write(&path, keypair_json(&keypair)).unwrap();
+        assert_eq!(
+            KeypairSigner::from_file(&path).unwrap().public_key(),
+            keypair.pubkey().to_bytes()
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            KeypairSigner::from_file(&path),
+            Err(WalletError::InvalidKeypair(_))
+        ));
+
+        let mut mismatched = keypair.to_bytes();
+        mismatched[32..].copy_from_slice(&Keypair::new().pubkey().to_bytes());
+        assert!(matches!(
+            KeypairSigner::from_bytes(&mismatched),
+            Err(WalletError::InvalidKeypair(_))
+        ));
+        assert!(matches!(
+            KeypairSigner::from_bytes(&keypair.to_bytes()[..32]),
+            Err(WalletError::InvalidKeypair(_))
+        ));
+        assert!(matches!(
+            KeypairSigner::from_json("[1, 2, 256]"),
+            Err(WalletError::InvalidKeypair(_))
+        ));
+        assert!(matches!(
+            KeypairSigner::from_json("1, 2, 3"),
+            Err(WalletError::InvalidKeypair(_))
+        ));
+    }
+
+    #[test]
+    fn connect_and_sign_message() {
+        let keypair = Keypair::new();
+        let mut signer = KeypairSigner::from_bytes(&keypair.to_bytes()).unwrap();
+
+        assert_eq!(
+            ready(signer.sign_message(b"Hello")).unwrap_err(),
+            WalletError::AccountNotFound
+        );
+
+        signer.set_cluster(Cluster::MainNet);
+        let account = ready(signer.connect()).unwrap();
+        assert_eq!(account.address, keypair.pubkey().to_string());
+        assert!(account.mainnet());
+        assert!(account.solana_signin());
+        assert!(account.solana_sign_message());
+        assert!(account.solana_sign_transaction());
+        assert!(!account.solana_sign_and_send_transaction());
+
+        let output = ready(signer.sign_message(b"Hello")).unwrap();
+        assert_eq!(output.message_bytes(), b"Hello");
+        assert_eq!(output.address().unwrap(), keypair.pubkey().to_string());
+        Utils::verify_signature(output.public_key().unwrap(), b"Hello", output.signature())
+            .unwrap();
+
+        ready(signer.disconnect()).unwrap();
+        assert!(!signer.is_connected());
+    }
+
+    #[test]
+    fn sign_in() {
+        let keypair = Keypair::new();
+        let signer = connected(&keypair);
+
+        // The message has no expiration time so the verifier
+        // needs a nonce store to remember the used nonces
+        let nonce_store = Arc::new(InMemoryNonceStore::new());
+        let nonce = nonce_store
+            .issue(
+                "example.com",
+                None,
+                Duration::from_secs(300),
+                SystemTime::now(),
+            )
+            .unwrap();
+
+        let mut signin_input = SigninInput::new();
+        signin_input
+            .set_custom_domain("example.com")
+            .set_statement("Sign in to the CLI")
+            .set_chain_id(Cluster::DevNet)
+            .custom_nonce(&nonce)
+            .unwrap();
+
+        let output = ready(signer.sign_in(&signin_input, signer.public_key())).unwrap();
+        let parsed = SiwsVerifier::new("example.com")
+            .set_nonce_store(nonce_store)
+            .verify(&output)
+            .unwrap();
+        assert_eq!(parsed.address(), Some(&signer.address()));
+
+        assert_eq!(
+            ready(signer.sign_in(&signin_input, Keypair::new().pubkey().to_bytes())).unwrap_err(),
+            WalletError::SigninAddressMismatch
+        );
+
+        let mut other_address = signin_input.clone();
+        other_address
+            .set_address(&Keypair::new().pubkey().to_string())
+            .unwrap();
+        assert_eq!(
+            ready(signer.sign_in(&other_address, signer.public_key())).unwrap_err(),
+            WalletError::SigninAddressMismatch
+        );
+
+        assert_eq!(
+            ready(signer.sign_in(&SigninInput::new(), signer.public_key())).unwrap_err(),
+            WalletError::MissingSigninField("domain".to_string())
+        );
+    }
+
+    #[test]
+    fn sign_legacy_transaction() {
+        let fee_payer = Keypair::new();
+        let keypair = Keypair::new();
+        let signer = connected(&keypair);
+
+        let instruction = transfer(&keypair.pubkey(), &fee_payer.pubkey(), 1_000);
+        let mut transaction =
+            Transaction::new_with_payer(&[instruction], Some(&fee_payer.pubkey()));
+        transaction.message.recent_blockhash = Hash::new_unique();
+        transaction.partial_sign(&[&fee_payer], transaction.message.recent_blockhash);
+
+        let signed = ready(signer.sign_transaction(
+            &bincode::serialize(&transaction).unwrap(),
+            Some(Cluster::DevNet),
+        ))
+        .unwrap();
+        assert_eq!(signed.len(), 1);
+
+        let signed: Transaction = bincode::deserialize(&signed[0]).unwrap();
+        assert!(signed.is_signed());
+        signed.verify().unwrap();
+
+        assert_eq!(
+            ready(signer.sign_transaction(
+                &bincode::serialize(&transaction).unwrap(),
+                Some(Cluster::MainNet),
+            ))
+            .unwrap_err(),
+            WalletError::UnsupportedChain(Cluster::MainNet.chain().to_string())
+        );
+    }
+
+    #[test]
+    fn sign_versioned_transaction() {
+        let keypair = Keypair::new();
+        let signer = connected(&keypair);
+
+        let instruction = transfer(&keypair.pubkey(), &Keypair::new().pubkey(), 1_000);
+        let message =
+            v0::Message::try_compile(&keypair.pubkey(), &[instruction], &[], Hash::new_unique())
+                .unwrap();
+        let transaction = VersionedTransaction {
+            signatures: vec![solana_sdk::signature::Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+
+        let signed =
+            ready(signer.sign_transaction(&bincode::serialize(&transaction).unwrap(), None))
+                .unwrap();
+        let signed: VersionedTransaction = bincode::deserialize(&signed[0]).unwrap();
+        assert!(signed.verify_with_results().iter().all(|valid| *valid));
+    }
+
+    #[test]
+    fn reject_invalid_transactions() {
+        let keypair = Keypair::new();
+        let signer = connected(&keypair);
+
+        let instruction = transfer(&Keypair::new().pubkey(), &keypair.pubkey(), 1_000);
+        let mut transaction = Transaction::new_with_payer(&[instruction], None);
+        transaction.message.recent_blockhash = Hash::new_unique();
+        let bytes = bincode::serialize(&transaction).unwrap();
+
+        assert_eq!(
+            ready(signer.sign_transaction(&bytes, None)).unwrap_err(),
+            WalletError::NotATransactionSigner
+        );
+        assert!(matches!(
+            ready(signer.sign_transaction(&bytes[..65], None)),
+            Err(WalletError::InvalidTransaction(_))
+        ));
+        assert!(matches!(
+            ready(signer.sign_transaction(&[0xff, 0xff, 0xff], None)),
+            Err(WalletError::InvalidTransaction(_))
+        ));
+
+        let mut unsupported_version = vec![1u8];
+        unsupported_version.extend_from_slice(&[0u8; 64]);
+        unsupported_version.push(0x81);
+        assert_eq!(
+            ready(signer.sign_transaction(&unsupported_version, None)).unwrap_err(),
+            WalletError::UnsupportedTransactionVersion
+        );
+    }
+
+    #[test]
+    fn standard_wallet_signer() {
+        let keypair = Keypair::new();
+        // The provider checks the connection, the signer signs every request
+        let signer = KeypairSigner::from_json(&keypair_json(&keypair)).unwrap();
+
+        assert_eq!(
+            StandardWalletSigner::public_key(&signer),
+            keypair.pubkey().to_bytes()
+        );
+
+        assert_eq!(
+            ready(StandardWalletSigner::approve_connection(&signer)),
+            Err(WalletError::AccountNotFound)
+        );
+        assert!(ready(StandardWalletSigner::approve_connection(&connected(
+            &keypair
+        )))
+        .is_ok());
+
+        let signature = ready(StandardWalletSigner::sign_message(&signer, b"Hello")).unwrap();
+        ed25519_dalek::VerifyingKey::from_bytes(&keypair.pubkey().to_bytes())
+            .unwrap()
+            .verify_strict(b"Hello", &ed25519_dalek::Signature::from_bytes(&signature))
+            .unwrap();
+
+        let mut transaction = Transaction::new_with_payer(
+            &[transfer(&keypair.pubkey(), &Pubkey::new_unique(), 1)],
+            Some(&keypair.pubkey()),
+        );
+        transaction.message.recent_blockhash = Hash::new_unique();
+
+        let signed = ready(StandardWalletSigner::sign_transaction(
+            &signer,
+            &bincode::serialize(&transaction).unwrap(),
+        ))
+        .unwrap();
+        let signed: Transaction = bincode::deserialize(&signed).unwrap();
+        signed.verify().unwrap();
+    }
+}
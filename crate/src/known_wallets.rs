@@ -0,0 +1,381 @@
+use crate::{WalletFilter, WalletIcon, WalletListing, WalletStorage};
+
+/// The browser extension stores a wallet can be downloaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExtensionStore {
+    /// The Chrome Web Store, also used by Brave and other Chromium based browsers
+    Chrome,
+    /// Firefox Browser Add-ons
+    Firefox,
+    /// Microsoft Edge Add-ons
+    Edge,
+    /// The Mac App Store for Safari extensions
+    Safari,
+}
+
+impl ExtensionStore {
+    /// Get the name of the extension store
+    pub fn display(&self) -> &str {
+        match self {
+            Self::Chrome => "Chrome Web Store",
+            Self::Firefox => "Firefox Browser Add-ons",
+            Self::Edge => "Microsoft Edge Add-ons",
+            Self::Safari => "Mac App Store",
+        }
+    }
+}
+
+impl core::fmt::Display for ExtensionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display())
+    }
+}
+
+/// A wallet known to the app which may or may not be installed in the browser
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct KnownWallet {
+    name: String,
+    icon: Option<WalletIcon>,
+    homepage: String,
+    extensions: Vec<(ExtensionStore, String)>,
+    mobile_deep_link: Option<String>,
+}
+
+impl KnownWallet {
+    /// Instantiate a known wallet from its name, which should be the same as the name
+    /// the wallet registers with, and the URL of its homepage
+    pub fn new(name: &str, homepage: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            homepage: homepage.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the icon displayed when the wallet is not installed
+    pub fn set_icon(&mut self, icon: WalletIcon) -> &mut Self {
+        self.icon.replace(icon);
+
+        self
+    }
+
+    /// Add the URL of the wallet in an extension store,
+    /// replacing any URL previously added for the same store
+    pub fn add_extension(&mut self, store: ExtensionStore, url: &str) -> &mut Self {
+        self.extensions.retain(|(existing, _)| existing != &store);
+        self.extensions.push((store, url.to_string()));
+
+        self
+    }
+
+    /// Set the base URL of the mobile deep links (universal links) of the wallet
+    pub fn set_mobile_deep_link(&mut self, base_url: &str) -> &mut Self {
+        self.mobile_deep_link.replace(base_url.to_string());
+
+        self
+    }
+
+    /// Get the name of the wallet
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Get the icon of the wallet
+    pub fn icon(&self) -> Option<&WalletIcon> {
+        self.icon.as_ref()
+    }
+
+    /// Get the URL of the homepage of the wallet
+    pub fn homepage(&self) -> &str {
+        self.homepage.as_str()
+    }
+
+    /// Get the URLs of the wallet in the extension stores
+    pub fn extensions(&self) -> &[(ExtensionStore, String)] {
+        self.extensions.as_slice()
+    }
+
+    /// Get the URL of the wallet in a certain extension store
+    pub fn extension(&self, store: ExtensionStore) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find_map(|(existing, url)| (existing == &store).then_some(url.as_str()))
+    }
+
+    /// Get the base URL of the mobile deep links of the wallet
+    pub fn mobile_deep_link(&self) -> Option<&str> {
+        self.mobile_deep_link.as_deref()
+    }
+}
+
+/// A registry of wallets known to the app. [KnownWallets::default] contains
+/// popular Solana wallets without icons since their artwork belongs to each wallet.
+/// The app can add its own wallets or replace the built-in entries using [KnownWallets::add],
+/// example to set the icon of a wallet from artwork published for that purpose.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KnownWallets(Vec<KnownWallet>);
+
+impl KnownWallets {
+    /// Instantiate a registry without any wallets
+    pub fn empty() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Add a wallet to the registry. A wallet with the same name, compared
+    /// case-insensitively, is replaced but keeps its position
+    pub fn add(&mut self, wallet: KnownWallet) -> &mut Self {
+        match self
+            .0
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(&wallet.name))
+        {
+            Some(existing) => *existing = wallet,
+            None => self.0.push(wallet),
+        }
+
+        self
+    }
+
+    /// Remove a wallet from the registry by its name
+    pub fn remove(&mut self, wallet_name: &str) -> &mut Self {
+        self.0
+            .retain(|existing| !existing.name.eq_ignore_ascii_case(wallet_name));
+
+        self
+    }
+
+    /// Get a wallet by its name
+    pub fn get(&self, wallet_name: &str) -> Option<&KnownWallet> {
+        self.0
+            .iter()
+            .find(|existing| existing.name.eq_ignore_ascii_case(wallet_name))
+    }
+
+    /// Get all the wallets in the registry
+    pub fn wallets(&self) -> &[KnownWallet] {
+        self.0.as_slice()
+    }
+
+    /// Merge the registry with the wallets registered in the `storage`.
+    /// The installed wallets are listed first as ordered by [WalletStorage::list_wallets]
+    /// followed by the known wallets that were not detected in the order they were added.
+    pub fn detect(&self, storage: &WalletStorage) -> Vec<WalletInstallState> {
+        let (installed, not_detected) = self
+            .detect_parts(storage.list_wallets(&WalletFilter::new()), |listing| {
+                listing.wallet().name()
+            });
+
+        installed
+            .into_iter()
+            .map(|(listing, known)| WalletInstallState::Installed {
+                listing: Box::new(listing),
+                known,
+            })
+            .chain(
+                not_detected
+                    .into_iter()
+                    .map(WalletInstallState::NotDetected),
+            )
+            .collect()
+    }
+
+    // The installed wallets with their registry entries in the order given
+    // and the known wallets that were not installed in the order they were added
+    fn detect_parts<L>(
+        &self,
+        installed: Vec<L>,
+        name: impl Fn(&L) -> &str,
+    ) -> (Vec<(L, Option<KnownWallet>)>, Vec<KnownWallet>) {
+        let not_detected = self
+            .0
+            .iter()
+            .filter(|known| {
+                !installed
+                    .iter()
+                    .any(|listing| name(listing).eq_ignore_ascii_case(&known.name))
+            })
+            .cloned()
+            .collect::<Vec<KnownWallet>>();
+
+        let installed = installed
+            .into_iter()
+            .map(|listing| {
+                let known = self.get(name(&listing)).cloned();
+
+                (listing, known)
+            })
+            .collect();
+
+        (installed, not_detected)
+    }
+}
+
+impl Default for KnownWallets {
+    fn default() -> Self {
+        let mut phantom = KnownWallet::new("Phantom", "https://phantom.com");
+        phantom
+            .add_extension(
+                ExtensionStore::Chrome,
+                "https://chromewebstore.google.com/detail/phantom/bfnaelmomeimhlpmgjnjophhpkkoljpa",
+            )
+            .add_extension(
+                ExtensionStore::Firefox,
+                "https://addons.mozilla.org/firefox/addon/phantom-app/",
+            )
+            .set_mobile_deep_link("https://phantom.app/ul/");
+
+        let mut solflare = KnownWallet::new("Solflare", "https://solflare.com");
+        solflare
+            .add_extension(
+                ExtensionStore::Chrome,
+                "https://chromewebstore.google.com/detail/solflare-wallet/bhhhlbepdkbapadjdnnojkbgioiodbic",
+            )
+            .add_extension(
+                ExtensionStore::Firefox,
+                "https://addons.mozilla.org/firefox/addon/solflare-wallet/",
+            )
+            .set_mobile_deep_link("https://solflare.com/ul/");
+
+        let mut backpack = KnownWallet::new("Backpack", "https://backpack.app");
+        backpack
+            .add_extension(
+                ExtensionStore::Chrome,
+                "https://chromewebstore.google.com/detail/backpack/aflkmfhebedbjioipglgcbcmnbpgliof",
+            )
+            .set_mobile_deep_link("https://backpack.app/ul/");
+
+        let mut registry = Self::empty();
+        registry.add(phantom).add(solflare).add(backpack);
+
+        registry
+    }
+}
+
+/// The install state of a wallet as resolved by [KnownWallets::detect]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum WalletInstallState {
+    /// The wallet registered itself with the app
+    Installed {
+        /// The registered wallet
+        listing: Box<WalletListing>,
+        /// The entry of the wallet in the registry if the wallet is known
+        known: Option<KnownWallet>,
+    },
+    /// The wallet is known but it did not register itself with the app
+    NotDetected(KnownWallet),
+}
+
+impl WalletInstallState {
+    /// Get the name of the wallet
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Installed { listing, .. } => listing.wallet().name(),
+            Self::NotDetected(known) => known.name(),
+        }
+    }
+
+    /// Get the icon of the wallet, preferring the icon the wallet registered with
+    pub fn icon(&self) -> Option<&WalletIcon> {
+        match self {
+            Self::Installed { listing, known } => listing
+                .wallet()
+                .icon()
+                .or(known.as_ref().and_then(|known| known.icon())),
+            Self::NotDetected(known) => known.icon(),
+        }
+    }
+
+    /// Get the entry of the wallet in the registry if the wallet is known
+    pub fn known(&self) -> Option<&KnownWallet> {
+        match self {
+            Self::Installed { known, .. } => known.as_ref(),
+            Self::NotDetected(known) => Some(known),
+        }
+    }
+
+    /// Whether the wallet is installed
+    pub fn is_installed(&self) -> bool {
+        matches!(self, Self::Installed { .. })
+    }
+}
+
+#[cfg(test)]
+mod known_wallets_tests {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        let mut registry = KnownWallets::default();
+        assert!(registry.get("phantom").is_some());
+
+        let mut custom = KnownWallet::new("phantom", "https://example.com");
+        custom
+            .add_extension(ExtensionStore::Edge, "https://example.com/edge")
+            .add_extension(ExtensionStore::Edge, "https://example.com/edge/v2");
+        registry.add(custom.clone());
+
+        assert_eq!(registry.wallets()[0], custom);
+        assert_eq!(custom.extensions().len(), 1);
+        assert_eq!(
+            custom.extension(ExtensionStore::Edge),
+            Some("https://example.com/edge/v2")
+        );
+        assert_eq!(custom.extension(ExtensionStore::Chrome), None);
+
+        registry.remove("PHANTOM");
+        assert!(registry.get("Phantom").is_none());
+    }
+
+    #[test]
+    fn detect_without_registered_wallets() {
+        let registry = KnownWallets::default();
+        let states = registry.detect(&WalletStorage::default());
+
+        assert_eq!(states.len(), registry.wallets().len());
+        assert!(states.iter().all(|state| !state.is_installed()));
+        assert_eq!(states[1].name(), "Solflare");
+        assert_eq!(
+            states[1].known().and_then(|known| known.mobile_deep_link()),
+            Some("https://solflare.com/ul/")
+        );
+        assert!(states.iter().all(|state| state.icon().is_none()));
+    }
+
+    #[test]
+    fn icon_from_app() {
+        let mut registry = KnownWallets::default();
+        let icon = WalletIcon("data:image/svg+xml;base64,PHN2Zz48L3N2Zz4=".into());
+
+        let mut phantom = registry.get("Phantom").unwrap().clone();
+        phantom.set_icon(icon.clone());
+        registry.add(phantom);
+
+        let states = registry.detect(&WalletStorage::default());
+        assert_eq!(states[0].name(), "Phantom");
+        assert_eq!(states[0].icon(), Some(&icon));
+        assert!(states[1..].iter().all(|state| state.icon().is_none()));
+    }
+
+    #[test]
+    fn detect_with_registered_wallets() {
+        let registry = KnownWallets::default();
+
+        // The installed wallets as ordered by `WalletStorage::list_wallets`
+        let (installed, not_detected) =
+            registry.detect_parts(vec!["Custom", "solflare"], |name| name);
+
+        assert_eq!(installed.len(), 2);
+        assert_eq!(installed[0], ("Custom", None));
+        assert_eq!(installed[1].0, "solflare");
+        assert_eq!(installed[1].1.as_ref(), registry.get("Solflare"));
+
+        // The known wallets that are not installed follow in the order they were added
+        assert_eq!(
+            not_detected
+                .iter()
+                .map(|known| known.name())
+                .collect::<Vec<_>>(),
+            ["Phantom", "Backpack"]
+        );
+    }
+}
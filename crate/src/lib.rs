@@ -5,6 +5,9 @@
 )]
 #![doc = include_str!(concat!("../", std::env!("CARGO_PKG_README")))]
 
+#[macro_use]
+mod logging;
+
 mod adapter;
 pub use adapter::*;
 
@@ -20,6 +23,9 @@ pub use utils::*;
 mod events;
 pub use events::*;
 
+mod broadcast;
+pub use broadcast::*;
+
 mod constants;
 pub use constants::*;
 
@@ -29,15 +35,54 @@ pub use wallet_ser_der::*;
 mod storage;
 pub use storage::*;
 
+mod known_wallets;
+pub use known_wallets::*;
+
+#[cfg(feature = "mwa")]
+mod mobile_wallet_adapter;
+#[cfg(feature = "mwa")]
+pub use mobile_wallet_adapter::*;
+
+#[cfg(feature = "deeplink")]
+mod deeplink;
+#[cfg(feature = "deeplink")]
+pub use deeplink::*;
+
+mod keypair_signer;
+pub use keypair_signer::*;
+
+mod wallet_provider;
+pub use wallet_provider::*;
+
+mod solana_pay;
+pub use solana_pay::*;
+
+#[cfg(feature = "burner")]
+mod burner;
+#[cfg(feature = "burner")]
+pub use burner::*;
+
+mod diagnostics;
+pub use diagnostics::*;
+
+#[cfg(feature = "serde")]
+mod serde_support;
+
+#[cfg(test)]
+mod test_utils;
+
 // Re-export of crates
 pub use async_channel;
 pub use blake3;
 pub use bs58;
 pub use ed25519_dalek;
+pub use futures_core;
 pub use getrandom;
 pub use humantime;
 pub use rand_chacha;
 pub use rand_core;
+#[cfg(feature = "serde")]
+pub use serde;
 pub use thiserror;
 pub use wasm_bindgen_futures;
 pub use web_sys;
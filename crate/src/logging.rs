@@ -0,0 +1,62 @@
+use std::future::Future;
+
+use crate::WalletResult;
+
+/// Emit a diagnostic at the given level (`trace`, `debug`, `info`, `warn` or `error`).
+/// The diagnostic is routed through `tracing` when the `tracing` feature is enabled,
+/// through `log` when the `logging` feature is enabled, otherwise it is discarded.
+/// The target is the module path of the caller.
+macro_rules! wallet_log {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::$level!($($arg)+);
+
+        #[cfg(all(feature = "logging", not(feature = "tracing")))]
+        ::log::$level!($($arg)+);
+
+        #[cfg(not(any(feature = "logging", feature = "tracing")))]
+        {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+/// Run a request sent to a browser wallet inside a `wallet_request` span
+/// carrying the `wallet` name and the wallet standard `feature` identifier
+/// of the request. The outcome of the request is logged within the span.
+pub(crate) async fn wallet_request<T, F>(wallet: &str, feature: &str, request: F) -> WalletResult<T>
+where
+    F: Future<Output = WalletResult<T>>,
+{
+    let request = async move {
+        wallet_log!(debug, "`{feature}` request sent to `{wallet}` wallet");
+
+        let outcome = request.await;
+
+        match outcome.as_ref() {
+            Ok(_) => wallet_log!(debug, "`{feature}` request to `{wallet}` wallet succeeded"),
+            Err(error) if error.is_user_rejected() => {
+                wallet_log!(
+                    info,
+                    "`{feature}` request to `{wallet}` wallet was rejected by the user: {error}"
+                )
+            }
+            Err(error) => {
+                wallet_log!(
+                    warn,
+                    "`{feature}` request to `{wallet}` wallet failed: {error}"
+                )
+            }
+        }
+
+        outcome
+    };
+
+    #[cfg(feature = "tracing")]
+    let request = tracing::Instrument::instrument(
+        request,
+        tracing::info_span!("wallet_request", wallet, feature),
+    );
+
+    request.await
+}
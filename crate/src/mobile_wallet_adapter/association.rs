@@ -0,0 +1,129 @@
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use p256::{
+    ecdsa::{signature::Signer, Signature, SigningKey},
+    SecretKey,
+};
+
+use crate::Utils;
+
+/// The scheme of the association URIs handled by Mobile Wallet Adapter wallets
+pub const MWA_ASSOCIATION_SCHEME: &str = "solana-wallet:";
+/// The path of the association URI for a wallet running on the same device
+pub const MWA_LOCAL_ASSOCIATION_PATH: &str = "/v1/associate/local";
+/// The websocket subprotocol of the Mobile Wallet Adapter protocol
+pub const MWA_WEBSOCKET_PROTOCOL: &str = "com.solana.mobilewalletadapter.v1";
+/// The lowest port a wallet can listen on for a local association
+pub const MWA_MIN_PORT: u16 = 49152;
+
+/// The association of the app with a Mobile Wallet Adapter wallet on the same device.
+/// The association keypair is a P-256 keypair generated for each association,
+/// its public key is shared with the wallet in the association URI and
+/// it signs the app's ephemeral public key during the session handshake.
+#[derive(Clone)]
+pub struct MwaAssociation {
+    keypair: SigningKey,
+    port: u16,
+}
+
+impl MwaAssociation {
+    /// Generate a new association keypair and a random port in the range
+    /// `49152..=65535` for the wallet to listen on
+    pub fn new() -> Self {
+        let random = Utils::rand_32bytes();
+        let port = MWA_MIN_PORT
+            + u16::from_be_bytes([random[0], random[1]]) % (u16::MAX - MWA_MIN_PORT + 1);
+
+        Self::new_with_port(port)
+    }
+
+    /// Generate a new association keypair for a wallet listening on a certain `port`
+    pub fn new_with_port(port: u16) -> Self {
+        Self {
+            keypair: SigningKey::from(p256_secret_key()),
+            port,
+        }
+    }
+
+    /// Get the port the wallet listens on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Get the association public key encoded as an uncompressed X9.62 point of 65 bytes
+    pub fn public_key(&self) -> Vec<u8> {
+        self.keypair
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Get the association URI, example
+    /// `solana-wallet:/v1/associate/local?association=<public key>&port=<port>`.
+    /// Opening this URI launches a Mobile Wallet Adapter wallet
+    pub fn uri(&self) -> String {
+        format!(
+            "{MWA_ASSOCIATION_SCHEME}{MWA_LOCAL_ASSOCIATION_PATH}?{}",
+            self.query()
+        )
+    }
+
+    /// Get the association URI for a wallet that returned a `wallet_uri_base`
+    /// when the app was authorized, example `https://wallet.example`
+    pub fn uri_with_base(&self, wallet_uri_base: &str) -> String {
+        format!(
+            "{}{MWA_LOCAL_ASSOCIATION_PATH}?{}",
+            wallet_uri_base.trim_end_matches('/'),
+            self.query()
+        )
+    }
+
+    /// Get the URL of the websocket served by the wallet
+    pub fn websocket_url(&self) -> String {
+        format!("ws://localhost:{}/solana-wallet", self.port)
+    }
+
+    /// Sign the app's ephemeral public key, returning the signature
+    /// as the 64 bytes of `r` and `s`
+    pub(crate) fn sign(&self, message: &[u8]) -> [u8; 64] {
+        let signature: Signature = self.keypair.sign(message);
+
+        signature.to_bytes().into()
+    }
+
+    fn query(&self) -> String {
+        format!(
+            "association={}&port={}",
+            BASE64_URL_SAFE_NO_PAD.encode(self.public_key()),
+            self.port
+        )
+    }
+}
+
+impl Default for MwaAssociation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for MwaAssociation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MwaAssociation")
+            .field(
+                "public_key",
+                &BASE64_URL_SAFE_NO_PAD.encode(self.public_key()),
+            )
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+/// Generate a P-256 secret key from random bytes. Random bytes that
+/// are not a valid scalar for the curve are discarded.
+pub(crate) fn p256_secret_key() -> SecretKey {
+    loop {
+        if let Ok(secret_key) = SecretKey::from_slice(&Utils::rand_32bytes()) {
+            return secret_key;
+        }
+    }
+}
@@ -0,0 +1,701 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    Cluster, MwaAssociation, MwaHandshake, MwaSession, MwaTransport, PublicKeyBytes,
+    SignedMessageOutput, Utils, WalletError, WalletResult,
+};
+
+/// The identity of the app shown by the wallet when the app requests authorization
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct AppIdentity {
+    /// The name of the app
+    pub name: String,
+    /// The URI of the app, example `https://example.com`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// The URI of the icon of the app relative to the `uri`, example `favicon.ico`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl AppIdentity {
+    /// Instantiate the identity of the app from its name
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the URI of the app
+    pub fn set_uri(&mut self, uri: &str) -> &mut Self {
+        self.uri.replace(uri.to_string());
+
+        self
+    }
+
+    /// Set the URI of the icon of the app relative to the URI of the app
+    pub fn set_icon(&mut self, icon: &str) -> &mut Self {
+        self.icon.replace(icon.to_string());
+
+        self
+    }
+}
+
+/// An account authorized by a Mobile Wallet Adapter wallet
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MwaAccount {
+    public_key: PublicKeyBytes,
+    label: Option<String>,
+    chains: Vec<String>,
+}
+
+impl MwaAccount {
+    /// Get the Ed25519 public key of the account
+    pub fn public_key(&self) -> PublicKeyBytes {
+        self.public_key
+    }
+
+    /// Get the Base58 address of the account
+    pub fn address(&self) -> String {
+        bs58::encode(self.public_key).into_string()
+    }
+
+    /// Get the shortened Base58 address of the account, example `FXdl...RGd4`
+    pub fn shorten_address(&self) -> WalletResult<String> {
+        Ok(Utils::shorten_base58(&self.address())?.to_string())
+    }
+
+    /// Get the label of the account
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Get the chains supported by the account, example `solana:mainnet`
+    pub fn chains(&self) -> &[String] {
+        self.chains.as_slice()
+    }
+}
+
+/// The authorization granted to the app by a Mobile Wallet Adapter wallet
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MwaAuthorization {
+    auth_token: String,
+    accounts: Vec<MwaAccount>,
+    wallet_uri_base: Option<String>,
+}
+
+impl MwaAuthorization {
+    /// The token used to reauthorize the app in later sessions
+    pub fn auth_token(&self) -> &str {
+        self.auth_token.as_str()
+    }
+
+    /// The accounts authorized by the wallet
+    pub fn accounts(&self) -> &[MwaAccount] {
+        self.accounts.as_slice()
+    }
+
+    /// The base URI to use for the association URI in later sessions,
+    /// see [MwaAssociation::uri_with_base]
+    pub fn wallet_uri_base(&self) -> Option<&str> {
+        self.wallet_uri_base.as_deref()
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthorizationResult {
+    auth_token: String,
+    accounts: Vec<AccountResult>,
+    wallet_uri_base: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AccountResult {
+    address: String,
+    label: Option<String>,
+    #[serde(default)]
+    chains: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SignedPayloadsResult {
+    signed_payloads: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    id: Option<u64>,
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// A Mobile Wallet Adapter client which talks to a wallet over an encrypted session.
+/// It exposes the Mobile Wallet Adapter methods, `authorize`, `reauthorize`, `deauthorize`,
+/// `sign_transactions` and `sign_messages`, and the same methods as a browser
+/// [crate::Wallet] for connecting and signing using the authorized account.
+///
+/// #### Example
+/// ```rust,no_run
+/// # async fn example() -> wallet_adapter::WalletResult<()> {
+/// use wallet_adapter::{AppIdentity, Cluster, MwaAssociation, MwaClient, WebSocketTransport};
+///
+/// let association = MwaAssociation::new();
+/// // Open `association.uri()` to launch the wallet, then
+/// let transport = WebSocketTransport::connect_with_retry(
+///     &association.websocket_url(),
+///     30,
+///     std::time::Duration::from_millis(150),
+/// )
+/// .await?;
+///
+/// let mut client = MwaClient::establish(transport, &association, AppIdentity::new("Example")).await?;
+/// let account = client.connect(Cluster::DevNet).await?;
+/// let signed = client.sign_message(b"Hello Solana").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MwaClient<T: MwaTransport> {
+    transport: T,
+    session: MwaSession,
+    identity: AppIdentity,
+    next_id: u64,
+    authorization: Option<MwaAuthorization>,
+}
+
+impl<T: MwaTransport> MwaClient<T> {
+    /// Perform the session handshake with the wallet over the `transport`
+    pub async fn establish(
+        mut transport: T,
+        association: &MwaAssociation,
+        identity: AppIdentity,
+    ) -> WalletResult<Self> {
+        let handshake = MwaHandshake::new(association);
+        transport.send(handshake.hello_request().to_vec()).await?;
+
+        let session = handshake.finish(&transport.receive().await?)?;
+
+        Ok(Self {
+            transport,
+            session,
+            identity,
+            next_id: 1,
+            authorization: Option::None,
+        })
+    }
+
+    /// Request authorization of the app for the `cluster`. A previous `auth_token`
+    /// can be provided to authorize the app without prompting the user again
+    pub async fn authorize(
+        &mut self,
+        cluster: Cluster,
+        auth_token: Option<&str>,
+    ) -> WalletResult<&MwaAuthorization> {
+        let mut params = json!({
+            "identity": self.identity,
+            "chain": cluster.chain(),
+        });
+        if let Some(auth_token) = auth_token {
+            params["auth_token"] = json!(auth_token);
+        }
+
+        let result = self.request("authorize", params).await?;
+
+        self.set_authorization(result)
+    }
+
+    /// Reauthorize the app using the `auth_token` of a previous authorization
+    pub async fn reauthorize(&mut self, auth_token: &str) -> WalletResult<&MwaAuthorization> {
+        let params = json!({
+            "identity": self.identity,
+            "auth_token": auth_token,
+        });
+
+        let result = self.request("reauthorize", params).await?;
+
+        self.set_authorization(result)
+    }
+
+    /// Revoke the authorization of the app
+    pub async fn deauthorize(&mut self) -> WalletResult<()> {
+        let auth_token = self.authorization()?.auth_token.clone();
+
+        self.request("deauthorize", json!({ "auth_token": auth_token }))
+            .await?;
+        self.authorization.take();
+
+        Ok(())
+    }
+
+    /// Request the wallet to sign the serialized `transactions`,
+    /// returning the signed transactions in the same order
+    pub async fn sign_transactions(
+        &mut self,
+        transactions: &[&[u8]],
+    ) -> WalletResult<Vec<Vec<u8>>> {
+        let params = json!({ "payloads": encode_payloads(transactions) });
+
+        self.signed_payloads("sign_transactions", params, transactions.len())
+            .await
+    }
+
+    /// Request the wallet to sign the `messages` using the accounts with the `addresses`,
+    /// returning each message followed by its signatures
+    pub async fn sign_messages(
+        &mut self,
+        messages: &[&[u8]],
+        addresses: &[PublicKeyBytes],
+    ) -> WalletResult<Vec<Vec<u8>>> {
+        let addresses = addresses
+            .iter()
+            .map(|address| address.as_slice())
+            .collect::<Vec<&[u8]>>();
+
+        let params = json!({
+            "addresses": encode_payloads(&addresses),
+            "payloads": encode_payloads(messages),
+        });
+
+        self.signed_payloads("sign_messages", params, messages.len())
+            .await
+    }
+
+    /// Authorize the app for the `cluster` and get the first authorized account
+    pub async fn connect(&mut self, cluster: Cluster) -> WalletResult<MwaAccount> {
+        self.authorize(cluster, Option::None).await?;
+
+        self.connected_account().cloned()
+    }
+
+    /// Same as [Self::deauthorize]
+    pub async fn disconnect(&mut self) -> WalletResult<()> {
+        self.deauthorize().await
+    }
+
+    /// Sign a message using the connected account and verify the signature
+    pub async fn sign_message(&mut self, message: &[u8]) -> WalletResult<SignedMessageOutput> {
+        let public_key = self.connected_account()?.public_key;
+
+        let signed_message = self
+            .sign_messages(&[message], &[public_key])
+            .await?
+            .remove(0);
+
+        let signature = signed_message
+            .strip_prefix(message)
+            .ok_or(WalletError::SignedMessageMismatch)?;
+        let signature = Utils::to64byte_array(signature.get(..64).unwrap_or(signature))?;
+
+        Utils::verify_signature(
+            Utils::public_key(public_key)?,
+            message,
+            Utils::signature(signature),
+        )?;
+
+        Ok(SignedMessageOutput::new(message, public_key, signature))
+    }
+
+    /// Sign a serialized transaction using the connected account
+    pub async fn sign_transaction(&mut self, transaction_bytes: &[u8]) -> WalletResult<Vec<u8>> {
+        self.connected_account()?;
+
+        Ok(self
+            .sign_transactions(&[transaction_bytes])
+            .await?
+            .remove(0))
+    }
+
+    /// Get the current authorization
+    pub fn authorization(&self) -> WalletResult<&MwaAuthorization> {
+        self.authorization
+            .as_ref()
+            .ok_or(WalletError::AccountNotFound)
+    }
+
+    /// Get the accounts authorized by the wallet
+    pub fn accounts(&self) -> &[MwaAccount] {
+        self.authorization
+            .as_ref()
+            .map(|authorization| authorization.accounts())
+            .unwrap_or_default()
+    }
+
+    /// Get the first authorized account
+    pub fn connected_account(&self) -> WalletResult<&MwaAccount> {
+        self.accounts().first().ok_or(WalletError::AccountNotFound)
+    }
+
+    /// Check whether the wallet authorized the app
+    pub fn is_connected(&self) -> bool {
+        self.authorization.is_some()
+    }
+
+    /// Get the identity of the app
+    pub fn identity(&self) -> &AppIdentity {
+        &self.identity
+    }
+
+    /// Get the transport used by the client
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    fn set_authorization(&mut self, result: Value) -> WalletResult<&MwaAuthorization> {
+        let result = parse_result::<AuthorizationResult>(result)?;
+
+        let accounts = result
+            .accounts
+            .into_iter()
+            .map(|account| {
+                Ok(MwaAccount {
+                    public_key: Utils::to32byte_array(&decode_payload(&account.address)?)?,
+                    label: account.label,
+                    chains: account.chains,
+                })
+            })
+            .collect::<WalletResult<Vec<MwaAccount>>>()?;
+
+        if accounts.is_empty() {
+            return Err(WalletError::ConnectHasNoAccounts);
+        }
+
+        Ok(self.authorization.insert(MwaAuthorization {
+            auth_token: result.auth_token,
+            accounts,
+            wallet_uri_base: result.wallet_uri_base,
+        }))
+    }
+
+    async fn signed_payloads(
+        &mut self,
+        method: &str,
+        params: Value,
+        expected: usize,
+    ) -> WalletResult<Vec<Vec<u8>>> {
+        let result = parse_result::<SignedPayloadsResult>(self.request(method, params).await?)?;
+
+        if result.signed_payloads.len() != expected {
+            return Err(WalletError::MwaInvalidMessage(format!(
+                "Expected `{expected}` signed payloads from `{method}` but the wallet returned `{}`",
+                result.signed_payloads.len()
+            )));
+        }
+
+        result
+            .signed_payloads
+            .iter()
+            .map(|payload| decode_payload(payload))
+            .collect()
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> WalletResult<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+
+        let message = self.session.encrypt(request.to_string().as_bytes())?;
+        self.transport.send(message).await?;
+
+        let message = self.transport.receive().await?;
+        let response = serde_json::from_slice::<RpcResponse>(&self.session.decrypt(&message)?)
+            .map_err(|error| WalletError::MwaInvalidMessage(error.to_string()))?;
+
+        if response.id != Some(id) {
+            return Err(WalletError::MwaInvalidMessage(format!(
+                "Expected a response to request `{id}`"
+            )));
+        }
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(WalletError::WalletRequestError {
+                code: error.code,
+                message: error.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(WalletError::MwaInvalidMessage(format!(
+                "The response to `{method}` has no result"
+            ))),
+        }
+    }
+}
+
+fn parse_result<R: for<'de> Deserialize<'de>>(result: Value) -> WalletResult<R> {
+    serde_json::from_value(result)
+        .map_err(|error| WalletError::MwaInvalidMessage(error.to_string()))
+}
+
+fn encode_payloads(payloads: &[&[u8]]) -> Vec<String> {
+    payloads
+        .iter()
+        .map(|payload| BASE64_STANDARD.encode(payload))
+        .collect()
+}
+
+fn decode_payload(payload: &str) -> WalletResult<Vec<u8>> {
+    BASE64_STANDARD
+        .decode(payload)
+        .map_err(|error| WalletError::MwaInvalidMessage(error.to_string()))
+}
+
+#[cfg(test)]
+mod mwa_tests {
+    use std::collections::VecDeque;
+
+    use ed25519_dalek::{Signer, SigningKey};
+    use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+
+    use super::*;
+    use crate::{
+        encode_public_key, p256_secret_key, test_utils::ready, MWA_MIN_PORT, P256_PUBLIC_KEY_LENGTH,
+    };
+
+    /// A wallet endpoint that performs the wallet side of the protocol in memory
+    struct StandInWallet {
+        association_public_key: Vec<u8>,
+        session: Option<MwaSession>,
+        keypair: SigningKey,
+        replies: VecDeque<Vec<u8>>,
+    }
+
+    impl StandInWallet {
+        fn new(association: &MwaAssociation) -> Self {
+            Self {
+                association_public_key: association.public_key(),
+                session: Option::None,
+                keypair: SigningKey::from_bytes(&[7u8; 32]),
+                replies: VecDeque::new(),
+            }
+        }
+
+        fn hello(&mut self, hello_request: &[u8]) -> Vec<u8> {
+            let (app_public_key, signature) = hello_request.split_at(P256_PUBLIC_KEY_LENGTH);
+
+            VerifyingKey::from_sec1_bytes(&self.association_public_key)
+                .unwrap()
+                .verify(app_public_key, &Signature::from_slice(signature).unwrap())
+                .unwrap();
+
+            let secret_key = p256_secret_key();
+            self.session = Some(
+                MwaSession::derive(&secret_key, app_public_key, &self.association_public_key)
+                    .unwrap(),
+            );
+
+            encode_public_key(&secret_key.public_key())
+        }
+
+        fn handle(&self, method: &str, params: &Value) -> Result<Value, (i64, &str)> {
+            let payloads = |key: &str| {
+                params[key]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|payload| decode_payload(payload.as_str().unwrap()).unwrap())
+                    .collect::<Vec<Vec<u8>>>()
+            };
+            let authorized = json!({
+                "auth_token": "stand-in-token",
+                "accounts": [{
+                    "address": BASE64_STANDARD.encode(self.keypair.verifying_key().as_bytes()),
+                    "label": "Stand-in",
+                    "chains": [params["chain"].as_str().unwrap_or("solana:devnet")],
+                }],
+                "wallet_uri_base": "https://wallet.example",
+            });
+
+            match method {
+                "authorize" if params["auth_token"] == "revoked" => Err((-1, "Not authorized")),
+                "authorize" | "reauthorize" => Ok(authorized),
+                "deauthorize" => Ok(json!({})),
+                "sign_transactions" => Ok(json!({
+                    "signed_payloads": payloads("payloads")
+                        .iter()
+                        .map(|payload| {
+                            let mut signed = self.keypair.sign(payload).to_bytes().to_vec();
+                            signed.extend_from_slice(payload);
+                            BASE64_STANDARD.encode(signed)
+                        })
+                        .collect::<Vec<String>>(),
+                })),
+                "sign_messages" => Ok(json!({
+                    "signed_payloads": payloads("payloads")
+                        .iter()
+                        .map(|payload| {
+                            let mut signed = payload.clone();
+                            signed.extend_from_slice(&self.keypair.sign(payload).to_bytes());
+                            BASE64_STANDARD.encode(signed)
+                        })
+                        .collect::<Vec<String>>(),
+                })),
+                _ => Err((-32601, "Method not found")),
+            }
+        }
+    }
+
+    impl MwaTransport for StandInWallet {
+        async fn send(&mut self, message: Vec<u8>) -> WalletResult<()> {
+            let Some(mut session) = self.session.take() else {
+                let hello_response = self.hello(&message);
+                self.replies.push_back(hello_response);
+
+                return Ok(());
+            };
+
+            let request =
+                serde_json::from_slice::<Value>(&session.decrypt(&message).unwrap()).unwrap();
+
+            let response = match self
+                .handle(request["method"].as_str().unwrap(), &request["params"])
+            {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+                Err((code, message)) => json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": { "code": code, "message": message },
+                }),
+            };
+
+            self.replies
+                .push_back(session.encrypt(response.to_string().as_bytes()).unwrap());
+            self.session.replace(session);
+
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> WalletResult<Vec<u8>> {
+            self.replies
+                .pop_front()
+                .ok_or(WalletError::MwaTransportError("No reply".to_string()))
+        }
+    }
+
+    fn client() -> MwaClient<StandInWallet> {
+        let association = MwaAssociation::new_with_port(50_000);
+
+        ready(MwaClient::establish(
+            StandInWallet::new(&association),
+            &association,
+            AppIdentity::new("Stand-in App"),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn association_uri() {
+        let association = MwaAssociation::new();
+
+        assert!(association.port() >= MWA_MIN_PORT);
+        assert_eq!(association.public_key().len(), P256_PUBLIC_KEY_LENGTH);
+        assert!(association
+            .uri()
+            .starts_with("solana-wallet:/v1/associate/local?association="));
+        assert!(association
+            .uri_with_base("https://wallet.example/")
+            .starts_with("https://wallet.example/v1/associate/local?association="));
+        assert!(association
+            .uri()
+            .ends_with(&format!("&port={}", association.port())));
+        assert_eq!(
+            association.websocket_url(),
+            format!("ws://localhost:{}/solana-wallet", association.port())
+        );
+    }
+
+    #[test]
+    fn session_rejects_replayed_and_tampered_messages() {
+        let association = MwaAssociation::new();
+        let app_key = p256_secret_key();
+        let wallet_key = p256_secret_key();
+
+        let mut app = MwaSession::derive(
+            &app_key,
+            &encode_public_key(&wallet_key.public_key()),
+            &association.public_key(),
+        )
+        .unwrap();
+        let mut wallet = MwaSession::derive(
+            &wallet_key,
+            &encode_public_key(&app_key.public_key()),
+            &association.public_key(),
+        )
+        .unwrap();
+
+        let message = app.encrypt(b"hello").unwrap();
+        assert_eq!(&message[..4], &1u32.to_be_bytes());
+        assert_eq!(wallet.decrypt(&message).unwrap(), b"hello");
+        assert!(wallet.decrypt(&message).is_err());
+
+        let mut tampered = app.encrypt(b"hello").unwrap();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(wallet.decrypt(&tampered).is_err());
+    }
+
+    #[test]
+    fn authorize_and_sign() {
+        let mut client = client();
+        assert!(!client.is_connected());
+        assert_eq!(
+            ready(client.sign_message(b"Hello")),
+            Err(WalletError::AccountNotFound)
+        );
+
+        let account = ready(client.connect(Cluster::MainNet)).unwrap();
+        assert_eq!(account.label(), Some("Stand-in"));
+        assert_eq!(account.chains(), ["solana:mainnet".to_string()]);
+        assert_eq!(
+            client.authorization().unwrap().wallet_uri_base(),
+            Some("https://wallet.example")
+        );
+
+        let signed = ready(client.sign_message(b"Hello")).unwrap();
+        assert_eq!(signed.message_bytes(), b"Hello");
+        assert_eq!(
+            signed.public_key().unwrap().to_bytes(),
+            account.public_key()
+        );
+
+        let signed_transaction = ready(client.sign_transaction(b"transaction")).unwrap();
+        assert!(signed_transaction.ends_with(b"transaction"));
+
+        let auth_token = client.authorization().unwrap().auth_token().to_string();
+        ready(client.disconnect()).unwrap();
+        assert!(!client.is_connected());
+
+        ready(client.reauthorize(&auth_token)).unwrap();
+        assert!(client.is_connected());
+    }
+
+    #[test]
+    fn wallet_errors() {
+        let mut client = client();
+
+        assert_eq!(
+            ready(client.authorize(Cluster::DevNet, Some("revoked"))),
+            Err(WalletError::WalletRequestError {
+                code: -1,
+                message: "Not authorized".to_string()
+            })
+        );
+        assert_eq!(
+            ready(client.request("get_capabilities", json!({})))
+                .unwrap_err()
+                .code(),
+            Some(-32601)
+        );
+    }
+}
@@ -0,0 +1,11 @@
+mod association;
+pub use association::*;
+
+mod session;
+pub use session::*;
+
+mod transport;
+pub use transport::*;
+
+mod client;
+pub use client::*;
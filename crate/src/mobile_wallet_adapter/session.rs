@@ -0,0 +1,201 @@
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes128Gcm, KeyInit, Nonce,
+};
+use hkdf::Hkdf;
+use p256::{ecdh::diffie_hellman, elliptic_curve::sec1::ToEncodedPoint, PublicKey, SecretKey};
+use sha2::Sha256;
+
+use crate::{p256_secret_key, MwaAssociation, Utils, WalletError, WalletResult};
+
+/// The length of an uncompressed P-256 public key
+pub(crate) const P256_PUBLIC_KEY_LENGTH: usize = 65;
+const SEQUENCE_NUMBER_LENGTH: usize = 4;
+const IV_LENGTH: usize = 12;
+
+/// The app side of the Mobile Wallet Adapter session handshake.
+/// The app sends [Self::hello_request] to the wallet and derives
+/// the [MwaSession] from the `HELLO_RSP` sent back by the wallet.
+pub struct MwaHandshake {
+    secret_key: SecretKey,
+    association_public_key: Vec<u8>,
+    hello_request: Vec<u8>,
+}
+
+impl MwaHandshake {
+    /// Generate the ephemeral P-256 keypair of the app and sign its
+    /// public key using the association keypair
+    pub fn new(association: &MwaAssociation) -> Self {
+        let secret_key = p256_secret_key();
+
+        let mut hello_request = encode_public_key(&secret_key.public_key());
+        hello_request.extend_from_slice(&association.sign(&hello_request));
+
+        Self {
+            secret_key,
+            association_public_key: association.public_key(),
+            hello_request,
+        }
+    }
+
+    /// The `HELLO_REQ` message, the app's ephemeral public key
+    /// followed by its signature by the association keypair
+    pub fn hello_request(&self) -> &[u8] {
+        self.hello_request.as_slice()
+    }
+
+    /// Derive the encrypted session from the `HELLO_RSP` message of the wallet
+    /// which starts with the wallet's ephemeral public key
+    pub fn finish(self, hello_response: &[u8]) -> WalletResult<MwaSession> {
+        let wallet_public_key =
+            hello_response
+                .get(..P256_PUBLIC_KEY_LENGTH)
+                .ok_or(WalletError::MwaHandshakeFailed(
+                    "The `HELLO_RSP` message is too short".to_string(),
+                ))?;
+
+        MwaSession::derive(
+            &self.secret_key,
+            wallet_public_key,
+            &self.association_public_key,
+        )
+    }
+}
+
+impl core::fmt::Debug for MwaHandshake {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MwaHandshake").finish_non_exhaustive()
+    }
+}
+
+/// An encrypted Mobile Wallet Adapter session. Each message is encrypted
+/// using AES-128-GCM and framed as a 4 byte big-endian sequence number,
+/// a 12 byte random IV and the ciphertext. The sequence number is the
+/// additional authenticated data and it must increase with each message.
+pub struct MwaSession {
+    cipher: Aes128Gcm,
+    sent: u32,
+    received: u32,
+}
+
+impl MwaSession {
+    /// Derive the session key using ECDH between the `secret_key` and the `peer_public_key`
+    /// followed by HKDF-SHA256 using the association public key as the salt
+    pub(crate) fn derive(
+        secret_key: &SecretKey,
+        peer_public_key: &[u8],
+        association_public_key: &[u8],
+    ) -> WalletResult<Self> {
+        let peer_public_key = PublicKey::from_sec1_bytes(peer_public_key).or(Err(
+            WalletError::MwaHandshakeFailed("Invalid P-256 public key".to_string()),
+        ))?;
+
+        let shared_secret =
+            diffie_hellman(secret_key.to_nonzero_scalar(), peer_public_key.as_affine());
+
+        let mut key = [0u8; 16];
+        Hkdf::<Sha256>::new(
+            Some(association_public_key),
+            shared_secret.raw_secret_bytes(),
+        )
+        .expand(&[], &mut key)
+        .or(Err(WalletError::MwaHandshakeFailed(
+            "Unable to derive the session key".to_string(),
+        )))?;
+
+        Ok(Self {
+            cipher: Aes128Gcm::new(&key.into()),
+            sent: 0,
+            received: 0,
+        })
+    }
+
+    /// Encrypt a message using the next sequence number
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> WalletResult<Vec<u8>> {
+        self.sent = self
+            .sent
+            .checked_add(1)
+            .ok_or(WalletError::MwaInvalidMessage(
+                "The session has run out of sequence numbers".to_string(),
+            ))?;
+
+        let sequence_number = self.sent.to_be_bytes();
+        let random = Utils::rand_32bytes();
+        let iv = &random[..IV_LENGTH];
+
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(iv),
+                Payload {
+                    msg: plaintext,
+                    aad: &sequence_number,
+                },
+            )
+            .or(Err(WalletError::MwaInvalidMessage(
+                "Unable to encrypt the message".to_string(),
+            )))?;
+
+        let mut message = sequence_number.to_vec();
+        message.extend_from_slice(iv);
+        message.extend_from_slice(&ciphertext);
+
+        Ok(message)
+    }
+
+    /// Decrypt a message, rejecting messages that are not authentic
+    /// or whose sequence number is not greater than the last message received
+    pub fn decrypt(&mut self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        if message.len() < SEQUENCE_NUMBER_LENGTH + IV_LENGTH {
+            return Err(WalletError::MwaInvalidMessage(
+                "The encrypted message is too short".to_string(),
+            ));
+        }
+
+        let (sequence_number, rest) = message.split_at(SEQUENCE_NUMBER_LENGTH);
+        let (iv, ciphertext) = rest.split_at(IV_LENGTH);
+
+        let sequence = u32::from_be_bytes([
+            sequence_number[0],
+            sequence_number[1],
+            sequence_number[2],
+            sequence_number[3],
+        ]);
+        if sequence <= self.received {
+            return Err(WalletError::MwaInvalidMessage(format!(
+                "Unexpected sequence number `{sequence}`"
+            )));
+        }
+
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(iv),
+                Payload {
+                    msg: ciphertext,
+                    aad: sequence_number,
+                },
+            )
+            .or(Err(WalletError::MwaInvalidMessage(
+                "Unable to decrypt the message".to_string(),
+            )))?;
+
+        self.received = sequence;
+
+        Ok(plaintext)
+    }
+}
+
+impl core::fmt::Debug for MwaSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MwaSession")
+            .field("sent", &self.sent)
+            .field("received", &self.received)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encode a P-256 public key as an uncompressed X9.62 point
+pub(crate) fn encode_public_key(public_key: &PublicKey) -> Vec<u8> {
+    public_key.to_encoded_point(false).as_bytes().to_vec()
+}
@@ -0,0 +1,170 @@
+use std::{future::Future, time::Duration};
+
+use async_channel::{unbounded, Receiver};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{ArrayBuffer, Promise, Uint8Array},
+    wasm_bindgen::{prelude::Closure, JsCast, JsValue},
+    BinaryType, MessageEvent, WebSocket,
+};
+
+use crate::{WalletError, WalletResult, MWA_WEBSOCKET_PROTOCOL};
+
+/// The channel carrying the binary messages between the app and a Mobile Wallet Adapter wallet.
+/// [WebSocketTransport] connects to a wallet on the same device and other transports,
+/// like a reflector for remote wallets or a stand-in wallet in tests, can implement this trait.
+pub trait MwaTransport {
+    /// Send a message to the wallet
+    fn send(&mut self, message: Vec<u8>) -> impl Future<Output = WalletResult<()>>;
+
+    /// Receive the next message from the wallet
+    fn receive(&mut self) -> impl Future<Output = WalletResult<Vec<u8>>>;
+}
+
+/// A [MwaTransport] over the websocket served by a wallet on the same device
+pub struct WebSocketTransport {
+    socket: WebSocket,
+    messages: Receiver<WalletResult<Vec<u8>>>,
+    // The callbacks are dropped together with the socket
+    _on_message: Closure<dyn Fn(MessageEvent)>,
+    _on_close: Closure<dyn Fn(JsValue)>,
+}
+
+impl WebSocketTransport {
+    /// Connect to the websocket at `url` using the Mobile Wallet Adapter subprotocol
+    pub async fn connect(url: &str) -> WalletResult<Self> {
+        let socket = WebSocket::new_with_str(url, MWA_WEBSOCKET_PROTOCOL)?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let (opened_sender, opened) = unbounded::<bool>();
+        let on_open = {
+            let opened_sender = opened_sender.clone();
+            Closure::once(move |_: JsValue| {
+                let _ = opened_sender.try_send(true);
+            })
+        };
+        let on_error = Closure::once(move |_: JsValue| {
+            let _ = opened_sender.try_send(false);
+        });
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        let is_open = opened.recv().await.unwrap_or_default();
+
+        socket.set_onopen(None);
+        socket.set_onerror(None);
+
+        if !is_open {
+            let _ = socket.close();
+
+            return Err(WalletError::MwaTransportError(format!(
+                "Unable to connect to `{url}`"
+            )));
+        }
+
+        let (message_sender, messages) = unbounded::<WalletResult<Vec<u8>>>();
+
+        let on_message = {
+            let message_sender = message_sender.clone();
+            Closure::wrap(Box::new(move |event: MessageEvent| {
+                let message = event
+                    .data()
+                    .dyn_into::<ArrayBuffer>()
+                    .map(|buffer| Uint8Array::new(&buffer).to_vec())
+                    .or(Err(WalletError::MwaTransportError(
+                        "Expected a binary message".to_string(),
+                    )));
+
+                let _ = message_sender.try_send(message);
+            }) as Box<dyn Fn(_)>)
+        };
+        let on_close = Closure::wrap(Box::new(move |_: JsValue| {
+            let _ = message_sender.try_send(Err(WalletError::MwaTransportError(
+                "The wallet closed the connection".to_string(),
+            )));
+            message_sender.close();
+        }) as Box<dyn Fn(_)>);
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            messages,
+            _on_message: on_message,
+            _on_close: on_close,
+        })
+    }
+
+    /// Connect to the websocket at `url`, retrying up to `attempts` times with a `delay`
+    /// between the attempts since the wallet takes some time to start its websocket server
+    /// after the association URI is opened
+    pub async fn connect_with_retry(
+        url: &str,
+        attempts: usize,
+        delay: Duration,
+    ) -> WalletResult<Self> {
+        let mut attempt = 1;
+
+        loop {
+            match Self::connect(url).await {
+                Ok(transport) => return Ok(transport),
+                Err(error) if attempt >= attempts => return Err(error),
+                Err(_) => {
+                    wallet_log!(debug, "Attempt `{attempt}` to connect to `{url}` failed");
+
+                    sleep(delay).await?;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+impl MwaTransport for WebSocketTransport {
+    async fn send(&mut self, message: Vec<u8>) -> WalletResult<()> {
+        Ok(self.socket.send_with_u8_array(&message)?)
+    }
+
+    async fn receive(&mut self) -> WalletResult<Vec<u8>> {
+        self.messages
+            .recv()
+            .await
+            .or(Err(WalletError::MwaTransportError(
+                "The wallet closed the connection".to_string(),
+            )))?
+    }
+}
+
+impl Drop for WebSocketTransport {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+
+        let _ = self.socket.close();
+    }
+}
+
+impl core::fmt::Debug for WebSocketTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocketTransport")
+            .field("url", &self.socket.url())
+            .finish()
+    }
+}
+
+async fn sleep(delay: Duration) -> WalletResult<()> {
+    let window = web_sys::window().ok_or(WalletError::MissingAccessToBrowserWindow)?;
+    let mut outcome = Ok(0);
+
+    let promise = Promise::new(&mut |resolve, _| {
+        outcome = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            &resolve,
+            delay.as_millis().try_into().unwrap_or(i32::MAX),
+        );
+    });
+    outcome?;
+
+    JsFuture::from(promise).await?;
+
+    Ok(())
+}
@@ -0,0 +1,542 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Cluster, Commitment, SignInOutput, WalletAccount, WalletIcon};
+
+impl Serialize for Cluster {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.chain())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cluster {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        value.as_str().try_into().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Commitment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Commitment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        value.as_str().try_into().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for WalletIcon {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for WalletIcon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        WalletIcon::new(&value).map_err(de::Error::custom)
+    }
+}
+
+// The wire representation of a [WalletAccount] without the `js_value`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WalletAccountRepr {
+    address: String,
+    #[serde(with = "byte_array")]
+    public_key: [u8; 32],
+    chains: Vec<String>,
+    features: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<WalletIcon>,
+}
+
+impl Serialize for WalletAccount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WalletAccountRepr {
+            address: self.address.clone(),
+            public_key: self.public_key,
+            chains: self.chains.clone(),
+            features: self.features.clone(),
+            label: self.label.clone(),
+            icon: self.icon.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WalletAccount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = WalletAccountRepr::deserialize(deserializer)?;
+
+        WalletAccount::from_parts(
+            repr.address,
+            repr.public_key,
+            repr.chains,
+            repr.features,
+            repr.label,
+            repr.icon,
+        )
+        .map_err(de::Error::custom)
+    }
+}
+
+const ED25519_SIGNATURE_TYPE: &str = "ed25519";
+
+// The wire representation of a [SignInOutput] matching the wallet standard
+// `SolanaSignInOutput`. The `publicKey` used to verify the signature is
+// also included since it is not always the public key of the `account`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInOutputRepr {
+    account: WalletAccount,
+    #[serde(with = "base64_bytes")]
+    signed_message: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    signature: [u8; 64],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_key: Option<PublicKeyRepr>,
+}
+
+// Borrows the fields of a [SignInOutput] to serialize it as a [SignInOutputRepr]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignInOutputRef<'a> {
+    account: &'a WalletAccount,
+    #[serde(with = "base64_bytes")]
+    signed_message: &'a [u8],
+    #[serde(with = "base64_bytes")]
+    signature: &'a [u8; 64],
+    signature_type: &'a str,
+    public_key: PublicKeyRepr,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PublicKeyRepr(#[serde(with = "byte_array")] [u8; 32]);
+
+impl Serialize for SignInOutput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SignInOutputRef {
+            account: &self.account,
+            signed_message: self.message.as_bytes(),
+            signature: &self.signature,
+            signature_type: ED25519_SIGNATURE_TYPE,
+            public_key: PublicKeyRepr(self.public_key),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SignInOutput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SignInOutputRepr::deserialize(deserializer)?;
+
+        if let Some(signature_type) = repr.signature_type {
+            if signature_type != ED25519_SIGNATURE_TYPE {
+                return Err(de::Error::custom(format!(
+                    "Unsupported signature type `{signature_type}`"
+                )));
+            }
+        }
+
+        let message = String::from_utf8(repr.signed_message).map_err(de::Error::custom)?;
+        let public_key = repr
+            .public_key
+            .map(|public_key| public_key.0)
+            .unwrap_or(repr.account.public_key);
+
+        Ok(Self {
+            account: repr.account,
+            message,
+            signature: repr.signature,
+            public_key,
+        })
+    }
+}
+
+/// (De)serialize byte arrays of any length since serde only supports arrays up to 32 elements.
+/// Human readable formats also accept a base64 string or an object keyed by the index
+/// of each byte which is how `JSON.stringify()` encodes a `Uint8Array`
+pub(crate) mod byte_array {
+    use serde::{de, ser::SerializeTuple, Deserializer, Serializer};
+
+    use super::BytesVisitor;
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        bytes
+            .iter()
+            .try_for_each(|byte| tuple.serialize_element(byte))?;
+        tuple.end()
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BytesVisitor(Some(N)))?
+        } else {
+            deserializer.deserialize_tuple(N, BytesVisitor(Some(N)))?
+        };
+
+        bytes.try_into().map_err(|bytes: Vec<u8>| {
+            de::Error::invalid_length(bytes.len(), &BytesVisitor(Some(N)))
+        })
+    }
+}
+
+/// (De)serialize bytes as a base64 string for human readable formats.
+/// Deserializing accepts the same inputs as [byte_array]
+pub(crate) mod base64_bytes {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use serde::{de, Deserializer, Serializer};
+
+    use super::BytesVisitor;
+
+    pub(crate) fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        bytes: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&BASE64_STANDARD.encode(bytes.as_ref()))
+        } else {
+            serializer.serialize_bytes(bytes.as_ref())
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, T: TryFrom<Vec<u8>>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BytesVisitor(None))?
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor(None))?
+        };
+        let length = bytes.len();
+
+        T::try_from(bytes)
+            .map_err(|_| de::Error::invalid_length(length, &"a valid number of bytes"))
+    }
+}
+
+// Collects bytes from a sequence, a base64 string, raw bytes or an object keyed by the index
+// of each byte. Checks the number of bytes if the length is known.
+struct BytesVisitor(Option<usize>);
+
+impl BytesVisitor {
+    fn check<E: de::Error>(&self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+        match self.0 {
+            Some(length) if length != bytes.len() => Err(E::invalid_length(bytes.len(), self)),
+            _ => Ok(bytes),
+        }
+    }
+}
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            Some(length) => write!(formatter, "{length} bytes"),
+            None => write!(formatter, "bytes"),
+        }?;
+
+        write!(
+            formatter,
+            " as an array, a base64 string or an object keyed by index"
+        )
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::<u8>::with_capacity(self.0.or(seq.size_hint()).unwrap_or_default());
+
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        self.check(bytes)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut indexed = std::collections::BTreeMap::<usize, u8>::new();
+
+        while let Some((index, byte)) = map.next_entry::<String, u8>()? {
+            let index = index
+                .parse::<usize>()
+                .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&index), &self))?;
+            indexed.insert(index, byte);
+        }
+
+        if indexed
+            .keys()
+            .enumerate()
+            .any(|(position, index)| position != *index)
+        {
+            return Err(de::Error::custom("the byte indexes are not contiguous"));
+        }
+
+        self.check(indexed.into_values().collect())
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        use base64::{prelude::BASE64_STANDARD, Engine};
+
+        let bytes = BASE64_STANDARD.decode(value).map_err(E::custom)?;
+
+        self.check(bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        self.check(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        self.check(value)
+    }
+}
+
+/// (De)serialize an optional [SystemTime](std::time::SystemTime) as an ISO 8601 datetime string
+pub(crate) mod iso8601_option {
+    use std::time::SystemTime;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(time) => {
+                serializer.serialize_str(&humantime::format_rfc3339_millis(*time).to_string())
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| humantime::parse_rfc3339(&value).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod serde_tests {
+    use crate::{
+        SignInOutput, SignedMessageOutput, SignedMessageProof, SigninInput, WalletError,
+        WalletEvent, SOLANA_SIGN_MESSAGE_IDENTIFIER, STANDARD_CONNECT_IDENTIFIER,
+    };
+
+    use super::*;
+
+    const ADDRESS: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+
+    fn account() -> WalletAccount {
+        let public_key: [u8; 32] = bs58::decode(ADDRESS)
+            .into_vec()
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        WalletAccount::from_parts(
+            ADDRESS.to_string(),
+            public_key,
+            vec!["solana:mainnet".to_string(), "solana:devnet".to_string()],
+            vec![
+                STANDARD_CONNECT_IDENTIFIER.to_string(),
+                SOLANA_SIGN_MESSAGE_IDENTIFIER.to_string(),
+            ],
+            Some("Main".to_string()),
+            None,
+        )
+        .unwrap()
+    }
+
+    // Compares the JSON output since comparing types containing a `JsValue`
+    // is not supported on non-wasm32 targets
+    fn round_trip<T>(value: &T) -> String
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        let deserialized = serde_json::from_str::<T>(&json).unwrap();
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+
+        json
+    }
+
+    #[test]
+    fn cluster_and_commitment() {
+        assert_eq!(round_trip(&Cluster::MainNet), r#""solana:mainnet""#);
+        assert_eq!(round_trip(&Cluster::LocalNet), r#""solana:localnet""#);
+        assert_eq!(
+            serde_json::from_str::<Cluster>(r#""devnet""#).unwrap(),
+            Cluster::DevNet
+        );
+        assert!(serde_json::from_str::<Cluster>(r#""ethereum:1""#).is_err());
+
+        assert_eq!(round_trip(&Commitment::Confirmed), r#""confirmed""#);
+        assert!(serde_json::from_str::<Commitment>(r#""final""#).is_err());
+    }
+
+    #[test]
+    fn wallet_account() {
+        let account = account();
+        let json = round_trip(&account);
+
+        assert!(json.contains(r#""publicKey":["#));
+        assert!(!json.contains("icon"));
+
+        let deserialized = serde_json::from_str::<WalletAccount>(&json).unwrap();
+        assert!(deserialized.mainnet() && deserialized.devnet());
+        assert!(deserialized.solana_sign_message() && !deserialized.solana_signin());
+
+        let mismatched_address = json.replace(ADDRESS, "11111111111111111111111111111111");
+        assert!(serde_json::from_str::<WalletAccount>(&mismatched_address).is_err());
+    }
+
+    #[test]
+    fn signin_input() {
+        let json = r#"{"domain":"example.com","address":"4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T","statement":"Sign in","chainId":"solana:mainnet","nonce":"12345678","issuedAt":"2025-01-01T00:00:00.123Z","resources":["https://example.com"]}"#;
+
+        let signin_input = serde_json::from_str::<SigninInput>(json).unwrap();
+        assert_eq!(signin_input.chain_id(), Some(&Cluster::MainNet));
+        assert!(signin_input.issued_at().is_some());
+        assert!(signin_input.expiration_time().is_none());
+
+        assert_eq!(round_trip(&signin_input), json);
+        assert_eq!(round_trip(&SigninInput::default()), "{}");
+    }
+
+    #[test]
+    fn signin_output() {
+        let output = SignInOutput {
+            account: account(),
+            message: "example.com wants you to sign in".to_string(),
+            signature: [7u8; 64],
+            public_key: account().public_key(),
+        };
+
+        let json = round_trip(&output);
+        assert!(json.contains(r#""signedMessage":"ZXhhbXBsZS5jb20gd2FudHMgeW91IHRvIHNpZ24gaW4=""#));
+        assert!(json.contains(r#""signatureType":"ed25519""#));
+        assert!(json.contains(r#""publicKey":["#));
+    }
+
+    // The shape produced by `JSON.stringify()` of a wallet standard `SolanaSignInOutput`
+    // where the `Uint8Array`s are encoded as objects keyed by index
+    #[test]
+    fn signin_output_wallet_standard() {
+        let public_key = account().public_key;
+        let indexed = |bytes: &[u8]| {
+            let entries = bytes
+                .iter()
+                .enumerate()
+                .map(|(index, byte)| format!(r#""{index}":{byte}"#))
+                .collect::<Vec<String>>();
+
+            "{".to_string() + &entries.join(",") + "}"
+        };
+
+        let json = format!(
+            r#"{{"account":{{"address":"{ADDRESS}","publicKey":{},"chains":["solana:mainnet"],"features":[]}},"signedMessage":{},"signature":{:?}}}"#,
+            indexed(&public_key),
+            indexed(b"Sign in"),
+            [3u8; 64],
+        );
+
+        let output = serde_json::from_str::<SignInOutput>(&json).unwrap();
+        assert_eq!(output.message, "Sign in");
+        assert_eq!(output.signature, [3u8; 64]);
+        assert_eq!(output.public_key, public_key);
+        assert_eq!(output.account.public_key, public_key);
+
+        let base64_public_key = json.replace(
+            &indexed(&public_key),
+            &(r#"""#.to_string()
+                + &base64::Engine::encode(&base64::prelude::BASE64_STANDARD, public_key)
+                + r#"""#),
+        );
+        assert_eq!(
+            serde_json::from_str::<SignInOutput>(&base64_public_key)
+                .unwrap()
+                .account
+                .public_key,
+            public_key
+        );
+
+        let short_signature = json.replace(&format!("{:?}", [3u8; 64]), "[3, 3]");
+        assert!(serde_json::from_str::<SignInOutput>(&short_signature).is_err());
+
+        let invalid_type = json.replace(
+            r#""signature""#,
+            r#""signatureType":"secp256k1","signature""#,
+        );
+        assert!(serde_json::from_str::<SignInOutput>(&invalid_type).is_err());
+    }
+
+    #[test]
+    fn signed_message_output() {
+        let output = SignedMessageOutput::new(b"Hello Solana", account().public_key(), [9u8; 64]);
+
+        let bytes = bincode::serialize(&output).unwrap();
+        assert_eq!(
+            bincode::deserialize::<SignedMessageOutput>(&bytes).unwrap(),
+            output
+        );
+
+        let json = round_trip(&output);
+        assert!(json.contains(r#""message":"SGVsbG8gU29sYW5h""#));
+        assert!(json.contains(r#""signature":["#));
+
+        let proof = SignedMessageProof {
+            address: ADDRESS.to_string(),
+            message: "AP8=".to_string(),
+            signature: bs58::encode([9u8; 64]).into_string(),
+        };
+        assert_eq!(
+            round_trip(&proof),
+            format!(
+                r#"{{"address":"{ADDRESS}","message":"AP8=","signature":"{}"}}"#,
+                proof.signature
+            )
+        );
+    }
+
+    #[test]
+    fn wallet_event_and_error() {
+        assert_eq!(
+            round_trip(&WalletEvent::Disconnected),
+            r#"{"type":"disconnected"}"#
+        );
+        round_trip(&WalletEvent::Connected(account()));
+        round_trip(&WalletEvent::BackgroundTaskError(
+            WalletError::WalletNotFound,
+        ));
+
+        assert_eq!(
+            round_trip(&WalletError::UnsupportedChain("ethereum:1".to_string())),
+            r#"{"type":"unsupportedChain","data":"ethereum:1"}"#
+        );
+        round_trip(&WalletError::JsError {
+            name: "Error".to_string(),
+            message: "User rejected the request".to_string(),
+            stack: String::default(),
+        });
+    }
+}
@@ -0,0 +1,563 @@
+use crate::{Utils, WalletError, WalletResult};
+
+/// The URL scheme of Solana Pay requests
+pub const SOLANA_PAY_SCHEME: &str = "solana";
+/// The number of decimal places of native SOL, one lamport is `0.000000001` SOL
+pub const SOL_DECIMALS: u8 = 9;
+
+/// An exact non-negative decimal amount of a Solana Pay transfer request in "user" units,
+/// `1` is one SOL or one token and not one lamport or one base unit of the token.
+/// The amount is kept as an integer and a number of decimal places so no precision is lost.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct SolanaPayAmount {
+    value: u128,
+    decimals: u8,
+}
+
+impl SolanaPayAmount {
+    /// Convert an amount of base units, like lamports, of a token with `decimals` decimal places
+    pub fn from_base_units(units: u64, decimals: u8) -> Self {
+        Self {
+            value: units as u128,
+            decimals,
+        }
+        .normalize()
+    }
+
+    /// Parse an amount as written in a transfer request, digits with an optional decimal point
+    /// followed by digits. A leading `0` is required for amounts less than one and signs,
+    /// exponents and separators are rejected, for example `0.01` is valid but `.01` and `1e-2` are not
+    pub fn parse(amount: &str) -> WalletResult<Self> {
+        let invalid = || WalletError::InvalidSolanaPayAmount(amount.to_string());
+
+        let (integer, fraction) = match amount.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (amount, None),
+        };
+
+        let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(integer) || !fraction.is_none_or(is_digits) {
+            return Err(invalid());
+        }
+
+        let fraction = fraction.unwrap_or_default();
+        let decimals = u8::try_from(fraction.len()).or(Err(invalid()))?;
+        let value = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .try_fold(0u128, |value, digit| {
+                value.checked_mul(10)?.checked_add((digit - b'0') as u128)
+            })
+            .ok_or_else(invalid)?;
+
+        Ok(Self { value, decimals }.normalize())
+    }
+
+    /// Get the number of decimal places, excluding trailing zeros
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Convert the amount to base units, like lamports, of a token with `token_decimals`
+    /// decimal places. An error is returned if the amount has more decimal places than
+    /// the token, in which case the wallet must reject the request as malformed
+    pub fn to_base_units(&self, token_decimals: u8) -> WalletResult<u64> {
+        let invalid = || WalletError::InvalidSolanaPayAmount(self.to_string());
+
+        let scale = token_decimals
+            .checked_sub(self.decimals)
+            .ok_or_else(invalid)?;
+
+        10u128
+            .checked_pow(scale as u32)
+            .and_then(|scale| self.value.checked_mul(scale))
+            .and_then(|units| u64::try_from(units).ok())
+            .ok_or_else(invalid)
+    }
+
+    /// Remove the trailing zeros of the fraction so that equal amounts compare equal
+    fn normalize(mut self) -> Self {
+        while self.decimals > 0 && self.value % 10 == 0 {
+            self.value /= 10;
+            self.decimals -= 1;
+        }
+
+        self
+    }
+}
+
+impl core::fmt::Display for SolanaPayAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.value.to_string();
+        let decimals = self.decimals as usize;
+
+        if decimals == 0 {
+            return write!(f, "{digits}");
+        }
+
+        // Pad with leading zeros so that there is at least one digit before the decimal point
+        let digits = format!("{digits:0>width$}", width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+
+        write!(f, "{integer}.{fraction}")
+    }
+}
+
+/// A Solana Pay transfer request, the URL
+/// `solana:<recipient>?amount=<amount>&spl-token=<mint>&reference=<reference>&label=<label>&message=<message>&memo=<memo>`
+/// asking a wallet to transfer SOL or an SPL token to the recipient.
+/// Only the recipient is required, the wallet prompts the user for the amount if it is missing.
+///
+/// #### Example
+/// ```rust
+/// use wallet_adapter::{SolanaPayAmount, SolanaPayTransfer};
+///
+/// let mut transfer = SolanaPayTransfer::new("mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN")?;
+/// transfer
+///     .set_amount(SolanaPayAmount::parse("0.01")?)?
+///     .set_label("Michael");
+///
+/// assert_eq!(
+///     transfer.to_string(),
+///     "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?amount=0.01&label=Michael"
+/// );
+/// assert_eq!(SolanaPayTransfer::parse(&transfer.to_string())?, transfer);
+/// # Ok::<(), wallet_adapter::WalletError>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SolanaPayTransfer {
+    recipient: String,
+    amount: Option<SolanaPayAmount>,
+    spl_token: Option<String>,
+    references: Vec<String>,
+    label: Option<String>,
+    message: Option<String>,
+    memo: Option<String>,
+}
+
+impl SolanaPayTransfer {
+    /// Instantiate a transfer request to the Base58 `recipient` address
+    pub fn new(recipient: &str) -> WalletResult<Self> {
+        Ok(Self {
+            recipient: public_key(recipient, "recipient")?,
+            ..Default::default()
+        })
+    }
+
+    /// Parse a `solana:` transfer request URL
+    pub fn parse(url: &str) -> WalletResult<Self> {
+        let (scheme, request) = url
+            .trim()
+            .split_once(':')
+            .ok_or(invalid("The URL has no scheme"))?;
+        if !scheme.eq_ignore_ascii_case(SOLANA_PAY_SCHEME) {
+            return Err(invalid("The scheme must be `solana:`"));
+        }
+
+        let request = request
+            .split_once('#')
+            .map_or(request, |(request, _)| request);
+        let (recipient, query) = request.split_once('?').unwrap_or((request, ""));
+
+        if recipient.is_empty() {
+            return Err(invalid("The recipient is missing"));
+        }
+        // A transaction request has a URL instead of a recipient
+        if recipient.contains(':') || recipient.contains('%') {
+            return Err(invalid(
+                "The URL is a transaction request and not a transfer request",
+            ));
+        }
+
+        let mut transfer = Self::new(recipient)?;
+        let mut amount = Option::<SolanaPayAmount>::None;
+
+        for (key, value) in Utils::parse_query(query)? {
+            match key.as_str() {
+                "amount" => set_once(&mut amount, SolanaPayAmount::parse(&value)?, &key)?,
+                "spl-token" => set_once(&mut transfer.spl_token, public_key(&value, &key)?, &key)?,
+                "reference" => transfer.references.push(public_key(&value, &key)?),
+                "label" => set_once(&mut transfer.label, value, &key)?,
+                "message" => set_once(&mut transfer.message, value, &key)?,
+                "memo" => set_once(&mut transfer.memo, value, &key)?,
+                // Parameters added by later versions of the specification are ignored
+                _ => (),
+            }
+        }
+
+        if let Some(amount) = amount {
+            transfer.set_amount(amount)?;
+        }
+
+        Ok(transfer)
+    }
+
+    /// Set the amount in "user" units. If no SPL token mint is set, the amount is in SOL
+    /// and an error is returned if it has more than [SOL_DECIMALS] decimal places
+    pub fn set_amount(&mut self, amount: SolanaPayAmount) -> WalletResult<&mut Self> {
+        if self.spl_token.is_none() && amount.decimals() > SOL_DECIMALS {
+            return Err(WalletError::InvalidSolanaPayAmount(amount.to_string()));
+        }
+
+        self.amount.replace(amount);
+
+        Ok(self)
+    }
+
+    /// Request a transfer of the SPL token with the Base58 `mint` address instead of SOL
+    pub fn set_spl_token(&mut self, mint: &str) -> WalletResult<&mut Self> {
+        self.spl_token.replace(public_key(mint, "spl-token")?);
+
+        Ok(self)
+    }
+
+    /// Add a Base58 `reference` address. The wallet adds the references to the transfer
+    /// instruction as read-only non-signer keys so that the transaction can be found
+    /// using `getSignaturesForAddress`
+    pub fn add_reference(&mut self, reference: &str) -> WalletResult<&mut Self> {
+        self.references.push(public_key(reference, "reference")?);
+
+        Ok(self)
+    }
+
+    /// Set the label describing the source of the request, for example the name of a store
+    pub fn set_label(&mut self, label: &str) -> &mut Self {
+        self.label.replace(label.to_string());
+
+        self
+    }
+
+    /// Set the message describing the nature of the request, for example the item purchased
+    pub fn set_message(&mut self, message: &str) -> &mut Self {
+        self.message.replace(message.to_string());
+
+        self
+    }
+
+    /// Set the memo the wallet includes in an SPL Memo instruction of the transaction.
+    /// The memo is public on chain
+    pub fn set_memo(&mut self, memo: &str) -> &mut Self {
+        self.memo.replace(memo.to_string());
+
+        self
+    }
+
+    /// Get the Base58 address of the recipient
+    pub fn recipient(&self) -> &str {
+        self.recipient.as_str()
+    }
+
+    /// Get the amount
+    pub fn amount(&self) -> Option<SolanaPayAmount> {
+        self.amount
+    }
+
+    /// Get the Base58 address of the SPL token mint, [Option::None] for a SOL transfer
+    pub fn spl_token(&self) -> Option<&str> {
+        self.spl_token.as_deref()
+    }
+
+    /// Get the Base58 reference addresses in the order of the URL
+    pub fn references(&self) -> &[String] {
+        self.references.as_slice()
+    }
+
+    /// Get the label
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Get the message
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Get the memo
+    pub fn memo(&self) -> Option<&str> {
+        self.memo.as_deref()
+    }
+
+    /// Build the `solana:` URL with the parameters in the order of the specification
+    pub fn to_url(&self) -> String {
+        let mut query = Vec::<String>::new();
+
+        if let Some(amount) = self.amount {
+            query.push(format!("amount={amount}"));
+        }
+        if let Some(spl_token) = self.spl_token.as_ref() {
+            query.push(format!("spl-token={spl_token}"));
+        }
+        self.references.iter().for_each(|reference| {
+            query.push(format!("reference={reference}"));
+        });
+        [
+            ("label", &self.label),
+            ("message", &self.message),
+            ("memo", &self.memo),
+        ]
+        .iter()
+        .for_each(|(key, value)| {
+            if let Some(value) = value {
+                query.push(format!("{key}={}", Utils::percent_encode(value)));
+            }
+        });
+
+        let mut url = format!("{SOLANA_PAY_SCHEME}:{}", self.recipient);
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+
+        url
+    }
+}
+
+impl core::fmt::Display for SolanaPayTransfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_url())
+    }
+}
+
+fn invalid(reason: &str) -> WalletError {
+    WalletError::InvalidSolanaPayUrl(reason.to_string())
+}
+
+/// Check that the `value` of the `field` is a Base58 encoded 32 byte public key
+fn public_key(value: &str, field: &str) -> WalletResult<String> {
+    let decoded = bs58::decode(value)
+        .into_vec()
+        .or(Err(invalid(&format!("The `{field}` is not valid Base58"))))?;
+
+    if decoded.len() != 32 {
+        return Err(invalid(&format!(
+            "The `{field}` must be a 32 byte public key"
+        )));
+    }
+
+    Ok(value.to_string())
+}
+
+/// Set a parameter that must appear at most once in the URL
+fn set_once<T>(parameter: &mut Option<T>, value: T, key: &str) -> WalletResult<()> {
+    if parameter.replace(value).is_some() {
+        return Err(invalid(&format!(
+            "The `{key}` parameter appears more than once"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod solana_pay_tests {
+    use super::*;
+
+    const RECIPIENT: &str = "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const REFERENCE: &str = "82ZJ7nbGpixjeDCmEhUcmwXYfvurzAgGdtSMuHnUgyny";
+
+    fn is_invalid_url(url: &str) -> bool {
+        matches!(
+            SolanaPayTransfer::parse(url),
+            Err(WalletError::InvalidSolanaPayUrl(_))
+        )
+    }
+
+    #[test]
+    fn spec_examples() {
+        // Native SOL transfer with a label, message and memo
+        let url = "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?amount=1&label=Michael&message=Thanks%20for%20all%20the%20fish&memo=OrderId12345";
+        let transfer = SolanaPayTransfer::parse(url).unwrap();
+        assert_eq!(transfer.recipient(), RECIPIENT);
+        assert_eq!(
+            transfer.amount().unwrap().to_base_units(SOL_DECIMALS),
+            Ok(1_000_000_000)
+        );
+        assert_eq!(transfer.spl_token(), None);
+        assert_eq!(transfer.label(), Some("Michael"));
+        assert_eq!(transfer.message(), Some("Thanks for all the fish"));
+        assert_eq!(transfer.memo(), Some("OrderId12345"));
+        assert_eq!(transfer.to_url(), url);
+
+        let mut built = SolanaPayTransfer::new(RECIPIENT).unwrap();
+        built
+            .set_amount(SolanaPayAmount::parse("1").unwrap())
+            .unwrap()
+            .set_label("Michael")
+            .set_message("Thanks for all the fish")
+            .set_memo("OrderId12345");
+        assert_eq!(built, transfer);
+
+        // SPL token transfer
+        let url = "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?amount=0.01&spl-token=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let transfer = SolanaPayTransfer::parse(url).unwrap();
+        assert_eq!(transfer.spl_token(), Some(USDC));
+        assert_eq!(transfer.amount().unwrap().to_base_units(6), Ok(10_000));
+        assert_eq!(transfer.to_url(), url);
+
+        // The wallet prompts for the amount
+        let url = "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?label=Michael";
+        let transfer = SolanaPayTransfer::parse(url).unwrap();
+        assert!(transfer.amount().is_none());
+        assert_eq!(transfer.to_url(), url);
+
+        // Only the recipient
+        let url = "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN";
+        assert_eq!(SolanaPayTransfer::parse(url).unwrap().to_url(), url);
+    }
+
+    #[test]
+    fn references() {
+        let second = bs58::encode([7u8; 32]).into_string();
+        let url = format!(
+            "solana:{RECIPIENT}?amount=0.5&reference={REFERENCE}&reference={second}&label=Store"
+        );
+
+        let transfer = SolanaPayTransfer::parse(&url).unwrap();
+        assert_eq!(
+            transfer.references(),
+            [REFERENCE.to_string(), second.clone()]
+        );
+        assert_eq!(transfer.to_url(), url);
+
+        let mut built = SolanaPayTransfer::new(RECIPIENT).unwrap();
+        built
+            .set_amount(SolanaPayAmount::parse("0.5").unwrap())
+            .unwrap()
+            .add_reference(REFERENCE)
+            .unwrap()
+            .add_reference(&second)
+            .unwrap()
+            .set_label("Store");
+        assert_eq!(built, transfer);
+
+        assert!(built.add_reference("not-a-public-key").is_err());
+        assert!(is_invalid_url(&format!(
+            "solana:{RECIPIENT}?reference={REFERENCE}&reference=abc"
+        )));
+    }
+
+    #[test]
+    fn encoding() {
+        let mut transfer = SolanaPayTransfer::new(RECIPIENT).unwrap();
+        transfer
+            .set_label("Café & Co")
+            .set_message("50% off = #1 deal?")
+            .set_memo("☕+🍰");
+
+        let url = transfer.to_url();
+        assert_eq!(
+            url,
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?label=Caf%C3%A9%20%26%20Co&message=50%25%20off%20%3D%20%231%20deal%3F&memo=%E2%98%95%2B%F0%9F%8D%B0"
+        );
+        assert_eq!(SolanaPayTransfer::parse(&url).unwrap(), transfer);
+
+        // `+` is a space in a query and the fragment is ignored
+        let transfer =
+            SolanaPayTransfer::parse(&format!("SOLANA:{RECIPIENT}?message=Thanks+a+lot#top"))
+                .unwrap();
+        assert_eq!(transfer.message(), Some("Thanks a lot"));
+
+        // Unknown parameters are ignored
+        let transfer =
+            SolanaPayTransfer::parse(&format!("solana:{RECIPIENT}?label=Shop&future=1")).unwrap();
+        assert_eq!(transfer.label(), Some("Shop"));
+
+        assert!(matches!(
+            SolanaPayTransfer::parse(&format!("solana:{RECIPIENT}?label=%ZZ")),
+            Err(WalletError::InvalidPercentEncoding(_))
+        ));
+        assert!(matches!(
+            SolanaPayTransfer::parse(&format!("solana:{RECIPIENT}?label=%FF")),
+            Err(WalletError::InvalidPercentEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_urls() {
+        assert!(is_invalid_url(""));
+        assert!(is_invalid_url(RECIPIENT));
+        assert!(is_invalid_url(&format!("bitcoin:{RECIPIENT}")));
+        assert!(is_invalid_url("solana:"));
+        assert!(is_invalid_url("solana:?amount=1"));
+        assert!(is_invalid_url(
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2k"
+        ));
+        assert!(is_invalid_url("solana:0OIl"));
+        assert!(is_invalid_url(
+            "solana:https%3A%2F%2Fexample.com%2Fapi%2Fpay"
+        ));
+        assert!(is_invalid_url("solana:https://example.com/api/pay"));
+        assert!(is_invalid_url(&format!(
+            "solana:{RECIPIENT}?amount=1&amount=2"
+        )));
+        assert!(is_invalid_url(&format!(
+            "solana:{RECIPIENT}?label=a&label=b"
+        )));
+        assert!(is_invalid_url(&format!(
+            "solana:{RECIPIENT}?spl-token=notamint"
+        )));
+    }
+
+    #[test]
+    fn amounts() {
+        let amount = |value: &str| SolanaPayAmount::parse(value).unwrap();
+
+        assert_eq!(amount("1").to_string(), "1");
+        assert_eq!(amount("0.01").to_string(), "0.01");
+        assert_eq!(amount("1.50").to_string(), "1.5");
+        assert_eq!(amount("1.000").to_string(), "1");
+        assert_eq!(amount("0").to_string(), "0");
+        assert_eq!(amount("10.000000001").decimals(), 9);
+        assert_eq!(amount("1.5"), amount("1.50"));
+
+        assert_eq!(amount("0.000000001").to_base_units(SOL_DECIMALS), Ok(1));
+        assert_eq!(
+            amount("1.5").to_base_units(0),
+            Err(WalletError::InvalidSolanaPayAmount("1.5".to_string()))
+        );
+        assert_eq!(
+            amount("18446744073709551615").to_base_units(0),
+            Ok(u64::MAX)
+        );
+        assert!(amount("18446744073709551616").to_base_units(0).is_err());
+
+        assert_eq!(
+            SolanaPayAmount::from_base_units(1_500_000_000, SOL_DECIMALS),
+            amount("1.5")
+        );
+        assert_eq!(
+            SolanaPayAmount::from_base_units(1, 6).to_string(),
+            "0.000001"
+        );
+
+        [
+            "", "-1", "+1", ".5", "1.", "1e3", "1,5", " 1", "0x10", "1.2.3", "∞",
+        ]
+        .iter()
+        .for_each(|value| {
+            assert_eq!(
+                SolanaPayAmount::parse(value),
+                Err(WalletError::InvalidSolanaPayAmount(value.to_string()))
+            );
+        });
+        assert!(SolanaPayAmount::parse(&"9".repeat(40)).is_err());
+
+        // SOL has 9 decimal places, the decimals of a token mint are checked by the wallet
+        let ten_decimals = amount("0.0000000001");
+        assert!(SolanaPayTransfer::new(RECIPIENT)
+            .unwrap()
+            .set_amount(ten_decimals)
+            .is_err());
+        assert!(
+            SolanaPayTransfer::parse(&format!("solana:{RECIPIENT}?amount=0.0000000001")).is_err()
+        );
+
+        let transfer = SolanaPayTransfer::parse(&format!(
+            "solana:{RECIPIENT}?amount=0.0000000001&spl-token={USDC}"
+        ))
+        .unwrap();
+        assert_eq!(transfer.amount(), Some(ten_decimals));
+        assert!(transfer.amount().unwrap().to_base_units(6).is_err());
+    }
+}
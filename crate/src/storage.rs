@@ -1,39 +1,410 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
-use crate::Wallet;
+use crate::{Cluster, Wallet};
 
-/// Convenience type for `HashMap<blake3::Hash, Wallet>;`
-pub type StorageSchema = HashMap<blake3::Hash, Wallet>;
+/// The registered wallets kept in the order they were registered.
+/// Each wallet is keyed by the [blake3::Hash] of its lowercase name.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RegisteredWallets(Vec<(blake3::Hash, Wallet)>);
+
+impl RegisteredWallets {
+    /// Insert a wallet. A wallet that registers again with the same `key`
+    /// replaces the previous entry but keeps its position.
+    /// Returns the previous entry if it exists
+    pub fn insert(&mut self, key: blake3::Hash, wallet: Wallet) -> Option<Wallet> {
+        insert_entry(&mut self.0, key, wallet)
+    }
+
+    /// Get a wallet by its `key`
+    pub fn get(&self, key: &blake3::Hash) -> Option<&Wallet> {
+        self.0
+            .iter()
+            .find_map(|(existing, wallet)| (existing == key).then_some(wallet))
+    }
+
+    /// Get a mutable reference to a wallet by its `key`
+    pub(crate) fn get_mut(&mut self, key: &blake3::Hash) -> Option<&mut Wallet> {
+        self.0
+            .iter_mut()
+            .find_map(|(existing, wallet)| (existing == key).then_some(wallet))
+    }
+
+    /// Iterate over the wallets in the order they were registered
+    pub fn values(&self) -> impl Iterator<Item = &Wallet> {
+        self.0.iter().map(|(_, wallet)| wallet)
+    }
+
+    /// The number of registered wallets
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no wallet has been registered
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+// Push the entry or replace the value of an existing entry with the same `key` in place
+fn insert_entry<W>(entries: &mut Vec<(blake3::Hash, W)>, key: blake3::Hash, value: W) -> Option<W> {
+    match entries.iter_mut().find(|(existing, _)| existing == &key) {
+        Some((_, existing)) => Some(core::mem::replace(existing, value)),
+        None => {
+            entries.push((key, value));
+
+            Option::None
+        }
+    }
+}
+
+/// Convenience type for [RegisteredWallets]
+pub type StorageSchema = RegisteredWallets;
 
 /// Convenience type for `Rc<RefCell<StorageSchema>>;`
 pub type StorageType = Rc<RefCell<StorageSchema>>;
 
 /// Storage used by the [crate::WalletAdapter]
 #[derive(Default, PartialEq, Eq, Clone)]
-pub struct WalletStorage(StorageType);
+pub struct WalletStorage {
+    wallets: StorageType,
+    preferred: Rc<RefCell<Vec<String>>>,
+    used: Rc<RefCell<Vec<String>>>,
+}
 
 impl WalletStorage {
-    /// Clone the inner field  as `Rc<RefCell<HashMap<blake3::Hash, Wallet>>>`
+    /// Clone the inner field  as `Rc<RefCell<RegisteredWallets>>`
     pub fn clone_inner(&self) -> StorageType {
-        Rc::clone(&self.0)
+        Rc::clone(&self.wallets)
     }
 
-    /// Get all the wallets from storage
+    /// Get all the wallets from storage in the order they were registered
     pub fn get_wallets(&self) -> Vec<Wallet> {
-        self.0.borrow().values().cloned().collect::<Vec<Wallet>>()
+        self.wallets
+            .borrow()
+            .values()
+            .cloned()
+            .collect::<Vec<Wallet>>()
     }
 
     /// Get a certain wallet by name from storage
     pub fn get_wallet(&self, wallet_name: &str) -> Option<Wallet> {
-        let storage_ref = self.0.borrow();
+        let storage_ref = self.wallets.borrow();
         storage_ref
             .get(&blake3::hash(wallet_name.to_lowercase().as_bytes()))
             .cloned()
     }
+
+    /// Apply `update` to a registered wallet by name, example when the wallet
+    /// emits a change event with new chains or features
+    pub(crate) fn update_wallet(&self, wallet_name: &str, update: impl FnOnce(&mut Wallet)) {
+        if let Some(wallet) = self
+            .wallets
+            .borrow_mut()
+            .get_mut(&blake3::hash(wallet_name.to_lowercase().as_bytes()))
+        {
+            update(wallet);
+        }
+    }
+
+    /// Set the names of the preferred wallets which are listed first
+    /// by [Self::list_wallets] in the order given
+    pub fn set_preferred_wallets(&self, wallet_names: &[&str]) -> &Self {
+        *self.preferred.borrow_mut() = wallet_names
+            .iter()
+            .map(|wallet_name| wallet_name.to_string())
+            .collect();
+
+        self
+    }
+
+    /// Get the names of the preferred wallets
+    pub fn preferred_wallets(&self) -> Vec<String> {
+        self.preferred.borrow().clone()
+    }
+
+    /// Mark a wallet as used, example after it has been connected
+    pub fn mark_used(&self, wallet_name: &str) -> &Self {
+        let mut used = self.used.borrow_mut();
+
+        if !used
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(wallet_name))
+        {
+            used.push(wallet_name.to_string());
+        }
+
+        self
+    }
+
+    /// Replace the names of the wallets that have been used before,
+    /// example with names persisted in a previous session
+    pub fn set_used_wallets(&self, wallet_names: Vec<String>) -> &Self {
+        *self.used.borrow_mut() = wallet_names;
+
+        self
+    }
+
+    /// Get the names of the wallets that have been used before
+    pub fn used_wallets(&self) -> Vec<String> {
+        self.used.borrow().clone()
+    }
+
+    /// List the wallets matching the `filter`. Preferred wallets are listed first
+    /// in the order they were set, followed by the other wallets in the
+    /// order they were registered. Wallets that have been used before are marked.
+    pub fn list_wallets(&self, filter: &WalletFilter) -> Vec<WalletListing> {
+        list_parts(
+            self.wallets.borrow().values(),
+            |wallet| filter.matches(wallet),
+            Wallet::name,
+            &self.preferred.borrow(),
+            &self.used.borrow(),
+        )
+        .into_iter()
+        .map(|(wallet, preferred, used)| WalletListing {
+            wallet,
+            preferred,
+            used,
+        })
+        .collect()
+    }
 }
 
 impl core::fmt::Debug for WalletStorage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", &*self.0.borrow())
+        write!(f, "{:?}", &*self.wallets.borrow())
+    }
+}
+
+/// A registered wallet listed by [WalletStorage::list_wallets]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct WalletListing {
+    wallet: Wallet,
+    preferred: bool,
+    used: bool,
+}
+
+impl WalletListing {
+    /// Get the wallet
+    pub fn wallet(&self) -> &Wallet {
+        &self.wallet
+    }
+
+    /// Whether the wallet is in the list of preferred wallets
+    pub fn preferred(&self) -> bool {
+        self.preferred
+    }
+
+    /// Whether the wallet has been used before
+    pub fn used(&self) -> bool {
+        self.used
+    }
+}
+
+/// Selects the wallets that support all the required features and clusters.
+/// An empty filter selects every wallet.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct WalletFilter {
+    features: Vec<String>,
+    clusters: Vec<Cluster>,
+}
+
+impl WalletFilter {
+    /// Same as `Self::default()`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a feature by its identifier, example `solana:signIn`,
+    /// or by its name without the namespace, example `signIn`,
+    /// which matches the feature in any namespace
+    pub fn add_feature(&mut self, feature: &str) -> &mut Self {
+        self.features.push(feature.to_string());
+
+        self
+    }
+
+    /// Require a cluster
+    pub fn add_cluster(&mut self, cluster: Cluster) -> &mut Self {
+        self.clusters.push(cluster);
+
+        self
+    }
+
+    /// Get the identifiers of the required features
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// Get the required clusters
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// Check whether the `wallet` supports all the required features and clusters
+    pub fn matches(&self, wallet: &Wallet) -> bool {
+        self.matches_parts(&wallet.feature_identifiers(), wallet.chains())
+    }
+
+    fn matches_parts(&self, features: &[String], clusters: &[Cluster]) -> bool {
+        self.features.iter().all(|required| {
+            features.iter().any(|feature| {
+                feature == required
+                    || (!required.contains(':')
+                        && feature
+                            .split_once(':')
+                            .is_some_and(|(_, name)| name == required))
+            })
+        }) && self
+            .clusters
+            .iter()
+            .all(|cluster| clusters.contains(cluster))
+    }
+}
+
+// The wallets matching `filter` with whether they are preferred and used,
+// ordered as described by [WalletStorage::list_wallets]
+fn list_parts<'a, W: Clone + 'a>(
+    wallets: impl Iterator<Item = &'a W>,
+    filter: impl Fn(&W) -> bool,
+    name: impl Fn(&W) -> &str,
+    preferred: &[String],
+    used: &[String],
+) -> Vec<(W, bool, bool)> {
+    let mut listings = wallets
+        .filter(|wallet| filter(wallet))
+        .map(|wallet| {
+            (
+                wallet.clone(),
+                position(preferred, name(wallet)).is_some(),
+                position(used, name(wallet)).is_some(),
+            )
+        })
+        .collect::<Vec<(W, bool, bool)>>();
+
+    // A stable sort keeps the registration order of wallets with the same rank
+    listings
+        .sort_by_key(|(wallet, ..)| position(preferred, name(wallet)).unwrap_or(preferred.len()));
+
+    listings
+}
+
+fn position(wallet_names: &[String], wallet_name: &str) -> Option<usize> {
+    wallet_names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(wallet_name))
+}
+
+#[cfg(test)]
+mod storage_tests {
+    use super::*;
+
+    #[test]
+    fn filter() {
+        let features = ["standard:connect", "solana:signIn"].map(String::from);
+        let clusters = [Cluster::MainNet, Cluster::DevNet];
+
+        assert!(WalletFilter::new().matches_parts(&[], &[]));
+
+        let mut filter = WalletFilter::new();
+        filter
+            .add_feature("solana:signIn")
+            .add_cluster(Cluster::MainNet);
+        assert!(filter.matches_parts(&features, &clusters));
+        assert!(!filter.matches_parts(&features[..1], &clusters));
+        assert!(!filter.matches_parts(&features, &clusters[1..]));
+
+        // Features can be required by their name without the namespace
+        let mut filter = WalletFilter::new();
+        filter.add_feature("signIn").add_feature("connect");
+        assert!(filter.matches_parts(&features, &clusters));
+        assert!(!filter.matches_parts(&features[..1], &clusters));
+        assert!(!WalletFilter::new()
+            .add_feature("signin")
+            .matches_parts(&features, &clusters));
+        assert!(!WalletFilter::new()
+            .add_feature("solana:connect")
+            .matches_parts(&features, &clusters));
+    }
+
+    #[test]
+    fn used_and_preferred() {
+        let storage = WalletStorage::default();
+
+        storage.mark_used("Phantom").mark_used("phantom");
+        assert_eq!(storage.used_wallets(), vec!["Phantom".to_string()]);
+
+        storage.set_preferred_wallets(&["Solflare", "Backpack"]);
+        assert_eq!(position(&storage.preferred_wallets(), "backpack"), Some(1));
+        assert_eq!(position(&storage.preferred_wallets(), "Phantom"), None);
+        assert!(storage.list_wallets(&WalletFilter::new()).is_empty());
+    }
+
+    #[test]
+    fn registration_order_and_listing() {
+        let key = |name: &str| blake3::hash(name.to_lowercase().as_bytes());
+
+        let mut wallets = Vec::<(blake3::Hash, &str)>::new();
+        for name in ["Phantom", "Solflare", "Backpack", "Glow", "Nightly"] {
+            assert!(insert_entry(&mut wallets, key(name), name).is_none());
+        }
+        // A wallet registering again keeps its position
+        assert_eq!(
+            insert_entry(&mut wallets, key("solflare"), "Solflare"),
+            Some("Solflare")
+        );
+        assert_eq!(wallets.len(), 5);
+        let wallets = wallets
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            wallets,
+            ["Phantom", "Solflare", "Backpack", "Glow", "Nightly"]
+        );
+
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        let preferred = names(&["nightly", "Backpack", "Unknown"]);
+        let used = names(&["glow", "Backpack"]);
+
+        // Preferred wallets first in the preferred order, then the rest in registration order
+        assert_eq!(
+            list_parts(wallets.iter(), |_| true, |name| name, &preferred, &used),
+            [
+                ("Nightly", true, false),
+                ("Backpack", true, true),
+                ("Phantom", false, false),
+                ("Solflare", false, false),
+                ("Glow", false, true),
+            ]
+        );
+
+        assert_eq!(
+            list_parts(
+                wallets.iter(),
+                |name| !name.starts_with('N'),
+                |name| name,
+                &preferred,
+                &[]
+            ),
+            [
+                ("Backpack", true, false),
+                ("Phantom", false, false),
+                ("Solflare", false, false),
+                ("Glow", false, false),
+            ]
+        );
+        assert_eq!(
+            list_parts(wallets.iter(), |_| true, |name| name, &[], &[])
+                .into_iter()
+                .map(|(name, ..)| name)
+                .collect::<Vec<_>>(),
+            ["Phantom", "Solflare", "Backpack", "Glow", "Nightly"]
+        );
     }
 }
@@ -0,0 +1,27 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Poll the `future` once without a runtime
+pub(crate) fn poll_once<F: Future>(future: F) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(NoopWaker));
+
+    pin!(future).poll(&mut Context::from_waker(&waker))
+}
+
+/// Get the output of a `future` that never waits, panicking if it is pending when first polled
+pub(crate) fn ready<F: Future>(future: F) -> F::Output {
+    match poll_once(future) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("The future should be ready when first polled"),
+    }
+}
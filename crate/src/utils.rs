@@ -116,7 +116,7 @@ impl Utils {
     /// It displays the first 4 characters and the last for characters
     /// separated by ellipsis eg `FXdl...RGd4` .
     /// If the string is less than 8 characters, an error is thrown
-    pub fn shorten_base58(base58_str: &str) -> WalletResult<Cow<str>> {
+    pub fn shorten_base58(base58_str: &str) -> WalletResult<Cow<'_, str>> {
         if base58_str.len() < 8 {
             return Err(WalletError::InvalidBase58Address);
         }
@@ -130,7 +130,7 @@ impl Utils {
     /// Same as [Self::shorten_base58] but with a custom range
     /// instead of taking the first 4 character and the last 4 characters
     /// it uses a custom range.
-    pub fn custom_shorten_base58(base58_str: &str, take: usize) -> WalletResult<Cow<str>> {
+    pub fn custom_shorten_base58(base58_str: &str, take: usize) -> WalletResult<Cow<'_, str>> {
         if base58_str.len() < take + take {
             return Err(WalletError::InvalidBase58Address);
         }
@@ -140,6 +140,67 @@ impl Utils {
 
         Ok(Cow::Borrowed(first_part) + "..." + last_part)
     }
+
+    /// Percent-encode a value for a URL query or path.
+    /// Only the unreserved characters `A-Z a-z 0-9 - . _ ~` are not encoded
+    pub fn percent_encode(value: &str) -> String {
+        value.bytes().fold(String::new(), |mut encoded, byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                encoded.push(byte as char);
+            } else {
+                encoded.push_str(&format!("%{byte:02X}"));
+            }
+
+            encoded
+        })
+    }
+
+    /// Decode a percent-encoded value of a URL query where `+` is a space
+    pub fn percent_decode(value: &str) -> WalletResult<String> {
+        let mut decoded = Vec::with_capacity(value.len());
+        let mut bytes = value.bytes();
+
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'%' => {
+                    // `u8::from_str_radix` accepts a leading `+` so each
+                    // of the two characters must be an ASCII hex digit
+                    let mut hex_digit = || {
+                        bytes
+                            .next()
+                            .and_then(|digit| (digit as char).to_digit(16))
+                            .ok_or(WalletError::InvalidPercentEncoding(value.to_string()))
+                    };
+                    let high = hex_digit()?;
+                    let low = hex_digit()?;
+
+                    decoded.push((high * 16 + low) as u8);
+                }
+                b'+' => decoded.push(b' '),
+                _ => decoded.push(byte),
+            }
+        }
+
+        String::from_utf8(decoded).or(Err(WalletError::InvalidPercentEncoding(value.to_string())))
+    }
+
+    /// Parse the query of a URL into decoded key and value pairs.
+    /// The `url` can be a full URL, a query starting with `?` or a bare query.
+    /// The fragment starting with `#` is ignored
+    pub fn parse_query(url: &str) -> WalletResult<Vec<(String, String)>> {
+        let query = url.split_once('?').map_or(url, |(_, query)| query);
+        let query = query.split_once('#').map_or(query, |(query, _)| query);
+
+        query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+                Ok((Self::percent_decode(key)?, Self::percent_decode(value)?))
+            })
+            .collect()
+    }
 }
 
 /// Perform reflection on a [JsValue]
@@ -435,3 +496,46 @@ impl Clone for Reflection {
         Reflection(self.0.clone())
     }
 }
+
+#[cfg(test)]
+mod utils_tests {
+    use super::*;
+
+    #[test]
+    fn percent_encoding() {
+        let value = "https://example.com/callback?a=1&b=two words";
+        let encoded = Utils::percent_encode(value);
+
+        assert_eq!(
+            encoded,
+            "https%3A%2F%2Fexample.com%2Fcallback%3Fa%3D1%26b%3Dtwo%20words"
+        );
+        assert_eq!(Utils::percent_decode(&encoded).unwrap(), value);
+        assert_eq!(Utils::percent_decode("two+words").unwrap(), "two words");
+        assert!(Utils::percent_decode("%G1").is_err());
+        assert!(Utils::percent_decode("%4").is_err());
+        assert!(Utils::percent_decode("%+1").is_err());
+        assert!(Utils::percent_decode("%-1").is_err());
+        assert!(Utils::percent_decode("%1 ").is_err());
+        assert_eq!(Utils::percent_decode("%2b%2F").unwrap(), "+/");
+    }
+
+    #[test]
+    fn query() {
+        assert_eq!(
+            Utils::parse_query(
+                "https://example.com/?errorCode=4001&errorMessage=User%20rejected#top"
+            )
+            .unwrap(),
+            vec![
+                ("errorCode".to_string(), "4001".to_string()),
+                ("errorMessage".to_string(), "User rejected".to_string())
+            ]
+        );
+        assert_eq!(
+            Utils::parse_query("?flag").unwrap(),
+            vec![("flag".to_string(), String::new())]
+        );
+        assert!(Utils::parse_query("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,697 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    rc::Rc,
+};
+
+use web_sys::{
+    js_sys::{Array, Error, Function, Object, Promise, Reflect, Uint8Array},
+    wasm_bindgen::{prelude::Closure, JsValue},
+    CustomEvent, CustomEventInit, Window,
+};
+
+use crate::{
+    Cluster, Reflection, SigninInput, WalletError, WalletErrorCode, WalletIcon, WalletResult,
+    SOLANA_SIGN_IN_IDENTIFIER, SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+    STANDARD_CONNECT_IDENTIFIER, STANDARD_DISCONNECT_IDENTIFIER, STANDARD_EVENTS_IDENTIFIER,
+    WINDOW_APP_READY_EVENT_TYPE, WINDOW_REGISTER_WALLET_EVENT_TYPE,
+};
+
+/// The version of the wallet standard `Wallet` interface and of the features
+/// implemented by [StandardWalletProvider]
+pub const STANDARD_WALLET_VERSION: &str = "1.0.0";
+
+/// A signer that [StandardWalletProvider] exposes to the apps on the page as a wallet standard wallet.
+/// The signer holds a single Ed25519 account.
+pub trait StandardWalletSigner {
+    /// The public key of the account
+    fn public_key(&self) -> [u8; 32];
+
+    /// Approve a `standard:connect` or `solana:signIn` request from an app
+    /// that is not connected, for example by prompting the user.
+    /// Return an error, like a [WalletError::WalletRequestError] with code `4001`,
+    /// to reject the connection
+    fn approve_connection(&self) -> impl Future<Output = WalletResult<()>>;
+
+    /// Sign the `message`, returning the Ed25519 signature.
+    /// Sign In With Solana messages are also signed by this method.
+    fn sign_message(&self, message: &[u8]) -> impl Future<Output = WalletResult<[u8; 64]>>;
+
+    /// Sign the serialized transaction, returning the serialized signed transaction
+    fn sign_transaction(
+        &self,
+        transaction_bytes: &[u8],
+    ) -> impl Future<Output = WalletResult<Vec<u8>>>;
+}
+
+struct ProviderState<S> {
+    signer: S,
+    name: String,
+    icon: WalletIcon,
+    chains: RefCell<Vec<Cluster>>,
+    extensions: RefCell<Vec<(String, JsValue)>>,
+    connected: Cell<bool>,
+    listeners: RefCell<Vec<(u32, Function)>>,
+    next_listener: Cell<u32>,
+}
+
+/// Exposes a Rust [StandardWalletSigner] to every app on the page, including JavaScript apps,
+/// as a wallet standard wallet. [Self::register] dispatches the
+/// `wallet-standard:register-wallet` event with a wallet object that implements
+/// `standard:connect`, `standard:disconnect`, `standard:events`, `solana:signMessage`,
+/// `solana:signTransaction` and `solana:signIn`, the same object a browser extension registers.
+pub struct StandardWalletProvider<S: StandardWalletSigner + 'static> {
+    state: Rc<ProviderState<S>>,
+}
+
+impl<S: StandardWalletSigner + 'static> StandardWalletProvider<S> {
+    /// Instantiate a provider for the `signer` registering a wallet with the `name` and `icon`.
+    /// The wallet supports [Cluster::DevNet] by default, see [Self::set_chains]
+    pub fn new(name: &str, icon: WalletIcon, signer: S) -> Self {
+        Self {
+            state: Rc::new(ProviderState {
+                signer,
+                name: name.to_string(),
+                icon,
+                chains: RefCell::new(vec![Cluster::DevNet]),
+                extensions: RefCell::default(),
+                connected: Cell::new(false),
+                listeners: RefCell::default(),
+                next_listener: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Set the chains supported by the wallet and its account.
+    /// The apps listening to `standard:events` are notified if the wallet is registered.
+    pub fn set_chains(&mut self, chains: &[Cluster]) -> &mut Self {
+        *self.state.chains.borrow_mut() = chains.to_vec();
+        self.emit_change(true);
+
+        self
+    }
+
+    /// Add a non-standard feature, like `my-wallet:feature`, described by the `feature` object.
+    /// This must be added before the wallet is [registered](Self::register)
+    pub fn add_extension(&mut self, identifier: &str, feature: JsValue) -> &mut Self {
+        self.state
+            .extensions
+            .borrow_mut()
+            .push((identifier.to_string(), feature));
+
+        self
+    }
+
+    /// Get the signer
+    pub fn signer(&self) -> &S {
+        &self.state.signer
+    }
+
+    /// Get the name of the wallet
+    pub fn name(&self) -> &str {
+        self.state.name.as_str()
+    }
+
+    /// Get the icon of the wallet
+    pub fn icon(&self) -> &WalletIcon {
+        &self.state.icon
+    }
+
+    /// Get the chains supported by the wallet
+    pub fn chains(&self) -> Vec<Cluster> {
+        self.state.chains.borrow().clone()
+    }
+
+    /// Get the Base58 address of the account
+    pub fn address(&self) -> String {
+        bs58::encode(self.state.signer.public_key()).into_string()
+    }
+
+    /// Check whether an app connected to the wallet
+    pub fn is_connected(&self) -> bool {
+        self.state.connected.get()
+    }
+
+    /// Disconnect the apps from the wallet side,
+    /// notifying the apps listening to `standard:events`
+    pub fn disconnect(&self) {
+        self.state.connected.set(false);
+        self.emit_change(false);
+    }
+
+    /// Register the wallet with the apps on the page using the `wallet-standard:register-wallet`
+    /// event, and with the apps initialized later using the `wallet-standard:app-ready` event
+    pub fn register(&self, window: &Window) -> WalletResult<()> {
+        let wallet = self.wallet_object()?;
+        let name = self.state.name.clone();
+
+        // The callback receives the `{ register }` object of the app
+        let register_callback = Closure::wrap(Box::new(move |api: JsValue| {
+            let outcome = Reflection::new(api.clone())
+                .and_then(|api| api.get_function("register"))
+                .and_then(|register| Ok(register.call1(&api, &wallet)?));
+
+            if let Err(error) = outcome {
+                wallet_log!(error, "Unable to register the `{name}` wallet: {error}");
+            }
+        }) as Box<dyn Fn(_)>);
+        let register_callback =
+            Reflection::new(register_callback.into_js_value())?.into_function()?;
+
+        let app_ready_callback = register_callback.clone();
+        let app_ready_listener = Closure::wrap(Box::new(move |custom_event: CustomEvent| {
+            if let Err(error) = app_ready_callback.call1(&JsValue::null(), &custom_event.detail()) {
+                wallet_log!(
+                    error,
+                    "Unable to register the wallet with the app: {error:?}"
+                );
+            }
+        }) as Box<dyn Fn(_)>);
+        window.add_event_listener_with_callback(
+            WINDOW_APP_READY_EVENT_TYPE,
+            &Reflection::new(app_ready_listener.into_js_value())?.into_function()?,
+        )?;
+
+        let register_init = CustomEventInit::new();
+        register_init.set_bubbles(false);
+        register_init.set_cancelable(false);
+        register_init.set_composed(false);
+        register_init.set_detail(&register_callback);
+
+        let register_event = CustomEvent::new_with_event_init_dict(
+            WINDOW_REGISTER_WALLET_EVENT_TYPE,
+            &register_init,
+        )?;
+        window.dispatch_event(&register_event)?;
+
+        wallet_log!(debug, "Registered the `{}` wallet", self.state.name);
+
+        Ok(())
+    }
+
+    /// Build the wallet standard wallet object whose features call into [Self]
+    fn wallet_object(&self) -> WalletResult<JsValue> {
+        let mut wallet = Reflection::new_object();
+        wallet.set_object_str("version", STANDARD_WALLET_VERSION)?;
+        wallet.set_object_str("name", &self.state.name)?;
+        wallet.set_object_str("icon", &self.state.icon.0)?;
+        wallet.set_object(&"features".into(), &self.features_object()?)?;
+        let wallet = wallet.take();
+
+        // `chains` and `accounts` are getters so that they reflect the current state
+        let provider = self.clone();
+        define_getter(&wallet, "chains", move || provider.chains_array())?;
+        let provider = self.clone();
+        define_getter(&wallet, "accounts", move || {
+            provider.accounts_array().unwrap_or_default()
+        })?;
+
+        Ok(wallet)
+    }
+
+    fn features_object(&self) -> WalletResult<JsValue> {
+        let mut features = Reflection::new_object();
+
+        let provider = self.clone();
+        let connect = Closure::wrap(Box::new(move |input: JsValue| {
+            let provider = provider.clone();
+
+            promise(async move { provider.connect_request(input).await })
+        }) as Box<dyn Fn(_) -> Promise>);
+        features.set_object(
+            &STANDARD_CONNECT_IDENTIFIER.into(),
+            &feature_object("connect", connect.into_js_value())?.take(),
+        )?;
+
+        let provider = self.clone();
+        let disconnect = Closure::wrap(Box::new(move || {
+            provider.disconnect();
+
+            Promise::resolve(&JsValue::UNDEFINED)
+        }) as Box<dyn Fn() -> Promise>);
+        features.set_object(
+            &STANDARD_DISCONNECT_IDENTIFIER.into(),
+            &feature_object("disconnect", disconnect.into_js_value())?.take(),
+        )?;
+
+        let provider = self.clone();
+        let on = Closure::wrap(Box::new(move |event: JsValue, listener: Function| {
+            provider.add_listener(event, listener)
+        }) as Box<dyn Fn(JsValue, Function) -> JsValue>);
+        features.set_object(
+            &STANDARD_EVENTS_IDENTIFIER.into(),
+            &feature_object("on", on.into_js_value())?.take(),
+        )?;
+
+        let provider = self.clone();
+        let sign_message = variadic(move |inputs| {
+            let provider = provider.clone();
+
+            promise(async move { provider.sign_message_request(inputs).await })
+        })?;
+        features.set_object(
+            &SOLANA_SIGN_MESSAGE_IDENTIFIER.into(),
+            &feature_object("signMessage", sign_message)?.take(),
+        )?;
+
+        let provider = self.clone();
+        let sign_transaction = variadic(move |inputs| {
+            let provider = provider.clone();
+
+            promise(async move { provider.sign_transaction_request(inputs).await })
+        })?;
+        let mut sign_transaction = feature_object("signTransaction", sign_transaction)?;
+        sign_transaction.set_object(
+            &"supportedTransactionVersions".into(),
+            &Array::of2(&"legacy".into(), &0.into()),
+        )?;
+        features.set_object(
+            &SOLANA_SIGN_TRANSACTION_IDENTIFIER.into(),
+            &sign_transaction.take(),
+        )?;
+
+        let provider = self.clone();
+        let sign_in = variadic(move |inputs| {
+            let provider = provider.clone();
+
+            promise(async move { provider.sign_in_request(inputs).await })
+        })?;
+        features.set_object(
+            &SOLANA_SIGN_IN_IDENTIFIER.into(),
+            &feature_object("signIn", sign_in)?.take(),
+        )?;
+
+        self.state
+            .extensions
+            .borrow()
+            .iter()
+            .try_for_each(|(identifier, feature)| {
+                features.set_object(&identifier.into(), feature)?;
+
+                Ok::<(), WalletError>(())
+            })?;
+
+        Ok(features.take())
+    }
+
+    async fn connect_request(&self, input: JsValue) -> WalletResult<JsValue> {
+        let silent = Reflect::get(&input, &"silent".into())
+            .ok()
+            .and_then(|silent| silent.as_bool())
+            .unwrap_or_default();
+
+        // A silent request returns the accounts only if the app is already connected
+        if !silent {
+            self.ensure_connected().await?;
+        }
+
+        let mut output = Reflection::new_object();
+        output.set_object(&"accounts".into(), &self.accounts_array()?.into())?;
+
+        Ok(output.take())
+    }
+
+    async fn sign_message_request(&self, inputs: Vec<JsValue>) -> WalletResult<JsValue> {
+        let outputs = Array::new();
+
+        for input in inputs {
+            self.check_account(&input)?;
+
+            let message = Reflection::new(input)?.reflect_bytes("message")?;
+            let signature = self.state.signer.sign_message(&message).await?;
+
+            let mut output = Reflection::new_object();
+            output.set_object(
+                &"signedMessage".into(),
+                &Uint8Array::from(message.as_slice()),
+            )?;
+            output.set_object(&"signature".into(), &Uint8Array::from(signature.as_slice()))?;
+            output.set_object_str("signatureType", "ed25519")?;
+            outputs.push(&output.take());
+        }
+
+        Ok(outputs.into())
+    }
+
+    async fn sign_transaction_request(&self, inputs: Vec<JsValue>) -> WalletResult<JsValue> {
+        let outputs = Array::new();
+
+        for input in inputs {
+            self.check_account(&input)?;
+
+            let input = Reflection::new(input)?;
+            if let Ok(chain) = input.reflect_inner("chain") {
+                let cluster: Cluster = Reflection::get_string(&chain)?.as_str().try_into()?;
+
+                if !self.state.chains.borrow().contains(&cluster) {
+                    return Err(WalletError::UnsupportedChain(cluster.chain().to_string()));
+                }
+            }
+
+            let transaction = input.reflect_bytes("transaction")?;
+            let signed_transaction = self.state.signer.sign_transaction(&transaction).await?;
+
+            let mut output = Reflection::new_object();
+            output.set_object(
+                &"signedTransaction".into(),
+                &Uint8Array::from(signed_transaction.as_slice()),
+            )?;
+            outputs.push(&output.take());
+        }
+
+        Ok(outputs.into())
+    }
+
+    async fn sign_in_request(&self, inputs: Vec<JsValue>) -> WalletResult<JsValue> {
+        let outputs = Array::new();
+        let address = self.address();
+
+        for input in inputs {
+            let mut signin_input = SigninInput::from_object(&input)?;
+
+            match signin_input.address() {
+                Some(input_address) if *input_address != address => {
+                    return Err(WalletError::SigninAddressMismatch)
+                }
+                Some(_) => (),
+                None => {
+                    signin_input.set_address(&address)?;
+                }
+            }
+
+            // The domain must be the page the request comes from so that a page can not
+            // request a message signing in to another domain. The wallet determines
+            // the domain if the app did not provide it
+            let window = web_sys::window().ok_or(WalletError::MissingAccessToBrowserWindow)?;
+            let host = window.location().host()?;
+            match signin_input.domain() {
+                Some(domain) if *domain != host => {
+                    return Err(WalletError::SigninDomainMismatch(domain.clone()))
+                }
+                Some(_) => (),
+                None => {
+                    signin_input.set_custom_domain(&host);
+                }
+            }
+
+            // Signing in connects the app
+            self.ensure_connected().await?;
+
+            let message = signin_input.to_message()?;
+            let signature = self.state.signer.sign_message(message.as_bytes()).await?;
+
+            let mut output = Reflection::new_object();
+            output.set_object(&"account".into(), &self.account_object()?)?;
+            output.set_object(
+                &"signedMessage".into(),
+                &Uint8Array::from(message.as_bytes()),
+            )?;
+            output.set_object(&"signature".into(), &Uint8Array::from(signature.as_slice()))?;
+            output.set_object_str("signatureType", "ed25519")?;
+            outputs.push(&output.take());
+        }
+
+        Ok(outputs.into())
+    }
+
+    /// Ask the signer to approve the connection if the app is not connected
+    async fn ensure_connected(&self) -> WalletResult<()> {
+        if !self.is_connected() {
+            self.state.signer.approve_connection().await?;
+
+            self.state.connected.set(true);
+            self.emit_change(false);
+        }
+
+        Ok(())
+    }
+
+    /// Check that the app is connected and that the request is for the account of the wallet
+    fn check_account(&self, input: &JsValue) -> WalletResult<()> {
+        if !self.is_connected() {
+            return Err(WalletError::AccountNotFound);
+        }
+
+        let address = Reflection::new_from_str(input, "account")?.string("address")?;
+        if address != self.address() {
+            return Err(WalletError::AccountNotFound);
+        }
+
+        Ok(())
+    }
+
+    fn account_object(&self) -> WalletResult<JsValue> {
+        let features = Array::new();
+        [
+            SOLANA_SIGN_IN_IDENTIFIER,
+            SOLANA_SIGN_MESSAGE_IDENTIFIER,
+            SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+        ]
+        .iter()
+        .for_each(|feature| {
+            features.push(&(*feature).into());
+        });
+
+        let mut account = Reflection::new_object();
+        account.set_object_str("address", &self.address())?;
+        account.set_object(
+            &"publicKey".into(),
+            &Uint8Array::from(self.state.signer.public_key().as_slice()),
+        )?;
+        account.set_object(&"chains".into(), &self.chains_array())?;
+        account.set_object(&"features".into(), &features)?;
+        account.set_object_str("label", &self.state.name)?;
+        account.set_object_str("icon", &self.state.icon.0)?;
+
+        Ok(account.take())
+    }
+
+    fn accounts_array(&self) -> WalletResult<Array> {
+        if self.is_connected() {
+            Ok(Array::of1(&self.account_object()?))
+        } else {
+            Ok(Array::new())
+        }
+    }
+
+    fn chains_array(&self) -> Array {
+        self.state
+            .chains
+            .borrow()
+            .iter()
+            .map(|cluster| JsValue::from(cluster.chain()))
+            .collect()
+    }
+
+    /// `standard:events` `on`, returning the function that removes the listener
+    fn add_listener(&self, event: JsValue, listener: Function) -> JsValue {
+        if event.as_string().as_deref() != Some("change") {
+            return Closure::wrap(Box::new(|| {}) as Box<dyn Fn()>).into_js_value();
+        }
+
+        let id = self.state.next_listener.get();
+        self.state.next_listener.set(id.wrapping_add(1));
+        self.state.listeners.borrow_mut().push((id, listener));
+
+        let provider = self.clone();
+        Closure::wrap(Box::new(move || {
+            provider
+                .state
+                .listeners
+                .borrow_mut()
+                .retain(|(listener_id, _)| *listener_id != id);
+        }) as Box<dyn Fn()>)
+        .into_js_value()
+    }
+
+    /// Notify the `change` listeners that the accounts, and the chains if `chains_changed`, changed
+    fn emit_change(&self, chains_changed: bool) {
+        let change = self.accounts_array().and_then(|accounts| {
+            let mut change = Reflection::new_object();
+            change.set_object(&"accounts".into(), &accounts)?;
+            if chains_changed {
+                change.set_object(&"chains".into(), &self.chains_array())?;
+            }
+
+            Ok(change.take())
+        });
+        let change = match change {
+            Ok(change) => change,
+            Err(error) => {
+                wallet_log!(error, "Unable to build the `change` event: {error}");
+                return;
+            }
+        };
+
+        // The listeners are called after releasing the borrow since they can call the wallet
+        let listeners = self
+            .state
+            .listeners
+            .borrow()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect::<Vec<Function>>();
+
+        listeners.iter().for_each(|listener| {
+            if let Err(error) = listener.call1(&JsValue::null(), &change) {
+                wallet_log!(error, "A `change` listener failed: {error:?}");
+            }
+        });
+    }
+}
+
+impl<S: StandardWalletSigner + 'static> Clone for StandardWalletProvider<S> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<S: StandardWalletSigner + core::fmt::Debug + 'static> core::fmt::Debug
+    for StandardWalletProvider<S>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StandardWalletProvider")
+            .field("name", &self.state.name)
+            .field("signer", &self.state.signer)
+            .field("chains", &self.state.chains.borrow())
+            .field("connected", &self.state.connected.get())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A wallet standard feature object with the `version` and the `callback` under `key`
+fn feature_object(key: &str, callback: JsValue) -> WalletResult<Reflection> {
+    let mut feature = Reflection::new_object();
+    feature.set_object_str("version", STANDARD_WALLET_VERSION)?;
+    feature.set_object(&key.into(), &callback)?;
+
+    Ok(feature)
+}
+
+/// Define the `key` property of the `object` as a getter
+fn define_getter<T: Into<JsValue> + 'static>(
+    object: &JsValue,
+    key: &str,
+    getter: impl Fn() -> T + 'static,
+) -> WalletResult<()> {
+    let getter = Closure::wrap(Box::new(move || getter().into()) as Box<dyn Fn() -> JsValue>);
+
+    let mut descriptor = Reflection::new_object();
+    descriptor.set_object(&"get".into(), &getter.into_js_value())?;
+    descriptor.set_object(&"enumerable".into(), &JsValue::TRUE)?;
+
+    Object::define_property(
+        &Object::from(object.clone()),
+        &key.into(),
+        &Object::from(descriptor.take()),
+    );
+
+    Ok(())
+}
+
+/// A function taking the `...inputs` rest parameter of the wallet standard `solana:` features
+/// and passing all the inputs to the `handler`. The request is rejected if an input is not an object
+fn variadic(handler: impl Fn(Vec<JsValue>) -> Promise + 'static) -> WalletResult<JsValue> {
+    let handler = Closure::wrap(Box::new(move |inputs: Array| {
+        match request_inputs(inputs.to_vec(), JsValue::is_object) {
+            Ok(inputs) => handler(inputs),
+            Err(error) => promise(async move { Err(error) }),
+        }
+    }) as Box<dyn Fn(Array) -> Promise>);
+
+    // A Rust closure has a fixed number of arguments so a JavaScript function
+    // collects the rest parameter into a single array
+    let forward = Function::new_with_args("handler", "return (...inputs) => handler(inputs);");
+
+    Ok(forward.call1(&JsValue::NULL, &handler.into_js_value())?)
+}
+
+// Check that every input is an object, keeping all the inputs in order
+// since the outputs of a request match its inputs one-to-one
+fn request_inputs<T>(inputs: Vec<T>, is_object: impl Fn(&T) -> bool) -> WalletResult<Vec<T>> {
+    match inputs.iter().position(|input| !is_object(input)) {
+        Some(index) => Err(WalletError::InvalidRequestInput(index)),
+        None => Ok(inputs),
+    }
+}
+
+/// Run the request, resolving the promise with the output or rejecting it
+/// with an `Error` carrying the wallet standard error `code`
+fn promise(request: impl Future<Output = WalletResult<JsValue>> + 'static) -> Promise {
+    wasm_bindgen_futures::future_to_promise(async move {
+        request.await.map_err(|error| {
+            let code = match error {
+                WalletError::AccountNotFound => WalletErrorCode::Unauthorized,
+                WalletError::UnsupportedChain(_) => WalletErrorCode::ChainDisconnected,
+                WalletError::InternalError(_) | WalletError::JsError { .. } => {
+                    WalletErrorCode::InternalError
+                }
+                _ if error.is_user_rejected() => WalletErrorCode::UserRejectedRequest,
+                _ => WalletErrorCode::InvalidParams,
+            };
+
+            let js_error = Error::new(&error.to_string());
+            if let Err(error) = Reflect::set(&js_error, &"code".into(), &code.code().into()) {
+                wallet_log!(error, "Unable to set the code of the error: {error:?}");
+            }
+
+            js_error.into()
+        })
+    })
+}
+
+#[cfg(test)]
+mod wallet_provider_tests {
+    use super::*;
+
+    #[test]
+    fn request_inputs_are_objects() {
+        // More inputs than a closure with fixed arguments would take
+        let inputs = (0..12).map(Some).collect::<Vec<_>>();
+        assert_eq!(request_inputs(inputs.clone(), Option::is_some), Ok(inputs));
+
+        assert_eq!(
+            request_inputs(Vec::<Option<u8>>::new(), Option::is_some),
+            Ok(Vec::new())
+        );
+
+        // An `undefined` input in the middle rejects the request instead of ending the inputs
+        let mut inputs = (0..12).map(Some).collect::<Vec<_>>();
+        inputs[9] = None;
+        assert_eq!(
+            request_inputs(inputs, Option::is_some),
+            Err(WalletError::InvalidRequestInput(9))
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_arch = "wasm32")]
+mod wallet_provider_wasm_tests {
+    use super::*;
+
+    #[test]
+    fn variadic_forwards_all_inputs() {
+        let received = Rc::new(Cell::new(0usize));
+        let handler_received = Rc::clone(&received);
+
+        let function = Function::from(
+            variadic(move |inputs| {
+                handler_received.set(inputs.len());
+
+                Promise::resolve(&JsValue::UNDEFINED)
+            })
+            .unwrap(),
+        );
+
+        let inputs = Array::new();
+        (0..12).for_each(|_| {
+            inputs.push(&Object::new());
+        });
+        Reflect::apply(&function, &JsValue::NULL, &inputs).unwrap();
+
+        assert_eq!(received.get(), 12);
+    }
+}
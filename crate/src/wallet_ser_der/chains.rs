@@ -1,4 +1,8 @@
-use crate::WalletError;
+use crate::{
+    WalletError, SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER, SOLANA_SIGN_IN_IDENTIFIER,
+    SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+    STANDARD_CONNECT_IDENTIFIER, STANDARD_DISCONNECT_IDENTIFIER, STANDARD_EVENTS_IDENTIFIER,
+};
 
 /// Solana Mainnet cluster,  [https://api.mainnet-beta.solana.com](https://api.mainnet-beta.solana.com)
 pub const MAINNET_IDENTIFIER: &str = "solana:mainnet";
@@ -41,6 +45,18 @@ pub struct ChainSupport {
     pub localnet: bool,
 }
 
+impl ChainSupport {
+    /// Check which of the `clusters` are supported
+    pub fn from_clusters(clusters: &[Cluster]) -> Self {
+        Self {
+            mainnet: clusters.contains(&Cluster::MainNet),
+            devnet: clusters.contains(&Cluster::DevNet),
+            testnet: clusters.contains(&Cluster::TestNet),
+            localnet: clusters.contains(&Cluster::LocalNet),
+        }
+    }
+}
+
 /// Used as a helper struct to contain all the features supported by a wallet
 /// as defined by the wallet standard
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
@@ -61,6 +77,27 @@ pub struct FeatureSupport {
     pub sign_tx: bool,
 }
 
+impl FeatureSupport {
+    /// Get the identifiers of the supported features, example `standard:connect`
+    pub fn identifiers(&self) -> Vec<&'static str> {
+        [
+            (self.connect, STANDARD_CONNECT_IDENTIFIER),
+            (self.disconnect, STANDARD_DISCONNECT_IDENTIFIER),
+            (self.events, STANDARD_EVENTS_IDENTIFIER),
+            (self.sign_in, SOLANA_SIGN_IN_IDENTIFIER),
+            (self.sign_message, SOLANA_SIGN_MESSAGE_IDENTIFIER),
+            (
+                self.sign_and_send_tx,
+                SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+            ),
+            (self.sign_tx, SOLANA_SIGN_TRANSACTION_IDENTIFIER),
+        ]
+        .into_iter()
+        .filter_map(|(supported, identifier)| supported.then_some(identifier))
+        .collect()
+    }
+}
+
 /// Solana Clusters
 #[derive(Debug, PartialEq, Eq, Default, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum Cluster {
@@ -141,6 +178,29 @@ impl TryFrom<&str> for Cluster {
 mod chain_tests {
     use super::*;
 
+    #[test]
+    fn chain_and_feature_support() {
+        assert_eq!(
+            ChainSupport::from_clusters(&[Cluster::MainNet, Cluster::LocalNet]),
+            ChainSupport {
+                mainnet: true,
+                localnet: true,
+                ..Default::default()
+            }
+        );
+
+        let supported_features = FeatureSupport {
+            connect: true,
+            sign_message: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            supported_features.identifiers(),
+            vec!["standard:connect", "solana:signMessage"]
+        );
+        assert!(FeatureSupport::default().identifiers().is_empty());
+    }
+
     #[test]
     fn is_valid_uri() {
         assert_eq!(MAINNET_ENDPOINT, "https://api.mainnet-beta.solana.com");
@@ -4,6 +4,9 @@ pub use version::*;
 mod wallet_icon;
 pub use wallet_icon::*;
 
+mod svg_sanitizer;
+pub use svg_sanitizer::*;
+
 mod chains;
 pub use chains::*;
 
@@ -18,3 +21,6 @@ pub use standard_features::*;
 
 mod signin_standard;
 pub use signin_standard::*;
+
+mod offchain_message;
+pub use offchain_message::*;
@@ -0,0 +1,445 @@
+use crate::{Utils, WalletError, WalletResult};
+
+/// The signing domain that prefixes every Solana off-chain message.
+/// The first byte `0xff` can never be the first byte of a serialized transaction
+/// so an off-chain message cannot be replayed as a transaction.
+pub const OFFCHAIN_MESSAGE_SIGNING_DOMAIN: &[u8; 16] = b"\xffsolana offchain";
+
+/// The maximum size in bytes of the serialized message, including the preamble,
+/// for the [OffchainMessageFormat::RestrictedAscii] and [OffchainMessageFormat::LimitedUtf8] formats
+pub const OFFCHAIN_MESSAGE_MAX_LEN_LEDGER: usize = 1232;
+
+/// The maximum size in bytes of the message body for the [OffchainMessageFormat::ExtendedUtf8] format
+pub const OFFCHAIN_MESSAGE_MAX_LEN_EXTENDED: usize = u16::MAX as usize;
+
+/// The header version of the Solana off-chain message supported
+pub const OFFCHAIN_MESSAGE_VERSION: u8 = 0;
+
+// signing domain + version + application domain + format + signer count + message length
+const PREAMBLE_LEN_WITHOUT_SIGNERS: usize = 16 + 1 + 32 + 1 + 1 + 2;
+
+/// The format of the body of a Solana off-chain message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum OffchainMessageFormat {
+    /// Printable ASCII characters in the range `0x20..=0x7e` which can be displayed by hardware wallets
+    #[default]
+    RestrictedAscii,
+    /// UTF-8 encoded text that fits in [OFFCHAIN_MESSAGE_MAX_LEN_LEDGER]
+    LimitedUtf8,
+    /// UTF-8 encoded text of up to [OFFCHAIN_MESSAGE_MAX_LEN_EXTENDED] bytes
+    ExtendedUtf8,
+}
+
+impl OffchainMessageFormat {
+    /// The byte representing the format in the header
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::RestrictedAscii => 0,
+            Self::LimitedUtf8 => 1,
+            Self::ExtendedUtf8 => 2,
+        }
+    }
+
+    /// Checks that the message body is valid for the format and fits in the
+    /// maximum length given the number of signers
+    pub fn validate(&self, message: &[u8], signers_count: usize) -> WalletResult<()> {
+        if message.is_empty() {
+            return Err(WalletError::InvalidOffchainMessage(
+                "The message body is empty".to_string(),
+            ));
+        }
+
+        let max_len = match self {
+            Self::RestrictedAscii | Self::LimitedUtf8 => OFFCHAIN_MESSAGE_MAX_LEN_LEDGER
+                .saturating_sub(PREAMBLE_LEN_WITHOUT_SIGNERS + signers_count * 32),
+            Self::ExtendedUtf8 => OFFCHAIN_MESSAGE_MAX_LEN_EXTENDED,
+        };
+
+        if message.len() > max_len {
+            return Err(WalletError::OffchainMessageTooLong(message.len()));
+        }
+
+        let is_valid = match self {
+            Self::RestrictedAscii => message.iter().all(|byte| (0x20..=0x7e).contains(byte)),
+            Self::LimitedUtf8 | Self::ExtendedUtf8 => core::str::from_utf8(message).is_ok(),
+        };
+
+        if !is_valid {
+            return Err(WalletError::InvalidOffchainMessage(format!(
+                "The message body is not valid for the `{self:?}` format"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<u8> for OffchainMessageFormat {
+    type Error = WalletError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::RestrictedAscii),
+            1 => Ok(Self::LimitedUtf8),
+            2 => Ok(Self::ExtendedUtf8),
+            _ => Err(WalletError::InvalidOffchainMessage(format!(
+                "Unsupported message format `{value}`"
+            ))),
+        }
+    }
+}
+
+/// A Solana off-chain message as defined by the
+/// [off-chain message signing proposal](https://docs.anza.xyz/proposals/off-chain-message-signing).
+/// The serialized message is what the signers sign, for example by passing it to
+/// [WalletAdapter::sign_message](crate::WalletAdapter::sign_message).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OffchainMessage {
+    application_domain: [u8; 32],
+    format: OffchainMessageFormat,
+    signers: Vec<[u8; 32]>,
+    message: Vec<u8>,
+}
+
+impl OffchainMessage {
+    /// Create a [OffchainMessageBuilder] for the message body
+    pub fn builder(message: &[u8]) -> OffchainMessageBuilder {
+        OffchainMessageBuilder::new(message)
+    }
+
+    /// Serialize [Self] into the bytes to be signed
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::<u8>::with_capacity(
+            PREAMBLE_LEN_WITHOUT_SIGNERS + self.signers.len() * 32 + self.message.len(),
+        );
+
+        bytes.extend_from_slice(OFFCHAIN_MESSAGE_SIGNING_DOMAIN);
+        bytes.push(OFFCHAIN_MESSAGE_VERSION);
+        bytes.extend_from_slice(&self.application_domain);
+        bytes.push(self.format.as_u8());
+        // The builder and parser ensure there are at most 255 signers
+        // and that the message length fits in a u16
+        bytes.push(self.signers.len() as u8);
+        self.signers
+            .iter()
+            .for_each(|signer| bytes.extend_from_slice(signer));
+        bytes.extend_from_slice(&(self.message.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&self.message);
+
+        bytes
+    }
+
+    /// Parse and validate a serialized Solana off-chain message
+    pub fn parse(bytes: &[u8]) -> WalletResult<Self> {
+        let invalid = |reason: &str| WalletError::InvalidOffchainMessage(reason.to_string());
+
+        let rest = bytes
+            .strip_prefix(OFFCHAIN_MESSAGE_SIGNING_DOMAIN.as_slice())
+            .ok_or(invalid("The signing domain is missing"))?;
+
+        let (version, rest) = rest
+            .split_first()
+            .ok_or(invalid("The version is missing"))?;
+        if *version != OFFCHAIN_MESSAGE_VERSION {
+            return Err(WalletError::InvalidOffchainMessage(format!(
+                "Unsupported header version `{version}`"
+            )));
+        }
+
+        if rest.len() < 32 + 1 + 1 {
+            return Err(invalid("The header is truncated"));
+        }
+        let (application_domain, rest) = rest.split_at(32);
+        let format = OffchainMessageFormat::try_from(rest[0])?;
+        let signers_count = rest[1] as usize;
+        let rest = &rest[2..];
+
+        if rest.len() < signers_count * 32 + 2 {
+            return Err(invalid("The signers are truncated"));
+        }
+        let (signers, rest) = rest.split_at(signers_count * 32);
+        let signers = signers
+            .chunks_exact(32)
+            .map(Utils::to32byte_array)
+            .collect::<WalletResult<Vec<[u8; 32]>>>()?;
+
+        let message_len = u16::from_le_bytes([rest[0], rest[1]]) as usize;
+        let message = &rest[2..];
+        if message.len() != message_len {
+            return Err(invalid(
+                "The message length does not match the length of the message body",
+            ));
+        }
+
+        OffchainMessageBuilder {
+            application_domain: Utils::to32byte_array(application_domain)?,
+            format: Some(format),
+            signers,
+            message: message.to_vec(),
+        }
+        .build()
+    }
+
+    /// Verify that `signature` is the signature of the serialized message
+    /// by `public_key` which has to be one of the signers
+    pub fn verify(&self, public_key: [u8; 32], signature: [u8; 64]) -> WalletResult<()> {
+        if !self.signers.contains(&public_key) {
+            return Err(WalletError::OffchainMessageSignerNotFound);
+        }
+
+        Utils::verify_signature(
+            Utils::public_key(public_key)?,
+            &self.serialize(),
+            Utils::signature(signature),
+        )
+    }
+
+    /// Get the application domain
+    pub fn application_domain(&self) -> &[u8; 32] {
+        &self.application_domain
+    }
+
+    /// Get the message format
+    pub fn format(&self) -> OffchainMessageFormat {
+        self.format
+    }
+
+    /// Get the public keys of the signers
+    pub fn signers(&self) -> &[[u8; 32]] {
+        self.signers.as_slice()
+    }
+
+    /// Get the message body
+    pub fn message(&self) -> &[u8] {
+        self.message.as_slice()
+    }
+
+    /// Get the message body as a [str], the body is always valid UTF-8
+    pub fn message_str(&self) -> &str {
+        core::str::from_utf8(&self.message).unwrap_or_default()
+    }
+}
+
+/// Builds a [OffchainMessage]. If the format is not set, the most restrictive format
+/// that can hold the message body is used.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OffchainMessageBuilder {
+    application_domain: [u8; 32],
+    format: Option<OffchainMessageFormat>,
+    signers: Vec<[u8; 32]>,
+    message: Vec<u8>,
+}
+
+impl OffchainMessageBuilder {
+    /// Instantiate [Self] with the message body
+    pub fn new(message: &[u8]) -> Self {
+        Self {
+            message: message.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the 32 byte application domain, which identifies the application
+    /// requesting the signature. Defaults to zeroes.
+    pub fn set_application_domain(&mut self, application_domain: [u8; 32]) -> &mut Self {
+        self.application_domain = application_domain;
+
+        self
+    }
+
+    /// Set the message format instead of detecting it
+    pub fn set_format(&mut self, format: OffchainMessageFormat) -> &mut Self {
+        self.format.replace(format);
+
+        self
+    }
+
+    /// Add the public key of a signer
+    pub fn add_signer(&mut self, public_key: [u8; 32]) -> &mut Self {
+        self.signers.push(public_key);
+
+        self
+    }
+
+    /// Validate and build the [OffchainMessage]
+    pub fn build(&self) -> WalletResult<OffchainMessage> {
+        if self.signers.is_empty() {
+            return Err(WalletError::InvalidOffchainMessage(
+                "At least one signer is required".to_string(),
+            ));
+        }
+
+        if self.signers.len() > u8::MAX as usize {
+            return Err(WalletError::InvalidOffchainMessage(
+                "At most 255 signers are supported".to_string(),
+            ));
+        }
+
+        let format = match self.format {
+            Some(format) => format,
+            None => self.detect_format()?,
+        };
+        format.validate(&self.message, self.signers.len())?;
+
+        Ok(OffchainMessage {
+            application_domain: self.application_domain,
+            format,
+            signers: self.signers.clone(),
+            message: self.message.clone(),
+        })
+    }
+
+    fn detect_format(&self) -> WalletResult<OffchainMessageFormat> {
+        let mut last_error = None;
+
+        for format in [
+            OffchainMessageFormat::RestrictedAscii,
+            OffchainMessageFormat::LimitedUtf8,
+            OffchainMessageFormat::ExtendedUtf8,
+        ] {
+            match format.validate(&self.message, self.signers.len()) {
+                Ok(()) => return Ok(format),
+                // Report the length error over the content error of a more restrictive format
+                Err(error @ WalletError::OffchainMessageTooLong(_)) => last_error = Some(error),
+                Err(error) => {
+                    if !matches!(last_error, Some(WalletError::OffchainMessageTooLong(_))) {
+                        last_error = Some(error)
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or(WalletError::InvalidOffchainMessage(
+            "The message body is invalid".to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod offchain_message_tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    fn signer() -> SigningKey {
+        SigningKey::from_bytes(&[9u8; 32])
+    }
+
+    fn message(body: &[u8]) -> WalletResult<OffchainMessage> {
+        OffchainMessage::builder(body)
+            .add_signer(signer().verifying_key().to_bytes())
+            .build()
+    }
+
+    #[test]
+    fn serialize_layout() {
+        let message = OffchainMessage::builder(b"Hello")
+            .set_application_domain([1u8; 32])
+            .add_signer([2u8; 32])
+            .build()
+            .unwrap();
+
+        let mut expected = b"\xffsolana offchain".to_vec();
+        expected.push(0);
+        expected.extend_from_slice(&[1u8; 32]);
+        expected.push(0);
+        expected.push(1);
+        expected.extend_from_slice(&[2u8; 32]);
+        expected.extend_from_slice(&[5, 0]);
+        expected.extend_from_slice(b"Hello");
+
+        let bytes = message.serialize();
+        assert_eq!(bytes, expected);
+        assert_eq!(OffchainMessage::parse(&bytes).unwrap(), message);
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(
+            message(b"Sign in").unwrap().format(),
+            OffchainMessageFormat::RestrictedAscii
+        );
+        assert_eq!(
+            message(b"Line one\nLine two").unwrap().format(),
+            OffchainMessageFormat::LimitedUtf8
+        );
+        assert_eq!(
+            message("Karibu 👋".as_bytes()).unwrap().format(),
+            OffchainMessageFormat::LimitedUtf8
+        );
+        assert_eq!(
+            message(&[b'a'; 2000]).unwrap().format(),
+            OffchainMessageFormat::ExtendedUtf8
+        );
+
+        assert!(matches!(
+            message(&[0xc3, 0x28]),
+            Err(WalletError::InvalidOffchainMessage(_))
+        ));
+        assert!(matches!(
+            message(b""),
+            Err(WalletError::InvalidOffchainMessage(_))
+        ));
+        assert_eq!(
+            message(&[b'a'; 70_000]),
+            Err(WalletError::OffchainMessageTooLong(70_000))
+        );
+        assert!(matches!(
+            OffchainMessage::builder("👋".as_bytes())
+                .set_format(OffchainMessageFormat::RestrictedAscii)
+                .add_signer([2u8; 32])
+                .build(),
+            Err(WalletError::InvalidOffchainMessage(_))
+        ));
+        assert!(matches!(
+            OffchainMessage::builder(b"No signers").build(),
+            Err(WalletError::InvalidOffchainMessage(_))
+        ));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let bytes = message(b"Hello").unwrap().serialize();
+
+        assert!(OffchainMessage::parse(&bytes[1..]).is_err());
+        assert!(OffchainMessage::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(OffchainMessage::parse(&[bytes.as_slice(), b"!"].concat()).is_err());
+
+        let mut unsupported_version = bytes.clone();
+        unsupported_version[16] = 1;
+        assert!(OffchainMessage::parse(&unsupported_version).is_err());
+
+        let mut invalid_format = bytes.clone();
+        invalid_format[49] = 3;
+        assert!(OffchainMessage::parse(&invalid_format).is_err());
+    }
+
+    #[test]
+    fn verify() {
+        let message = message(b"Sign this").unwrap();
+        let signature = signer().sign(&message.serialize()).to_bytes();
+
+        assert!(message
+            .verify(signer().verifying_key().to_bytes(), signature)
+            .is_ok());
+        assert_eq!(
+            message.verify(signer().verifying_key().to_bytes(), [0u8; 64]),
+            Err(WalletError::InvalidSignature)
+        );
+
+        let other = SigningKey::from_bytes(&[10u8; 32]);
+        assert_eq!(
+            message.verify(
+                other.verifying_key().to_bytes(),
+                other.sign(&message.serialize()).to_bytes()
+            ),
+            Err(WalletError::OffchainMessageSignerNotFound)
+        );
+
+        // A signature of the message body alone is not valid
+        let body_signature = signer().sign(message.message()).to_bytes();
+        assert!(message
+            .verify(signer().verifying_key().to_bytes(), body_signature)
+            .is_err());
+    }
+}
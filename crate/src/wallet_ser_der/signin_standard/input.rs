@@ -12,48 +12,83 @@ use crate::{Cluster, Reflection, Utils, WalletError, WalletResult};
 /// `SignInWithSolana (SIWS)` requests as defined by the
 /// [SIWS](https://github.com/phantom/sign-in-with-solana) standard.
 /// A backup fork can be found at [https://github.com/JamiiDao/sign-in-with-solana](https://github.com/JamiiDao/sign-in-with-solana)
+/// With the `serde` feature, [Self] is (de)serialized using the shape of the wallet standard
+/// `SolanaSignInInput` where the times are ISO 8601 datetime strings.
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "camelCase")
+)]
 pub struct SigninInput {
     /// Optional EIP-4361 domain requesting the sign-in.
     /// If not provided, the wallet must determine the domain to include in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     domain: Option<String>,
     /// Optional Solana Base58 address performing the sign-in.
     /// The address is case-sensitive.
     /// If not provided, the wallet must determine the Address to include in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     address: Option<String>,
     /// Optional EIP-4361 Statement.
     /// The statement is a human readable string and should not have new-line characters (\n).
     /// If not provided, the wallet does not include Statement in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     statement: Option<String>,
     /// Optional EIP-4361 URI.
     /// The URL that is requesting the sign-in.
     /// If not provided, the wallet does not include URI in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uri: Option<String>,
     /// Optional EIP-4361 version.
     /// If not provided, the wallet does not include Version in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     version: Option<String>,
     /// Optional EIP-4361 Chain ID.
     /// The chainId can be one of the following:
     /// mainnet, testnet, devnet, localnet, solana:mainnet, solana:testnet, solana:devnet.
     /// If not provided, the wallet does not include Chain ID in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     chain_id: Option<Cluster>,
     /// Optional EIP-4361 Nonce.
     /// It should be an alphanumeric string containing a minimum of 8 characters.
     /// If not provided, the wallet does not include Nonce in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     nonce: Option<String>,
     /// Optional ISO 8601 datetime string.
     /// This represents the time at which the sign-in request was issued to the wallet.
     /// Note: For Phantom, issuedAt has a threshold and it should be
     /// within +- 10 minutes from the timestamp at which verification is taking place.
     /// If not provided, the wallet does not include Issued At in the message.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::serde_support::iso8601_option",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
     issued_at: Option<SystemTime>,
     /// Optional ISO 8601 datetime string.
     /// This represents the time at which the sign-in request should expire.
     /// If not provided, the wallet does not include Expiration Time in the message.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::serde_support::iso8601_option",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
     expiration_time: Option<SystemTime>,
     /// Optional ISO 8601 datetime string.
     /// This represents the time at which the sign-in request becomes valid.
     /// If not provided, the wallet does not include Not Before in the message.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::serde_support::iso8601_option",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
     not_before: Option<SystemTime>,
     /// Optional EIP-4361 Request ID.
     /// In addition to using nonce to avoid replay attacks,
@@ -61,6 +96,7 @@ pub struct SigninInput {
     /// Once the wallet returns the signed message,
     /// dapps can then verify this signature against the state to add an additional,
     /// strong layer of security. If not provided, the wallet does not include Request ID in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     request_id: Option<String>,
     /// Optional EIP-4361 Resources.
     /// Usually a list of references in the form of URIs that the
@@ -68,6 +104,7 @@ pub struct SigninInput {
     /// These URIs should be separated by \n-, ie,
     /// URIs in new lines starting with the character -.
     /// If not provided, the wallet does not include Resources in the message.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
     resources: Vec<String>,
 }
 
@@ -171,7 +208,7 @@ impl SigninInput {
     /// This is generated from the Cryptographically Secure Random Number Generator
     /// and the bytes converted to hex formatted string.
     pub fn custom_nonce(&mut self, nonce: &str) -> WalletResult<&mut Self> {
-        if nonce.len() < 8 {
+        if nonce.len() < 8 || !nonce.chars().all(|char| char.is_ascii_alphanumeric()) {
             return Err(WalletError::NonceMustBeAtLeast8Characters);
         }
         self.nonce.replace(nonce.to_string());
@@ -373,87 +410,157 @@ impl SigninInput {
         Ok(signin_input_object.take())
     }
 
-    /// Parses the Sign In With Solana (SIWS) result of the Response from a wallet
-    pub fn parser(input: &str) -> WalletResult<Self> {
-        let mut signin_input = Self::default();
-
-        input
-            .split_once(" ")
-            .map(|(left, _right)| signin_input.domain.replace(left.trim().to_string()));
-
-        let split_colon = |value: &str| {
-            value
-                .split_once(":")
-                .map(|(_left, right)| right.trim().to_string())
+    /// Parse [Self] from the `SolanaSignInInput` object an app passes to a wallet,
+    /// the reverse of [Self::get_object]
+    pub(crate) fn from_object(value: &JsValue) -> WalletResult<Self> {
+        let string = |key: &str| -> WalletResult<Option<String>> {
+            Ok(js_sys::Reflect::get(value, &key.into())?.as_string())
         };
-
-        let split_colon_system_time = |value: &str| -> WalletResult<Option<SystemTime>> {
-            value
-                .split_once(":")
-                .map(|(_left, right)| {
-                    humantime::parse_rfc3339(right.trim())
-                        .or(Err(WalletError::InvalidISO8601Timestamp(right.to_string())))
+        let time = |key: &str| -> WalletResult<Option<SystemTime>> {
+            string(key)?
+                .map(|timestamp| {
+                    humantime::parse_rfc3339(&timestamp)
+                        .or(Err(WalletError::InvalidISO8601Timestamp(timestamp)))
                 })
                 .transpose()
         };
 
-        input
-            .split("\n")
-            .enumerate()
-            .try_for_each(|(index, input)| {
-                if index == 1 {
-                    signin_input.address.replace(input.trim().to_string());
-                }
+        let mut signin_input = Self::new();
 
-                if index == 3 {
-                    signin_input.statement.replace(input.trim().to_string());
-                }
+        if let Some(address) = string("address")? {
+            signin_input.set_address(&address)?;
+        }
+        if let Some(nonce) = string("nonce")? {
+            signin_input.custom_nonce(&nonce)?;
+        }
+        signin_input.chain_id = string("chainId")?
+            .map(|chain| chain.as_str().try_into())
+            .transpose()?;
+        signin_input.domain = string("domain")?;
+        signin_input.statement = string("statement")?;
+        signin_input.uri = string("uri")?;
+        signin_input.version = string("version")?;
+        signin_input.issued_at = time("issuedAt")?;
+        signin_input.expiration_time = time("expirationTime")?;
+        signin_input.not_before = time("notBefore")?;
+        signin_input.request_id = string("requestId")?;
+
+        let resources = js_sys::Reflect::get(value, &"resources".into())?;
+        if Array::is_array(&resources) {
+            signin_input.resources = Array::from(&resources)
+                .iter()
+                .filter_map(|resource| resource.as_string())
+                .collect();
+        }
 
-                if input.contains("URI") {
-                    signin_input.uri = split_colon(input);
-                }
+        Ok(signin_input)
+    }
 
-                if input.contains("Version") {
-                    signin_input.version = split_colon(input);
-                }
+    /// Renders the Sign In With Solana (SIWS) message as defined by the
+    /// [SIWS ABNF](https://github.com/phantom/sign-in-with-solana#abnf-message-format).
+    /// This is the exact message a wallet is expected to sign for this input.
+    /// The `domain` and `address` fields are required.
+    pub fn to_message(&self) -> WalletResult<String> {
+        let domain = self
+            .domain
+            .as_ref()
+            .ok_or(WalletError::MissingSigninField("domain".to_string()))?;
+        let address = self
+            .address
+            .as_ref()
+            .ok_or(WalletError::MissingSigninField("address".to_string()))?;
+
+        // Every value is checked against the same rules as [Self::parser]
+        // so that the message is read back as this input
+        if domain.is_empty() || domain.contains(char::is_whitespace) {
+            return Err(SiwsParser::error(
+                1,
+                "The domain must not be empty or contain whitespace",
+            ));
+        }
+        if !SiwsParser::is_address(address) {
+            return Err(SiwsParser::error(
+                2,
+                "The address is not a valid Base58 Solana public key",
+            ));
+        }
 
-                if input.contains("Chain ID") {
-                    if let Some((_left, right)) = input.split_once(":") {
-                        let cluster: Cluster = right.trim().try_into()?;
+        let mut message = String::new() + domain + SIWS_HEADER_SUFFIX + "\n" + address;
 
-                        signin_input.chain_id.replace(cluster);
-                    }
-                }
-                if input.contains("Nonce") {
-                    signin_input.nonce = split_colon(input);
-                }
+        if let Some(statement) = self.statement.as_ref().filter(|value| !value.is_empty()) {
+            if statement.contains(['\n', '\r']) {
+                return Err(SiwsParser::error(
+                    4,
+                    "The statement must not contain new-line characters",
+                ));
+            }
+            if SiwsParser::is_field(statement) {
+                return Err(SiwsParser::error(
+                    4,
+                    "The statement must not look like a `<field>: <value>` line",
+                ));
+            }
 
-                if input.contains("Issued At") {
-                    signin_input.issued_at = split_colon_system_time(input)?;
-                }
+            message.push_str("\n\n");
+            message.push_str(statement);
+        }
 
-                if input.contains("Expiration") {
-                    signin_input.expiration_time = split_colon_system_time(input)?;
-                }
+        // The fields start after the empty line following the header or the statement
+        let fields_start = message.split('\n').count() + 2;
+        let mut fields = Vec::<String>::new();
+        let mut push_field = |position: usize, value: Option<&String>| -> WalletResult<()> {
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                SiwsParser::check_field(position, value, fields_start + fields.len())?;
 
-                if input.contains("Not Before") {
-                    signin_input.not_before = split_colon_system_time(input)?;
-                }
+                fields.push(String::new() + SIWS_FIELDS[position] + ": " + value);
+            }
 
-                if input.contains("Request ID") {
-                    signin_input.request_id = split_colon(input);
-                }
+            Ok(())
+        };
+
+        push_field(0, self.uri.as_ref())?;
+        push_field(1, self.version.as_ref())?;
+        push_field(
+            2,
+            self.chain_id
+                .as_ref()
+                .map(|cluster| cluster.chain().to_string())
+                .as_ref(),
+        )?;
+        push_field(3, self.nonce.as_ref())?;
+        push_field(4, self.issued_at_iso8601().as_ref())?;
+        push_field(5, self.expiration_time_iso8601().as_ref())?;
+        push_field(6, self.not_before_iso8601().as_ref())?;
+        push_field(7, self.request_id.as_ref())?;
 
-                if input.starts_with("-") {
-                    if let Some(value) = input.split("-").nth(1) {
-                        signin_input.resources.push(value.trim().to_string());
-                    }
+        if !self.resources.is_empty() {
+            fields.push(SIWS_RESOURCES.to_string());
+
+            for resource in self.resources.iter() {
+                if !SiwsParser::is_uri(resource) {
+                    return Err(SiwsParser::error(
+                        fields_start + fields.len(),
+                        "Expected a `- <URI>` resource",
+                    ));
                 }
 
-                Ok::<(), WalletError>(())
-            })?;
+                fields.push(String::new() + "- " + resource);
+            }
+        }
 
-        Ok(signin_input)
+        if !fields.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(&fields.join("\n"));
+        }
+
+        Ok(message)
+    }
+
+    /// Parses a Sign In With Solana (SIWS) message, like the message signed by a wallet,
+    /// strictly following the [SIWS ABNF](https://github.com/phantom/sign-in-with-solana#abnf-message-format).
+    /// Returns [WalletError::InvalidSigninMessage] with the line that does not conform to the grammar.
+    pub fn parser(input: &str) -> WalletResult<Self> {
+        SiwsParser::new(input).parse()
     }
 
     /// Checks if the response of a Sign In With Solana (SIWS) from the Wallet is the same as the
@@ -592,6 +699,605 @@ impl SigninInput {
     }
 }
 
+const SIWS_HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+const SIWS_RESOURCES: &str = "Resources:";
+// The optional fields of a SIWS message in the order required by the ABNF
+const SIWS_FIELDS: [&str; 8] = [
+    "URI",
+    "Version",
+    "Chain ID",
+    "Nonce",
+    "Issued At",
+    "Expiration Time",
+    "Not Before",
+    "Request ID",
+];
+
+// Parses a SIWS message line by line where each line
+// has to match the next expected rule of the ABNF
+struct SiwsParser<'a> {
+    lines: Vec<&'a str>,
+    signin_input: SigninInput,
+}
+
+impl<'a> SiwsParser<'a> {
+    fn new(input: &'a str) -> Self {
+        let mut lines = input.split('\n').collect::<Vec<&str>>();
+
+        // Trailing new-lines after the last rule are allowed
+        while lines.len() > 2 && lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        Self {
+            lines,
+            signin_input: SigninInput::default(),
+        }
+    }
+
+    fn error(line: usize, reason: &str) -> WalletError {
+        WalletError::InvalidSigninMessage {
+            line,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn parse(mut self) -> WalletResult<SigninInput> {
+        self.header()?;
+        self.address()?;
+
+        if self.lines.len() == 2 {
+            return Ok(self.signin_input);
+        }
+
+        self.empty_line(2, "Expected an empty line after the address")?;
+
+        let mut fields_start = 3;
+
+        if !Self::is_field(self.lines[3]) {
+            self.statement()?;
+
+            if self.lines.len() == 4 {
+                return Ok(self.signin_input);
+            }
+
+            self.empty_line(4, "Expected an empty line after the statement")?;
+            fields_start = 5;
+        }
+
+        self.fields(fields_start)?;
+
+        Ok(self.signin_input)
+    }
+
+    fn header(&mut self) -> WalletResult<()> {
+        let domain = self.lines[0]
+            .strip_suffix(SIWS_HEADER_SUFFIX)
+            .ok_or(Self::error(
+                1,
+                "Expected `<domain> wants you to sign in with your Solana account:`",
+            ))?;
+
+        if domain.is_empty() || domain.contains(char::is_whitespace) {
+            return Err(Self::error(
+                1,
+                "The domain must not be empty or contain whitespace",
+            ));
+        }
+
+        self.signin_input.domain.replace(domain.to_string());
+
+        Ok(())
+    }
+
+    fn address(&mut self) -> WalletResult<()> {
+        let address = self
+            .lines
+            .get(1)
+            .filter(|line| !line.is_empty())
+            .ok_or(Self::error(2, "Expected the Base58 address"))?;
+
+        if !Self::is_address(address) {
+            return Err(Self::error(
+                2,
+                "The address is not a valid Base58 Solana public key",
+            ));
+        }
+
+        self.signin_input.address.replace(address.to_string());
+
+        Ok(())
+    }
+
+    fn empty_line(&self, index: usize, reason: &str) -> WalletResult<()> {
+        if self.lines[index].is_empty() {
+            Ok(())
+        } else {
+            Err(Self::error(index + 1, reason))
+        }
+    }
+
+    fn statement(&mut self) -> WalletResult<()> {
+        let statement = self.lines[3];
+
+        if statement.is_empty() {
+            return Err(Self::error(4, "Expected a statement or a field"));
+        }
+
+        self.signin_input.statement.replace(statement.to_string());
+
+        Ok(())
+    }
+
+    fn is_field(line: &str) -> bool {
+        line == SIWS_RESOURCES
+            || SIWS_FIELDS.iter().any(|field| {
+                line.strip_prefix(field)
+                    .is_some_and(|value| value.starts_with(": "))
+            })
+    }
+
+    fn fields(&mut self, start: usize) -> WalletResult<()> {
+        let mut next_field = 0usize;
+
+        for index in start..self.lines.len() {
+            let line = self.lines[index];
+            let line_number = index + 1;
+
+            if line == SIWS_RESOURCES {
+                return self.resources(index + 1);
+            }
+
+            let (name, value) = line.split_once(": ").ok_or(Self::error(
+                line_number,
+                "Expected a `<field>: <value>` line",
+            ))?;
+
+            let position = SIWS_FIELDS
+                .iter()
+                .position(|field| *field == name)
+                .ok_or(Self::error(line_number, &format!("Unknown field `{name}`")))?;
+
+            if position < next_field {
+                return Err(Self::error(
+                    line_number,
+                    &format!("The field `{name}` is duplicated or out of order"),
+                ));
+            }
+            next_field = position + 1;
+
+            if value.is_empty() {
+                return Err(Self::error(
+                    line_number,
+                    &format!("The field `{name}` has no value"),
+                ));
+            }
+
+            self.field(position, value, line_number)?;
+        }
+
+        Ok(())
+    }
+
+    // Checks the value of the field at `position` of [SIWS_FIELDS]
+    fn check_field(position: usize, value: &str, line_number: usize) -> WalletResult<()> {
+        if value.contains(['\n', '\r']) {
+            return Err(Self::error(
+                line_number,
+                &format!(
+                    "The field `{}` must not contain new-line characters",
+                    SIWS_FIELDS[position]
+                ),
+            ));
+        }
+
+        match position {
+            0 if !Self::is_uri(value) => {
+                Err(Self::error(line_number, &format!("Invalid URI `{value}`")))
+            }
+            1 if value != "1" => Err(Self::error(line_number, "Only version `1` is supported")),
+            3 if value.len() < 8 || !value.chars().all(|char| char.is_ascii_alphanumeric()) => {
+                Err(Self::error(
+                    line_number,
+                    "The nonce must be at least 8 alphanumeric characters",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn field(&mut self, position: usize, value: &str, line_number: usize) -> WalletResult<()> {
+        let parse_time = |value: &str| {
+            humantime::parse_rfc3339(value).or(Err(Self::error(
+                line_number,
+                &format!("Invalid ISO 8601 timestamp `{value}`"),
+            )))
+        };
+
+        Self::check_field(position, value, line_number)?;
+
+        let signin_input = &mut self.signin_input;
+
+        match position {
+            0 => {
+                signin_input.uri.replace(value.to_string());
+            }
+            1 => {
+                signin_input.version.replace(value.to_string());
+            }
+            2 => {
+                let cluster = Cluster::try_from(value)
+                    .map_err(|error| Self::error(line_number, &error.to_string()))?;
+                signin_input.chain_id.replace(cluster);
+            }
+            3 => {
+                signin_input.nonce.replace(value.to_string());
+            }
+            4 => {
+                signin_input.issued_at.replace(parse_time(value)?);
+            }
+            5 => {
+                signin_input.expiration_time.replace(parse_time(value)?);
+            }
+            6 => {
+                signin_input.not_before.replace(parse_time(value)?);
+            }
+            _ => {
+                signin_input.request_id.replace(value.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resources(&mut self, start: usize) -> WalletResult<()> {
+        for index in start..self.lines.len() {
+            let resource = self.lines[index]
+                .strip_prefix("- ")
+                .filter(|resource| Self::is_uri(resource))
+                .ok_or(Self::error(index + 1, "Expected a `- <URI>` resource"))?;
+
+            self.signin_input.resources.push(resource.to_string());
+        }
+
+        Ok(())
+    }
+
+    fn is_address(value: &str) -> bool {
+        let mut buffer = [0u8; 32];
+
+        matches!(bs58::decode(value).onto(&mut buffer), Ok(32))
+    }
+
+    // A URI as defined by RFC 3986 starts with a scheme followed by `:`
+    fn is_uri(value: &str) -> bool {
+        value.split_once(':').is_some_and(|(scheme, _)| {
+            scheme.starts_with(|char: char| char.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || "+-.".contains(char))
+        }) && !value.contains(char::is_whitespace)
+    }
+}
+
+#[cfg(test)]
+mod siws_message_tests {
+    use super::*;
+
+    const ADDRESS: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+
+    fn parse_error(message: &str) -> (usize, String) {
+        match SigninInput::parser(message) {
+            Err(WalletError::InvalidSigninMessage { line, reason }) => (line, reason),
+            outcome => panic!("Expected an InvalidSigninMessage error, got {outcome:?}"),
+        }
+    }
+
+    // Reference message from the SIWS specification
+    #[test]
+    fn reference_vector() {
+        let message = "example.com wants you to sign in with your Solana account:
+7ySWXYGu3dEsLBUrDN3d6GS7qBb4EYxnrAc6KmyCGjDk
+
+Sign in to Example
+
+URI: https://example.com
+Version: 1
+Chain ID: mainnet
+Nonce: 32891756
+Issued At: 2021-09-30T16:25:24Z
+Resources:
+- ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/
+- https://example.com/my-web2-claim.json";
+
+        let signin_input = SigninInput::parser(message).unwrap();
+
+        assert_eq!(signin_input.domain().unwrap(), "example.com");
+        assert_eq!(
+            signin_input.address().unwrap(),
+            "7ySWXYGu3dEsLBUrDN3d6GS7qBb4EYxnrAc6KmyCGjDk"
+        );
+        assert_eq!(signin_input.statement().unwrap(), "Sign in to Example");
+        assert_eq!(signin_input.uri().unwrap(), "https://example.com");
+        assert_eq!(signin_input.version().unwrap(), "1");
+        assert_eq!(signin_input.chain_id(), Some(&Cluster::MainNet));
+        assert_eq!(signin_input.nonce().unwrap(), "32891756");
+        assert_eq!(
+            signin_input.issued_at_iso8601().unwrap(),
+            "2021-09-30T16:25:24.000Z"
+        );
+        assert!(signin_input.expiration_time().is_none());
+        assert!(signin_input.request_id().is_none());
+        assert_eq!(signin_input.resources().len(), 2);
+    }
+
+    #[test]
+    fn to_message_round_trip() {
+        let issued_at = humantime::parse_rfc3339("2025-01-01T00:00:00.123Z").unwrap();
+
+        let signin_input = SigninInput {
+            domain: Some("example.com".to_string()),
+            address: Some(ADDRESS.to_string()),
+            statement: Some("Version 2 of the URI: terms applies".to_string()),
+            uri: Some("https://example.com/login".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(Cluster::DevNet),
+            nonce: Some("abcdef123456".to_string()),
+            issued_at: Some(issued_at),
+            expiration_time: Some(issued_at + Duration::from_secs(600)),
+            not_before: Some(issued_at + Duration::from_secs(60)),
+            request_id: Some("request-1".to_string()),
+            resources: vec!["https://example.com/terms".to_string()],
+        };
+
+        let message = signin_input.to_message().unwrap();
+
+        assert_eq!(
+            message,
+            "example.com wants you to sign in with your Solana account:
+4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T
+
+Version 2 of the URI: terms applies
+
+URI: https://example.com/login
+Version: 1
+Chain ID: solana:devnet
+Nonce: abcdef123456
+Issued At: 2025-01-01T00:00:00.123Z
+Expiration Time: 2025-01-01T00:10:00.123Z
+Not Before: 2025-01-01T00:01:00.123Z
+Request ID: request-1
+Resources:
+- https://example.com/terms"
+        );
+
+        assert_eq!(SigninInput::parser(&message).unwrap(), signin_input);
+        assert!(signin_input.check_eq(&message).is_ok());
+    }
+
+    #[test]
+    fn optional_parts() {
+        let mut signin_input = SigninInput::new();
+        signin_input.set_custom_domain("localhost:8080");
+        signin_input.set_address(ADDRESS).unwrap();
+
+        let message = signin_input.to_message().unwrap();
+        assert_eq!(
+            message,
+            "localhost:8080 wants you to sign in with your Solana account:\n".to_string() + ADDRESS
+        );
+        assert_eq!(SigninInput::parser(&message).unwrap(), signin_input);
+        assert_eq!(
+            SigninInput::parser(&(message.clone() + "\n\n")).unwrap(),
+            signin_input
+        );
+
+        signin_input.set_statement("Sign in");
+        let message = signin_input.to_message().unwrap();
+        assert!(message.ends_with("\n\nSign in"));
+        assert_eq!(SigninInput::parser(&message).unwrap(), signin_input);
+
+        let mut signin_input = signin_input.clone();
+        signin_input.statement = None;
+        signin_input.set_version("1");
+        let message = signin_input.to_message().unwrap();
+        assert!(message.ends_with(&(ADDRESS.to_string() + "\n\nVersion: 1")));
+        assert_eq!(SigninInput::parser(&message).unwrap(), signin_input);
+    }
+
+    #[test]
+    fn to_message_errors() {
+        assert_eq!(
+            SigninInput::new().to_message(),
+            Err(WalletError::MissingSigninField("domain".to_string()))
+        );
+        assert_eq!(
+            SigninInput::new()
+                .set_custom_domain("example.com")
+                .to_message(),
+            Err(WalletError::MissingSigninField("address".to_string()))
+        );
+
+        let mut signin_input = SigninInput::new();
+        signin_input
+            .set_custom_domain("example.com")
+            .set_statement("Line one\nLine two")
+            .set_address(ADDRESS)
+            .unwrap();
+        assert!(matches!(
+            signin_input.to_message(),
+            Err(WalletError::InvalidSigninMessage { line: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn to_message_rejects_values_read_back_differently() {
+        let base = || {
+            let mut signin_input = SigninInput::new();
+            signin_input
+                .set_custom_domain("example.com")
+                .set_address(ADDRESS)
+                .unwrap();
+
+            signin_input
+        };
+        let error_line = |signin_input: &SigninInput| match signin_input.to_message() {
+            Err(WalletError::InvalidSigninMessage { line, .. }) => line,
+            outcome => panic!("Expected an InvalidSigninMessage error, got {outcome:?}"),
+        };
+
+        let mut signin_input = base();
+        signin_input.domain = Some("example .com".to_string());
+        assert_eq!(error_line(&signin_input), 1);
+
+        let mut signin_input = base();
+        signin_input.address = Some("4Nd1mBQtrMJVYVfKf2PJy9NZ".to_string());
+        assert_eq!(error_line(&signin_input), 2);
+
+        // A statement that would be parsed as the first field or as resources
+        for statement in ["URI: https://evil.example", "Nonce: 12345678", "Resources:"] {
+            let mut signin_input = base();
+            signin_input.set_statement(statement);
+            assert_eq!(error_line(&signin_input), 4, "{statement}");
+        }
+        let mut signin_input = base();
+        signin_input.set_statement("Line one\rLine two");
+        assert_eq!(error_line(&signin_input), 4);
+
+        // The fields start after the statement when there is one
+        let mut signin_input = base();
+        signin_input.set_statement("Sign in").set_version("2");
+        assert_eq!(error_line(&signin_input), 6);
+        let mut signin_input = base();
+        signin_input.set_version("1").uri = Some("not a uri".to_string());
+        assert_eq!(error_line(&signin_input), 4);
+
+        for nonce in ["1234567", "cli-nonce-1", "abc def12"] {
+            let mut signin_input = base();
+            signin_input.nonce = Some(nonce.to_string());
+            assert_eq!(error_line(&signin_input), 4, "{nonce}");
+        }
+
+        let mut signin_input = base();
+        signin_input.request_id = Some("request\nNonce: 12345678".to_string());
+        assert_eq!(error_line(&signin_input), 4);
+
+        let mut signin_input = base();
+        signin_input.set_version("1").resources =
+            vec!["https://example.com".to_string(), "- nested".to_string()];
+        assert_eq!(error_line(&signin_input), 7);
+    }
+
+    #[test]
+    fn to_message_parses_back() {
+        let issued_at = humantime::parse_rfc3339("2024-05-06T07:08:09Z").unwrap();
+
+        let mut inputs = Vec::<SigninInput>::new();
+        for statement in [None, Some("Sign in to Example: the app")] {
+            for nonce in [None, Some("Z9y8X7w6")] {
+                for resources in [vec![], vec!["urn:example:1".to_string()]] {
+                    inputs.push(SigninInput {
+                        domain: Some("app.example.com:3000".to_string()),
+                        address: Some(ADDRESS.to_string()),
+                        statement: statement.map(|value| value.to_string()),
+                        chain_id: Some(Cluster::MainNet),
+                        nonce: nonce.map(|value| value.to_string()),
+                        issued_at: Some(issued_at),
+                        resources,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        inputs.iter().for_each(|signin_input| {
+            let message = signin_input.to_message().unwrap();
+
+            assert_eq!(&SigninInput::parser(&message).unwrap(), signin_input);
+        });
+    }
+
+    #[test]
+    fn strict_errors() {
+        let header =
+            "example.com wants you to sign in with your Solana account:\n".to_string() + ADDRESS;
+
+        assert_eq!(parse_error("example.com wants you to sign in:").0, 1);
+        assert_eq!(
+            parse_error(" wants you to sign in with your Solana account:\nabc").0,
+            1
+        );
+        assert_eq!(
+            parse_error("example.com wants you to sign in with your Solana account:").0,
+            2
+        );
+        assert_eq!(
+            parse_error("example.com wants you to sign in with your Solana account:\n0OIl").0,
+            2
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\nURI: https://example.com")).0,
+            3
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nSign in\nURI: https://example.com")).0,
+            5
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nVersion: 1\nURI: https://example.com")),
+            (
+                5,
+                "The field `URI` is duplicated or out of order".to_string()
+            )
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nVersion: 1\nVersion: 1")).0,
+            5
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nVersion: 2")),
+            (4, "Only version `1` is supported".to_string())
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nURI: https://example.com\nColor: blue")),
+            (5, "Unknown field `Color`".to_string())
+        );
+        assert_eq!(parse_error(&(header.clone() + "\n\nNonce: 1234")).0, 4);
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nChain ID: ethereum:1")).0,
+            4
+        );
+        assert_eq!(
+            parse_error(&(header.clone() + "\n\nIssued At: yesterday")).0,
+            4
+        );
+        assert_eq!(parse_error(&(header.clone() + "\n\nURI: not a uri")).0, 4);
+        assert_eq!(
+            parse_error(
+                &(header.clone() + "\n\nResources:\n- https://example.com\nhttps://example.org")
+            )
+            .0,
+            6
+        );
+    }
+
+    #[test]
+    fn custom_nonce() {
+        let mut signin_input = SigninInput::new();
+
+        assert!(signin_input.custom_nonce("abcdef12").is_ok());
+        assert_eq!(signin_input.nonce(), Some(&"abcdef12".to_string()));
+
+        for nonce in ["abc-defg-1", "abcdefg 1", "abcdéfg1", "abc1234"] {
+            assert_eq!(
+                signin_input.custom_nonce(nonce).unwrap_err(),
+                WalletError::NonceMustBeAtLeast8Characters
+            );
+        }
+        assert_eq!(signin_input.nonce(), Some(&"abcdef12".to_string()));
+    }
+}
+
 #[cfg(test)]
 #[cfg(target_arch = "wasm32")]
 mod signin_input_sanity_checks {
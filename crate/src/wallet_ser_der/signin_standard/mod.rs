@@ -3,3 +3,12 @@ pub use input::*;
 
 mod output;
 pub use output::*;
+
+mod verifier;
+pub use verifier::*;
+
+mod nonce_store;
+pub use nonce_store::*;
+
+mod session;
+pub use session::*;
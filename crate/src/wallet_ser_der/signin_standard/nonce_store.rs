@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use crate::{Utils, WalletError, WalletResult};
+
+/// Issues Sign In With Solana (SIWS) nonces and consumes them during verification
+/// to protect against replay attacks. A nonce is bound to the domain and optionally
+/// the address it was issued for.
+pub trait NonceStore: Send + Sync {
+    /// Issue a new nonce for the `domain` and optional `address` which is valid
+    /// from `now` until `now + ttl`
+    fn issue(
+        &self,
+        domain: &str,
+        address: Option<&str>,
+        ttl: Duration,
+        now: SystemTime,
+    ) -> WalletResult<String>;
+
+    /// Atomically consume the `nonce` so that it can never be used again. Returns
+    /// - [WalletError::SigninUnknownNonce] if the nonce was not issued
+    /// - [WalletError::SigninNonceAlreadyUsed] if the nonce was already consumed
+    /// - [WalletError::SigninNonceExpired] if the nonce has expired at `now`
+    /// - [WalletError::SigninNonceBindingMismatch] if the nonce was issued for a different domain or address
+    fn consume(
+        &self,
+        nonce: &str,
+        domain: &str,
+        address: &str,
+        now: SystemTime,
+    ) -> WalletResult<()>;
+}
+
+/// A [NonceStore] that keeps the nonces in memory.
+/// Consumed nonces are kept until they expire so that reusing them returns
+/// [WalletError::SigninNonceAlreadyUsed]. Call [Self::purge_expired] periodically
+/// to remove expired nonces.
+#[derive(Debug, Default)]
+pub struct InMemoryNonceStore {
+    nonces: Mutex<HashMap<String, NonceRecord>>,
+}
+
+impl InMemoryNonceStore {
+    /// Same as `Self::default()`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a nonce from the Cryptographically Secure Random Number Generator
+    /// formatted as a hex string of 64 characters
+    pub fn generate_nonce() -> String {
+        let mut buffer = Utils::rand_32bytes();
+        let nonce = blake3::hash(&buffer).to_string();
+        buffer.fill(0);
+
+        nonce
+    }
+
+    /// Remove all the nonces that have expired at `now`
+    pub fn purge_expired(&self, now: SystemTime) {
+        self.nonces().retain(|_, record| record.expires_at > now);
+    }
+
+    /// The number of nonces in the store including consumed nonces that have not been purged
+    pub fn len(&self) -> usize {
+        self.nonces().len()
+    }
+
+    /// Whether the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.nonces().is_empty()
+    }
+
+    fn nonces(&self) -> std::sync::MutexGuard<'_, HashMap<String, NonceRecord>> {
+        self.nonces
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl NonceStore for InMemoryNonceStore {
+    fn issue(
+        &self,
+        domain: &str,
+        address: Option<&str>,
+        ttl: Duration,
+        now: SystemTime,
+    ) -> WalletResult<String> {
+        let expires_at = now.checked_add(ttl).ok_or(WalletError::InternalError(
+            "SystemTime::checked_add(ttl) overflow".to_string(),
+        ))?;
+
+        let nonce = Self::generate_nonce();
+
+        self.nonces().insert(
+            nonce.clone(),
+            NonceRecord {
+                domain: domain.to_string(),
+                address: address.map(|address| address.to_string()),
+                expires_at,
+                consumed: false,
+            },
+        );
+
+        Ok(nonce)
+    }
+
+    fn consume(
+        &self,
+        nonce: &str,
+        domain: &str,
+        address: &str,
+        now: SystemTime,
+    ) -> WalletResult<()> {
+        let mut nonces = self.nonces();
+
+        let record = nonces
+            .get_mut(nonce)
+            .ok_or(WalletError::SigninUnknownNonce(nonce.to_string()))?;
+
+        if record.consumed {
+            return Err(WalletError::SigninNonceAlreadyUsed(nonce.to_string()));
+        }
+
+        if record.expires_at <= now {
+            return Err(WalletError::SigninNonceExpired(nonce.to_string()));
+        }
+
+        if record.domain != domain
+            || record
+                .address
+                .as_ref()
+                .is_some_and(|issued_address| issued_address != address)
+        {
+            return Err(WalletError::SigninNonceBindingMismatch(nonce.to_string()));
+        }
+
+        record.consumed = true;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct NonceRecord {
+    domain: String,
+    address: Option<String>,
+    expires_at: SystemTime,
+    consumed: bool,
+}
+
+#[cfg(test)]
+mod nonce_store_tests {
+    use super::*;
+
+    const ADDRESS: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+    const TTL: Duration = Duration::from_secs(300);
+
+    #[test]
+    fn issue_and_consume() {
+        let store = InMemoryNonceStore::new();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let nonce = store.issue("example.com", None, TTL, now).unwrap();
+        assert_eq!(nonce.len(), 64);
+        assert!(nonce.chars().all(|char| char.is_ascii_alphanumeric()));
+
+        assert_eq!(
+            store.consume("unknown-nonce", "example.com", ADDRESS, now),
+            Err(WalletError::SigninUnknownNonce("unknown-nonce".to_string()))
+        );
+        assert!(store.consume(&nonce, "example.com", ADDRESS, now).is_ok());
+        assert_eq!(
+            store.consume(&nonce, "example.com", ADDRESS, now),
+            Err(WalletError::SigninNonceAlreadyUsed(nonce.clone()))
+        );
+
+        store.purge_expired(now + TTL);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn expiry_and_binding() {
+        let store = InMemoryNonceStore::new();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let nonce = store.issue("example.com", None, TTL, now).unwrap();
+        assert_eq!(
+            store.consume(&nonce, "example.com", ADDRESS, now + TTL),
+            Err(WalletError::SigninNonceExpired(nonce.clone()))
+        );
+
+        let nonce = store.issue("example.com", Some(ADDRESS), TTL, now).unwrap();
+        assert_eq!(
+            store.consume(&nonce, "evil.com", ADDRESS, now),
+            Err(WalletError::SigninNonceBindingMismatch(nonce.clone()))
+        );
+        assert_eq!(
+            store.consume(
+                &nonce,
+                "example.com",
+                "11111111111111111111111111111111",
+                now
+            ),
+            Err(WalletError::SigninNonceBindingMismatch(nonce.clone()))
+        );
+        assert!(store.consume(&nonce, "example.com", ADDRESS, now).is_ok());
+        assert_eq!(store.len(), 2);
+    }
+}
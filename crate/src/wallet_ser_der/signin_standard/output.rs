@@ -1,6 +1,9 @@
 use crate::WalletAccount;
 
-/// The output of Sign In With Solana (SIWS) response from a wallet
+/// The output of Sign In With Solana (SIWS) response from a wallet.
+/// With the `serde` feature, [Self] is (de)serialized using the shape of the wallet standard
+/// `SolanaSignInOutput` where `signedMessage` and `signature` are base64 strings.
+/// Deserializing also accepts byte arrays and `Uint8Array`s encoded by `JSON.stringify()`.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct SignInOutput {
     /// A [An Account](WalletAccount)
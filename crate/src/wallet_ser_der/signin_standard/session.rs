@@ -0,0 +1,719 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+
+use crate::{Cluster, SignInOutput, SiwsClock, SiwsVerifier, Utils, WalletError, WalletResult};
+
+/// The default time a session token is valid for, 24 hours
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The default time a session can be refreshed for since the user signed in, 30 days
+pub const DEFAULT_SESSION_LIFETIME: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+const SESSION_TOKEN_VERSION: u8 = 1;
+
+/// The claims carried by a session token
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SessionClaims {
+    session_id: String,
+    address: String,
+    domain: String,
+    chain: Option<Cluster>,
+    request_id: Option<String>,
+    issued_at: SystemTime,
+    expires_at: SystemTime,
+    session_expires_at: SystemTime,
+}
+
+impl SessionClaims {
+    /// The unique identifier of the session, kept when the token is refreshed
+    pub fn session_id(&self) -> &str {
+        self.session_id.as_str()
+    }
+
+    /// The Base58 address that signed in
+    pub fn address(&self) -> &str {
+        self.address.as_str()
+    }
+
+    /// The domain the address signed in to
+    pub fn domain(&self) -> &str {
+        self.domain.as_str()
+    }
+
+    /// The chain in the Sign In With Solana message
+    pub fn chain(&self) -> Option<&Cluster> {
+        self.chain.as_ref()
+    }
+
+    /// The request ID in the Sign In With Solana message
+    pub fn request_id(&self) -> Option<&String> {
+        self.request_id.as_ref()
+    }
+
+    /// The time the token was issued, in seconds precision
+    pub fn issued_at(&self) -> SystemTime {
+        self.issued_at
+    }
+
+    /// The time the token expires, in seconds precision
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    /// The time the session expires, in seconds precision.
+    /// Refreshing a token never extends it past this time.
+    pub fn session_expires_at(&self) -> SystemTime {
+        self.session_expires_at
+    }
+
+    // `version | issued_at | expires_at | session_expires_at | session_id | address | domain | chain | request_id`
+    // where the times are u64 little endian seconds and the strings are prefixed by
+    // their u16 little endian length, empty for `None`
+    fn encode(&self) -> WalletResult<Vec<u8>> {
+        let mut bytes = vec![SESSION_TOKEN_VERSION];
+        bytes.extend_from_slice(&Self::to_secs(self.issued_at).to_le_bytes());
+        bytes.extend_from_slice(&Self::to_secs(self.expires_at).to_le_bytes());
+        bytes.extend_from_slice(&Self::to_secs(self.session_expires_at).to_le_bytes());
+
+        let chain = self.chain.as_ref().map(|cluster| cluster.chain());
+
+        [
+            self.session_id.as_str(),
+            self.address.as_str(),
+            self.domain.as_str(),
+            chain.unwrap_or_default(),
+            self.request_id.as_deref().unwrap_or_default(),
+        ]
+        .iter()
+        .try_for_each(|value| {
+            let length = u16::try_from(value.len()).or(Err(WalletError::InvalidSessionToken(
+                "A claim is longer than 65535 bytes".to_string(),
+            )))?;
+            bytes.extend_from_slice(&length.to_le_bytes());
+            bytes.extend_from_slice(value.as_bytes());
+
+            Ok::<(), WalletError>(())
+        })?;
+
+        Ok(bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> WalletResult<Self> {
+        let mut reader = ClaimsReader(bytes);
+
+        if reader.take(1)? != [SESSION_TOKEN_VERSION] {
+            return Err(WalletError::InvalidSessionToken(
+                "Unsupported token version".to_string(),
+            ));
+        }
+
+        let issued_at = reader.time()?;
+        let expires_at = reader.time()?;
+        let session_expires_at = reader.time()?;
+        let session_id = reader.string()?;
+        let address = reader.string()?;
+        let domain = reader.string()?;
+        let chain = Some(reader.string()?)
+            .filter(|chain| !chain.is_empty())
+            .map(|chain| Cluster::try_from(chain.as_str()))
+            .transpose()?;
+        let request_id = Some(reader.string()?).filter(|request_id| !request_id.is_empty());
+
+        if !reader.0.is_empty() {
+            return Err(WalletError::InvalidSessionToken(
+                "Unexpected trailing bytes".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            session_id,
+            address,
+            domain,
+            chain,
+            request_id,
+            issued_at,
+            expires_at,
+            session_expires_at,
+        })
+    }
+
+    fn to_secs(time: SystemTime) -> u64 {
+        time.duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+    }
+
+    fn from_secs(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+}
+
+struct ClaimsReader<'a>(&'a [u8]);
+
+impl<'a> ClaimsReader<'a> {
+    fn take(&mut self, length: usize) -> WalletResult<&'a [u8]> {
+        if self.0.len() < length {
+            return Err(WalletError::InvalidSessionToken(
+                "The claims are truncated".to_string(),
+            ));
+        }
+
+        let (value, rest) = self.0.split_at(length);
+        self.0 = rest;
+
+        Ok(value)
+    }
+
+    fn time(&mut self) -> WalletResult<SystemTime> {
+        let secs = u64::from_le_bytes(self.take(8)?.try_into().or(Err(
+            WalletError::InvalidSessionToken("Invalid time".to_string()),
+        ))?);
+
+        Ok(SessionClaims::from_secs(secs))
+    }
+
+    fn string(&mut self) -> WalletResult<String> {
+        let length = u16::from_le_bytes(self.take(2)?.try_into().or(Err(
+            WalletError::InvalidSessionToken("Invalid length".to_string()),
+        ))?);
+
+        String::from_utf8(self.take(length as usize)?.to_vec())
+            .map_err(|error| WalletError::InvalidSessionToken(error.to_string()))
+    }
+}
+
+/// A hook to check whether a session has been revoked, like after the user logs out
+pub trait RevocationList: Send + Sync {
+    /// Whether the session with `session_id` has been revoked
+    fn is_revoked(&self, session_id: &str) -> bool;
+
+    /// Revoke the session with `session_id`. The session can be forgotten after
+    /// `expires_at` since no token of the session is valid then.
+    fn revoke(&self, session_id: &str, expires_at: SystemTime);
+}
+
+/// A [RevocationList] that keeps the revoked sessions in memory
+#[derive(Debug, Default)]
+pub struct InMemoryRevocationList {
+    revoked: Mutex<HashMap<String, SystemTime>>,
+}
+
+impl InMemoryRevocationList {
+    /// Same as `Self::default()`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove all the revoked sessions that have expired at `now`
+    pub fn purge_expired(&self, now: SystemTime) {
+        self.revoked().retain(|_, expires_at| *expires_at > now);
+    }
+
+    /// The number of revoked sessions
+    pub fn len(&self) -> usize {
+        self.revoked().len()
+    }
+
+    /// Whether no session has been revoked
+    pub fn is_empty(&self) -> bool {
+        self.revoked().is_empty()
+    }
+
+    fn revoked(&self) -> std::sync::MutexGuard<'_, HashMap<String, SystemTime>> {
+        self.revoked
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RevocationList for InMemoryRevocationList {
+    fn is_revoked(&self, session_id: &str) -> bool {
+        self.revoked().contains_key(session_id)
+    }
+
+    fn revoke(&self, session_id: &str, expires_at: SystemTime) {
+        self.revoked().insert(session_id.to_string(), expires_at);
+    }
+}
+
+/// Validates session tokens using the public key of the server that issued them.
+/// It does not call any browser APIs except for the default clock in wasm,
+/// so it can be used natively and in the frontend.
+pub struct SessionValidator {
+    public_key: VerifyingKey,
+    clock: SiwsClock,
+    revocation_list: Option<Arc<dyn RevocationList>>,
+}
+
+impl SessionValidator {
+    /// Instantiate a validator from the 32 byte Ed25519 public key of the issuer
+    pub fn new(public_key: [u8; 32]) -> WalletResult<Self> {
+        Ok(Self {
+            public_key: Utils::public_key(public_key)?,
+            clock: Box::new(SiwsVerifier::system_clock),
+            revocation_list: Option::default(),
+        })
+    }
+
+    /// Use a custom clock to get the current time
+    pub fn set_clock(
+        &mut self,
+        clock: impl Fn() -> WalletResult<SystemTime> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.clock = Box::new(clock);
+
+        self
+    }
+
+    /// Reject tokens of sessions in the [RevocationList]
+    pub fn set_revocation_list(&mut self, revocation_list: Arc<dyn RevocationList>) -> &mut Self {
+        self.revocation_list.replace(revocation_list);
+
+        self
+    }
+
+    /// Validate the signature, expiry and revocation of the `token` returning its claims
+    pub fn validate(&self, token: &str) -> WalletResult<SessionClaims> {
+        let (payload, signature) =
+            token
+                .split_once('.')
+                .ok_or(WalletError::InvalidSessionToken(
+                    "Expected `<claims>.<signature>`".to_string(),
+                ))?;
+
+        let decode = |value: &str| {
+            BASE64_URL_SAFE_NO_PAD
+                .decode(value)
+                .map_err(|error| WalletError::InvalidSessionToken(error.to_string()))
+        };
+
+        let payload = decode(payload)?;
+        let signature: [u8; 64] =
+            decode(signature)?
+                .try_into()
+                .or(Err(WalletError::InvalidSessionToken(
+                    "Invalid signature length".to_string(),
+                )))?;
+
+        Utils::verify_signature(self.public_key, &payload, Utils::signature(signature)).or(Err(
+            WalletError::InvalidSessionToken("The signature is invalid".to_string()),
+        ))?;
+
+        let claims = SessionClaims::decode(&payload)?;
+
+        if claims.expires_at <= (self.clock)()? {
+            return Err(WalletError::SessionExpired);
+        }
+
+        if self
+            .revocation_list
+            .as_ref()
+            .is_some_and(|revocation_list| revocation_list.is_revoked(&claims.session_id))
+        {
+            return Err(WalletError::SessionRevoked(claims.session_id));
+        }
+
+        Ok(claims)
+    }
+
+    /// Get the public key of the issuer
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public_key.to_bytes()
+    }
+}
+
+impl core::fmt::Debug for SessionValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionValidator")
+            .field("public_key", &bs58::encode(self.public_key).into_string())
+            .field("revocation_list", &self.revocation_list.is_some())
+            .finish()
+    }
+}
+
+/// Issues session tokens signed by a server Ed25519 key from a [SignInOutput]
+/// verified by a [SiwsVerifier].
+/// A token has the format `<claims>.<signature>` where both parts are base64url encoded.
+pub struct SessionIssuer {
+    signing_key: SigningKey,
+    ttl: Duration,
+    lifetime: Duration,
+    validator: SessionValidator,
+}
+
+impl SessionIssuer {
+    /// Instantiate an issuer from the 32 byte Ed25519 secret key of the server
+    pub fn new(secret_key: [u8; 32]) -> WalletResult<Self> {
+        let signing_key = SigningKey::from_bytes(&secret_key);
+        let validator = SessionValidator::new(signing_key.verifying_key().to_bytes())?;
+
+        Ok(Self {
+            signing_key,
+            ttl: DEFAULT_SESSION_TTL,
+            lifetime: DEFAULT_SESSION_LIFETIME,
+            validator,
+        })
+    }
+
+    /// The time a token is valid for. Defaults to [DEFAULT_SESSION_TTL]
+    pub fn set_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.ttl = ttl;
+
+        self
+    }
+
+    /// The time a session can be refreshed for since the user signed in.
+    /// Defaults to [DEFAULT_SESSION_LIFETIME]
+    pub fn set_lifetime(&mut self, lifetime: Duration) -> &mut Self {
+        self.lifetime = lifetime;
+
+        self
+    }
+
+    /// Use a custom clock to get the current time
+    pub fn set_clock(
+        &mut self,
+        clock: impl Fn() -> WalletResult<SystemTime> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.validator.set_clock(clock);
+
+        self
+    }
+
+    /// Reject refreshing and validating tokens of sessions in the [RevocationList]
+    pub fn set_revocation_list(&mut self, revocation_list: Arc<dyn RevocationList>) -> &mut Self {
+        self.validator.set_revocation_list(revocation_list);
+
+        self
+    }
+
+    /// Verify the [SignInOutput] using [SiwsVerifier::verify], which also consumes
+    /// its nonce, and issue a token for a new session if it is valid
+    pub fn issue(
+        &self,
+        verifier: &mut SiwsVerifier,
+        output: &SignInOutput,
+    ) -> WalletResult<String> {
+        let signin_input = verifier.verify(output)?;
+        let address = signin_input
+            .address()
+            .ok_or(WalletError::MissingSigninField("address".to_string()))?;
+        let domain = signin_input
+            .domain()
+            .ok_or(WalletError::MissingSigninField("domain".to_string()))?;
+
+        let now = SessionClaims::to_secs((self.validator.clock)()?);
+
+        let mut session_id = Utils::rand_32bytes();
+        let claims = SessionClaims {
+            session_id: blake3::hash(&session_id).to_hex()[..32].to_string(),
+            address: address.clone(),
+            domain: domain.clone(),
+            chain: signin_input.chain_id().cloned(),
+            request_id: signin_input.request_id().cloned(),
+            issued_at: UNIX_EPOCH,
+            expires_at: UNIX_EPOCH,
+            session_expires_at: SessionClaims::from_secs(
+                now.saturating_add(self.lifetime.as_secs()),
+            ),
+        };
+        session_id.fill(0);
+
+        self.sign(claims)
+    }
+
+    /// Issue a new token with a new expiry for the same session if the `token` is still valid.
+    /// The new expiry is capped at [SessionClaims::session_expires_at].
+    pub fn refresh(&self, token: &str) -> WalletResult<String> {
+        let claims = self.validator.validate(token)?;
+
+        self.sign(claims)
+    }
+
+    /// Validate a token issued by [Self]
+    pub fn validate(&self, token: &str) -> WalletResult<SessionClaims> {
+        self.validator.validate(token)
+    }
+
+    /// Revoke the session of a valid `token` in the [RevocationList],
+    /// returning [WalletError::InternalError] if no [RevocationList] is set
+    pub fn revoke(&self, token: &str) -> WalletResult<()> {
+        let claims = self.validator.validate(token)?;
+
+        let revocation_list =
+            self.validator
+                .revocation_list
+                .as_ref()
+                .ok_or(WalletError::InternalError(
+                    "A revocation list is required to revoke a session".to_string(),
+                ))?;
+        // Refreshed tokens of the session stay valid until the session expires
+        revocation_list.revoke(&claims.session_id, claims.session_expires_at);
+
+        Ok(())
+    }
+
+    /// The validator for tokens issued by [Self] which can be shared with
+    /// services that only need the public key
+    pub fn validator(&self) -> &SessionValidator {
+        &self.validator
+    }
+
+    fn sign(&self, mut claims: SessionClaims) -> WalletResult<String> {
+        let now = SessionClaims::to_secs((self.validator.clock)()?);
+        claims.issued_at = SessionClaims::from_secs(now);
+        claims.expires_at = SessionClaims::from_secs(now.saturating_add(self.ttl.as_secs()))
+            .min(claims.session_expires_at);
+
+        let payload = claims.encode()?;
+        let signature = self.signing_key.sign(&payload);
+
+        Ok(BASE64_URL_SAFE_NO_PAD.encode(&payload)
+            + "."
+            + BASE64_URL_SAFE_NO_PAD.encode(signature.to_bytes()).as_str())
+    }
+}
+
+impl core::fmt::Debug for SessionIssuer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionIssuer")
+            .field("ttl", &self.ttl)
+            .field("lifetime", &self.lifetime)
+            .field("validator", &self.validator)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod session_tests {
+    use ed25519_dalek::Signer;
+
+    use super::*;
+    use crate::WalletAccount;
+
+    fn now() -> SystemTime {
+        humantime::parse_rfc3339("2025-01-01T00:00:00Z").unwrap()
+    }
+
+    fn user_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn address() -> String {
+        bs58::encode(user_key().verifying_key().as_bytes()).into_string()
+    }
+
+    fn output_signed_by(signing_key: &SigningKey, nonce: &str) -> SignInOutput {
+        let public_key = user_key().verifying_key().to_bytes();
+
+        let message = [
+            "example.com wants you to sign in with your Solana account:".to_string(),
+            address(),
+            String::new(),
+            "Version: 1".to_string(),
+            "Chain ID: devnet".to_string(),
+            format!("Nonce: {nonce}"),
+            "Issued At: 2025-01-01T00:00:00Z".to_string(),
+            "Expiration Time: 2025-01-01T00:10:00Z".to_string(),
+            "Request ID: request-1".to_string(),
+        ]
+        .join("\n");
+
+        SignInOutput {
+            account: WalletAccount::from_parts(
+                address(),
+                public_key,
+                Vec::default(),
+                Vec::default(),
+                None,
+                None,
+            )
+            .unwrap(),
+            signature: signing_key.sign(message.as_bytes()).to_bytes(),
+            message,
+            public_key,
+        }
+    }
+
+    fn output() -> SignInOutput {
+        output_signed_by(&user_key(), "abcdef123456")
+    }
+
+    fn verifier() -> SiwsVerifier {
+        let mut verifier = SiwsVerifier::new("example.com");
+        verifier
+            .set_chain(Cluster::DevNet)
+            .set_clock(|| Ok(now() + Duration::from_secs(60)));
+
+        verifier
+    }
+
+    fn issuer(now: SystemTime) -> SessionIssuer {
+        let mut issuer = SessionIssuer::new([5u8; 32]).unwrap();
+        issuer
+            .set_ttl(Duration::from_secs(3600))
+            .set_lifetime(Duration::from_secs(3 * 3600))
+            .set_clock(move || Ok(now));
+
+        issuer
+    }
+
+    #[test]
+    fn issue_and_validate() {
+        let token = issuer(now()).issue(&mut verifier(), &output()).unwrap();
+
+        let mut validator = SessionValidator::new(issuer(now()).validator().public_key()).unwrap();
+        validator.set_clock(|| Ok(now() + Duration::from_secs(60)));
+        let claims = validator.validate(&token).unwrap();
+
+        assert_eq!(claims.address(), address());
+        assert_eq!(claims.domain(), "example.com");
+        assert_eq!(claims.chain(), Some(&Cluster::DevNet));
+        assert_eq!(claims.request_id().unwrap(), "request-1");
+        assert_eq!(claims.session_id().len(), 32);
+        assert_eq!(claims.issued_at(), now());
+        assert_eq!(claims.expires_at(), now() + Duration::from_secs(3600));
+        assert_eq!(
+            claims.session_expires_at(),
+            now() + Duration::from_secs(3 * 3600)
+        );
+
+        assert_eq!(
+            issuer(now() + Duration::from_secs(3600)).validate(&token),
+            Err(WalletError::SessionExpired)
+        );
+    }
+
+    #[test]
+    fn reject_unverified_output() {
+        let issuer = issuer(now());
+        let mut verifier = verifier();
+
+        let forged = output_signed_by(&SigningKey::from_bytes(&[8u8; 32]), "abcdef123456");
+        assert!(issuer.issue(&mut verifier, &forged).is_err());
+
+        let mut zeroed = output();
+        zeroed.signature = [0u8; 64];
+        assert!(issuer.issue(&mut verifier, &zeroed).is_err());
+
+        let other_key = SigningKey::from_bytes(&[9u8; 32]).verifying_key();
+        let mut other_account = output();
+        other_account.account = WalletAccount::from_parts(
+            bs58::encode(other_key.as_bytes()).into_string(),
+            other_key.to_bytes(),
+            Vec::default(),
+            Vec::default(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            issuer.issue(&mut verifier, &other_account),
+            Err(WalletError::SigninAddressMismatch)
+        );
+
+        // The nonce is consumed by the first valid sign in
+        assert!(issuer.issue(&mut verifier, &output()).is_ok());
+        assert_eq!(
+            issuer.issue(&mut verifier, &output()),
+            Err(WalletError::SigninNonceAlreadyUsed(
+                "abcdef123456".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn reject_tampered() {
+        let issuer = issuer(now());
+        let token = issuer.issue(&mut verifier(), &output()).unwrap();
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        let mut bytes = BASE64_URL_SAFE_NO_PAD.decode(payload).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        let tampered = BASE64_URL_SAFE_NO_PAD.encode(bytes) + "." + signature;
+
+        assert!(matches!(
+            issuer.validate(&tampered),
+            Err(WalletError::InvalidSessionToken(_))
+        ));
+        assert!(matches!(
+            issuer.validate(payload),
+            Err(WalletError::InvalidSessionToken(_))
+        ));
+
+        let other_issuer = SessionIssuer::new([6u8; 32]).unwrap();
+        assert!(matches!(
+            other_issuer.validate(&token),
+            Err(WalletError::InvalidSessionToken(_))
+        ));
+    }
+
+    #[test]
+    fn refresh_and_revoke() {
+        let revocation_list = Arc::new(InMemoryRevocationList::new());
+
+        let mut issuer = issuer(now());
+        issuer.set_revocation_list(revocation_list.clone());
+        let token = issuer.issue(&mut verifier(), &output()).unwrap();
+
+        let mut later = self::issuer(now() + Duration::from_secs(1800));
+        later.set_revocation_list(revocation_list.clone());
+        let refreshed = later.refresh(&token).unwrap();
+
+        let claims = later.validate(&refreshed).unwrap();
+        assert_eq!(
+            claims.session_id(),
+            later.validate(&token).unwrap().session_id()
+        );
+        assert_eq!(
+            claims.expires_at(),
+            now() + Duration::from_secs(1800 + 3600)
+        );
+        assert_eq!(
+            claims.session_expires_at(),
+            now() + Duration::from_secs(3 * 3600)
+        );
+
+        // Refreshing never extends the token past the session lifetime
+        let last = self::issuer(now() + Duration::from_secs(3600))
+            .set_ttl(Duration::from_secs(3 * 3600))
+            .refresh(&refreshed)
+            .unwrap();
+        assert_eq!(
+            later.validate(&last).unwrap().expires_at(),
+            claims.session_expires_at()
+        );
+
+        // Revoking the first token also revokes the refreshed tokens of the session
+        later.revoke(&token).unwrap();
+        assert_eq!(revocation_list.len(), 1);
+        assert_eq!(
+            later.validate(&refreshed),
+            Err(WalletError::SessionRevoked(claims.session_id().to_string()))
+        );
+        assert!(matches!(
+            later.refresh(&refreshed),
+            Err(WalletError::SessionRevoked(_))
+        ));
+
+        // The revocation outlives the expiry of the revoked token
+        revocation_list.purge_expired(now() + Duration::from_secs(3600));
+        assert_eq!(revocation_list.len(), 1);
+        let mut at_last = self::issuer(now() + Duration::from_secs(2 * 3600 + 1800));
+        at_last.set_revocation_list(revocation_list.clone());
+        assert!(matches!(
+            at_last.validate(&last),
+            Err(WalletError::SessionRevoked(_))
+        ));
+
+        revocation_list.purge_expired(claims.session_expires_at());
+        assert!(revocation_list.is_empty());
+    }
+}
@@ -0,0 +1,628 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use crate::{Cluster, NonceStore, SignInOutput, SigninInput, Utils, WalletError, WalletResult};
+
+/// Convenience type for the clock used by [SiwsVerifier] to get the current time
+pub type SiwsClock = Box<dyn Fn() -> WalletResult<SystemTime> + Send + Sync>;
+
+/// Verifies a [SignInOutput] received from a frontend.
+/// The verifier does not call any browser APIs so it can be used natively, like in a backend server.
+/// It checks that:
+/// - the message follows the SIWS grammar
+/// - the address in the message belongs to the public key that signed the message
+/// - the Ed25519 signature is valid
+/// - the domain, URI and chain are the expected values
+/// - the `Issued At`, `Expiration Time` and `Not Before` times are valid according to the clock
+/// - the nonce has not been used before, consuming it if the message is valid.
+///   If a [NonceStore] is set the nonce must also have been issued by the store
+///   for the same domain and address and must not have expired.
+///   Without a [NonceStore] the verifier remembers the used nonces until the messages expire
+///   so the message must contain an `Expiration Time` or the verifier must have a
+///   [maximum age](Self::set_max_age)
+pub struct SiwsVerifier {
+    domain: String,
+    uri: Option<String>,
+    chain: Option<Cluster>,
+    max_age: Option<Duration>,
+    clock_skew: Duration,
+    clock: SiwsClock,
+    // The used nonces and the time after which their message can no longer be verified
+    used_nonces: HashMap<String, SystemTime>,
+    nonce_store: Option<Arc<dyn NonceStore>>,
+}
+
+impl SiwsVerifier {
+    /// Instantiate a verifier expecting messages for the `domain`
+    /// using the system time as the clock
+    pub fn new(domain: &str) -> Self {
+        Self {
+            domain: domain.to_string(),
+            uri: Option::default(),
+            chain: Option::default(),
+            max_age: Option::default(),
+            clock_skew: Duration::default(),
+            clock: Box::new(Self::system_clock),
+            used_nonces: HashMap::default(),
+            nonce_store: Option::default(),
+        }
+    }
+
+    /// Require the message to contain this URI
+    pub fn set_uri(&mut self, uri: &str) -> &mut Self {
+        self.uri.replace(uri.to_string());
+
+        self
+    }
+
+    /// Require the message to contain this chain
+    pub fn set_chain(&mut self, cluster: Cluster) -> &mut Self {
+        self.chain.replace(cluster);
+
+        self
+    }
+
+    /// Require the message to contain an `Issued At` time that is not older than `max_age`
+    pub fn set_max_age(&mut self, max_age: Duration) -> &mut Self {
+        self.max_age.replace(max_age);
+
+        self
+    }
+
+    /// The difference in time tolerated between the clock of the verifier and the clock of the
+    /// frontend when checking the `Issued At`, `Expiration Time` and `Not Before` times.
+    /// Defaults to zero.
+    pub fn set_clock_skew(&mut self, clock_skew: Duration) -> &mut Self {
+        self.clock_skew = clock_skew;
+
+        self
+    }
+
+    /// Use a custom clock to get the current time, useful for tests
+    /// or when the time is provided by another service
+    pub fn set_clock(
+        &mut self,
+        clock: impl Fn() -> WalletResult<SystemTime> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.clock = Box::new(clock);
+
+        self
+    }
+
+    /// Consume the nonces using a [NonceStore] instead of only checking that the nonce
+    /// has not been used by this verifier before. The store can be shared with the
+    /// service that issues the nonces.
+    pub fn set_nonce_store(&mut self, nonce_store: Arc<dyn NonceStore>) -> &mut Self {
+        self.nonce_store.replace(nonce_store);
+
+        self
+    }
+
+    /// Verify the [SignInOutput] returning the [SigninInput] parsed from the signed message.
+    /// The nonce is consumed only if all the checks pass.
+    pub fn verify(&mut self, output: &SignInOutput) -> WalletResult<SigninInput> {
+        let signin_input = SigninInput::parser(&output.message)?;
+
+        self.verify_signer(&signin_input, output)?;
+        self.verify_fields(&signin_input)?;
+
+        let now = (self.clock)()?;
+        self.verify_times(&signin_input, now)?;
+
+        let nonce = signin_input
+            .nonce()
+            .ok_or(WalletError::MissingSigninField("nonce".to_string()))?;
+
+        if let Some(nonce_store) = self.nonce_store.as_ref() {
+            nonce_store.consume(nonce, &self.domain, output.address(), now)?;
+        } else {
+            self.consume_nonce(&signin_input, nonce, now)?;
+        }
+
+        Ok(signin_input)
+    }
+
+    /// Remember the nonce until the message expires, forgetting the nonces of expired messages
+    /// since [Self::verify_times] rejects those messages before their nonce is checked
+    fn consume_nonce(
+        &mut self,
+        signin_input: &SigninInput,
+        nonce: &str,
+        now: SystemTime,
+    ) -> WalletResult<()> {
+        let earliest = now.checked_sub(self.clock_skew).unwrap_or(now);
+        self.used_nonces
+            .retain(|_, expires_at| *expires_at >= earliest);
+
+        if self.used_nonces.contains_key(nonce) {
+            return Err(WalletError::SigninNonceAlreadyUsed(nonce.to_string()));
+        }
+
+        let max_age_expiry = self.max_age.and_then(|max_age| {
+            signin_input
+                .issued_at()
+                .and_then(|issued_at| issued_at.checked_add(max_age))
+        });
+        let expires_at = match (signin_input.expiration_time().copied(), max_age_expiry) {
+            (Some(expiration_time), Some(max_age_expiry)) => expiration_time.min(max_age_expiry),
+            (expiration_time, max_age_expiry) => {
+                expiration_time
+                    .or(max_age_expiry)
+                    .ok_or(WalletError::MissingSigninField(
+                        "expirationTime".to_string(),
+                    ))?
+            }
+        };
+
+        self.used_nonces.insert(nonce.to_string(), expires_at);
+
+        Ok(())
+    }
+
+    fn verify_signer(&self, signin_input: &SigninInput, output: &SignInOutput) -> WalletResult<()> {
+        let public_key_address = bs58::encode(&output.public_key).into_string();
+
+        if signin_input.address() != Some(&public_key_address)
+            || output.address() != public_key_address
+            || output.account.public_key != output.public_key
+        {
+            return Err(WalletError::SigninAddressMismatch);
+        }
+
+        Utils::verify_signature(
+            Utils::public_key(output.public_key)?,
+            output.message.as_bytes(),
+            Utils::signature(output.signature),
+        )
+    }
+
+    fn verify_fields(&self, signin_input: &SigninInput) -> WalletResult<()> {
+        let domain = signin_input
+            .domain()
+            .ok_or(WalletError::MissingSigninField("domain".to_string()))?;
+
+        if *domain != self.domain {
+            return Err(WalletError::SigninDomainMismatch(domain.clone()));
+        }
+
+        if let Some(expected_uri) = self.uri.as_ref() {
+            let uri = signin_input
+                .uri()
+                .ok_or(WalletError::MissingSigninField("uri".to_string()))?;
+
+            if uri != expected_uri {
+                return Err(WalletError::SigninUriMismatch(uri.clone()));
+            }
+        }
+
+        if let Some(expected_chain) = self.chain.as_ref() {
+            let chain = signin_input
+                .chain_id()
+                .ok_or(WalletError::MissingSigninField("chainId".to_string()))?;
+
+            if chain != expected_chain {
+                return Err(WalletError::SigninChainMismatch(chain.chain().to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_times(&self, signin_input: &SigninInput, now: SystemTime) -> WalletResult<()> {
+        let earliest = now.checked_sub(self.clock_skew).unwrap_or(now);
+        let latest = now.checked_add(self.clock_skew).unwrap_or(now);
+
+        if let Some(issued_at) = signin_input.issued_at() {
+            if *issued_at > latest {
+                return Err(WalletError::SigninIssuedInTheFuture);
+            }
+        }
+
+        if let Some(max_age) = self.max_age {
+            let issued_at = signin_input
+                .issued_at()
+                .ok_or(WalletError::MissingSigninField("issuedAt".to_string()))?;
+
+            if issued_at
+                .checked_add(max_age)
+                .is_some_and(|oldest| oldest < earliest)
+            {
+                return Err(WalletError::SigninIssuedTooLongAgo);
+            }
+        }
+
+        if let Some(expiration_time) = signin_input.expiration_time() {
+            if *expiration_time <= earliest {
+                return Err(WalletError::SigninExpired);
+            }
+        }
+
+        if let Some(not_before) = signin_input.not_before() {
+            if *not_before > latest {
+                return Err(WalletError::SigninNotYetValid);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The default clock, [SystemTime::now] natively and
+    /// [SigninInput::time_now] in wasm
+    pub(crate) fn system_clock() -> WalletResult<SystemTime> {
+        #[cfg(target_arch = "wasm32")]
+        return SigninInput::time_now();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        Ok(SystemTime::now())
+    }
+
+    /// Get the expected domain
+    pub fn domain(&self) -> &str {
+        self.domain.as_str()
+    }
+
+    /// Get the expected URI
+    pub fn uri(&self) -> Option<&String> {
+        self.uri.as_ref()
+    }
+
+    /// Get the expected chain
+    pub fn chain(&self) -> Option<&Cluster> {
+        self.chain.as_ref()
+    }
+
+    /// Get the maximum age of the `Issued At` time
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Get the clock skew tolerated
+    pub fn clock_skew(&self) -> Duration {
+        self.clock_skew
+    }
+}
+
+impl core::fmt::Debug for SiwsVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SiwsVerifier")
+            .field("domain", &self.domain)
+            .field("uri", &self.uri)
+            .field("chain", &self.chain)
+            .field("max_age", &self.max_age)
+            .field("clock_skew", &self.clock_skew)
+            .field("used_nonces", &self.used_nonces.len())
+            .field("nonce_store", &self.nonce_store.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod siws_verifier_tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+    use crate::{InMemoryNonceStore, WalletAccount};
+
+    const DOMAIN: &str = "example.com";
+    const URI: &str = "https://example.com/login";
+
+    fn issued_at() -> SystemTime {
+        humantime::parse_rfc3339("2025-01-01T00:00:00Z").unwrap()
+    }
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn sign(signing_key: &SigningKey, message: &str) -> SignInOutput {
+        let public_key = signing_key.verifying_key().to_bytes();
+        let address = bs58::encode(&public_key).into_string();
+
+        let account = WalletAccount::from_parts(
+            address,
+            public_key,
+            vec!["solana:mainnet".to_string()],
+            Vec::default(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        SignInOutput {
+            account,
+            message: message.to_string(),
+            signature: signing_key.sign(message.as_bytes()).to_bytes(),
+            public_key,
+        }
+    }
+
+    // The fields of the signed message, the message is written out since the
+    // `URI` and times can only be set from the browser with the setters of [SigninInput]
+    struct Fields {
+        domain: &'static str,
+        uri: Option<&'static str>,
+        chain: &'static str,
+        nonce: Option<&'static str>,
+        times: bool,
+    }
+
+    impl Default for Fields {
+        fn default() -> Self {
+            Self {
+                domain: DOMAIN,
+                uri: Some(URI),
+                chain: "mainnet",
+                nonce: Some("abcdef123456"),
+                times: true,
+            }
+        }
+    }
+
+    fn output_with(fields: Fields) -> SignInOutput {
+        let signing_key = signing_key();
+        let address = bs58::encode(signing_key.verifying_key().as_bytes()).into_string();
+
+        let mut lines = vec![
+            format!(
+                "{} wants you to sign in with your Solana account:",
+                fields.domain
+            ),
+            address,
+            String::new(),
+            "Sign in to Example".to_string(),
+            String::new(),
+        ];
+        if let Some(uri) = fields.uri {
+            lines.push(format!("URI: {uri}"));
+        }
+        lines.push("Version: 1".to_string());
+        lines.push(format!("Chain ID: {}", fields.chain));
+        if let Some(nonce) = fields.nonce {
+            lines.push(format!("Nonce: {nonce}"));
+        }
+        if fields.times {
+            lines.push("Issued At: 2025-01-01T00:00:00Z".to_string());
+            lines.push("Expiration Time: 2025-01-01T00:10:00Z".to_string());
+            lines.push("Not Before: 2025-01-01T00:01:00Z".to_string());
+        }
+
+        let message = lines.join("\n");
+        // The message must be valid SIWS
+        SigninInput::parser(&message).unwrap();
+
+        sign(&signing_key, &message)
+    }
+
+    fn output() -> SignInOutput {
+        output_with(Fields::default())
+    }
+
+    fn verifier(now: SystemTime) -> SiwsVerifier {
+        let mut verifier = SiwsVerifier::new(DOMAIN);
+        verifier
+            .set_uri(URI)
+            .set_chain(Cluster::MainNet)
+            .set_clock(move || Ok(now));
+
+        verifier
+    }
+
+    #[test]
+    fn valid_and_replay() {
+        let now = issued_at() + Duration::from_secs(120);
+        let mut verifier = verifier(now);
+        let output = output();
+
+        let signin_input = verifier.verify(&output).unwrap();
+        assert_eq!(signin_input.nonce().unwrap(), "abcdef123456");
+
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::SigninNonceAlreadyUsed(
+                "abcdef123456".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn nonce_store() {
+        let now = issued_at() + Duration::from_secs(120);
+        let store = Arc::new(InMemoryNonceStore::new());
+        let mut verifier = verifier(now);
+        verifier.set_nonce_store(store.clone());
+
+        let address = bs58::encode(signing_key().verifying_key().as_bytes()).into_string();
+        let nonce = store
+            .issue(
+                DOMAIN,
+                Some(&address),
+                Duration::from_secs(300),
+                issued_at(),
+            )
+            .unwrap();
+
+        let output = sign(
+            &signing_key(),
+            &output().message.replace("abcdef123456", &nonce),
+        );
+
+        assert!(verifier.verify(&output).is_ok());
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::SigninNonceAlreadyUsed(nonce))
+        );
+
+        // Nonces not issued by the store are rejected
+        assert_eq!(
+            verifier.verify(&self::output()),
+            Err(WalletError::SigninUnknownNonce("abcdef123456".to_string()))
+        );
+    }
+
+    #[test]
+    fn signer_errors() {
+        let mut verifier = verifier(issued_at() + Duration::from_secs(120));
+
+        let mut output = output();
+        output.signature[0] ^= 1;
+        assert_eq!(verifier.verify(&output), Err(WalletError::InvalidSignature));
+
+        let other = sign(&SigningKey::from_bytes(&[8u8; 32]), &output.message);
+        assert_eq!(
+            verifier.verify(&other),
+            Err(WalletError::SigninAddressMismatch)
+        );
+
+        // The failed attempts do not consume the nonce
+        assert!(verifier.verify(&self::output()).is_ok());
+    }
+
+    #[test]
+    fn field_errors() {
+        let mut verifier = verifier(issued_at() + Duration::from_secs(120));
+
+        let output = output_with(Fields {
+            domain: "evil.com",
+            ..Default::default()
+        });
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::SigninDomainMismatch("evil.com".to_string()))
+        );
+
+        let output = output_with(Fields {
+            uri: None,
+            ..Default::default()
+        });
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::MissingSigninField("uri".to_string()))
+        );
+
+        let output = output_with(Fields {
+            uri: Some("https://evil.com"),
+            ..Default::default()
+        });
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::SigninUriMismatch(
+                "https://evil.com".to_string()
+            ))
+        );
+
+        let output = output_with(Fields {
+            chain: "devnet",
+            ..Default::default()
+        });
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::SigninChainMismatch(
+                "solana:devnet".to_string()
+            ))
+        );
+
+        let output = output_with(Fields {
+            nonce: None,
+            ..Default::default()
+        });
+        assert_eq!(
+            verifier.verify(&output),
+            Err(WalletError::MissingSigninField("nonce".to_string()))
+        );
+    }
+
+    #[test]
+    fn used_nonces_expire() {
+        let now = Arc::new(std::sync::Mutex::new(
+            issued_at() + Duration::from_secs(120),
+        ));
+        let clock = Arc::clone(&now);
+        let mut verifier = verifier(issued_at());
+        verifier.set_clock(move || Ok(*clock.lock().unwrap()));
+
+        assert!(verifier.verify(&output()).is_ok());
+        assert_eq!(verifier.used_nonces.len(), 1);
+
+        // Once the first message expires its nonce is forgotten
+        // since the message is rejected as expired before its nonce is checked
+        *now.lock().unwrap() = issued_at() + Duration::from_secs(900);
+        let later = sign(
+            &signing_key(),
+            &output()
+                .message
+                .replace("abcdef123456", "ghijkl789012")
+                .replace("00:10:00Z", "00:20:00Z"),
+        );
+        assert!(verifier.verify(&later).is_ok());
+        assert_eq!(
+            verifier.used_nonces.keys().collect::<Vec<_>>(),
+            ["ghijkl789012"]
+        );
+        assert_eq!(verifier.verify(&output()), Err(WalletError::SigninExpired));
+        assert_eq!(
+            verifier.verify(&later),
+            Err(WalletError::SigninNonceAlreadyUsed(
+                "ghijkl789012".to_string()
+            ))
+        );
+
+        // A message that never expires needs a maximum age or a nonce store
+        let never_expires = output_with(Fields {
+            times: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            verifier.verify(&never_expires),
+            Err(WalletError::MissingSigninField(
+                "expirationTime".to_string()
+            ))
+        );
+        assert!(verifier.used_nonces.len() == 1);
+
+        let mut verifier = self::verifier(issued_at() + Duration::from_secs(120));
+        verifier.set_max_age(Duration::from_secs(300));
+        let issued_only = sign(
+            &signing_key(),
+            &never_expires.message.replace(
+                "Chain ID: mainnet\nNonce: abcdef123456",
+                "Chain ID: mainnet\nNonce: abcdef123456\nIssued At: 2025-01-01T00:00:00Z",
+            ),
+        );
+        assert!(verifier.verify(&issued_only).is_ok());
+        assert_eq!(
+            verifier.used_nonces.get("abcdef123456"),
+            Some(&(issued_at() + Duration::from_secs(300)))
+        );
+    }
+
+    #[test]
+    fn time_errors() {
+        let output = output();
+
+        assert_eq!(
+            verifier(issued_at() - Duration::from_secs(1)).verify(&output),
+            Err(WalletError::SigninIssuedInTheFuture)
+        );
+        assert_eq!(
+            verifier(issued_at() + Duration::from_secs(30)).verify(&output),
+            Err(WalletError::SigninNotYetValid)
+        );
+        assert_eq!(
+            verifier(issued_at() + Duration::from_secs(600)).verify(&output),
+            Err(WalletError::SigninExpired)
+        );
+        assert!(verifier(issued_at() + Duration::from_secs(600))
+            .set_clock_skew(Duration::from_secs(5))
+            .verify(&output)
+            .is_ok());
+        assert_eq!(
+            verifier(issued_at() + Duration::from_secs(300))
+                .set_max_age(Duration::from_secs(120))
+                .verify(&output),
+            Err(WalletError::SigninIssuedTooLongAgo)
+        );
+    }
+}
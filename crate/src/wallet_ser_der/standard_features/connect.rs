@@ -1,7 +1,8 @@
 use web_sys::{js_sys, wasm_bindgen::JsValue};
 
 use crate::{
-    Reflection, SemverVersion, StandardFunction, WalletAccount, WalletError, WalletResult,
+    CallContext, Reflection, SemverVersion, StandardFunction, WalletAccount, WalletError,
+    WalletResult, STANDARD_CONNECT_IDENTIFIER,
 };
 
 /// The `standard:connect` struct containing a `version` and `callback`
@@ -18,34 +19,36 @@ impl Connect {
     }
 
     /// Connect to a wallet by calling the callback function
-    pub(crate) async fn call_connect(&self) -> WalletResult<WalletAccount> {
-        let outcome = self.0.callback.call0(&JsValue::from_bool(false))?;
+    pub(crate) async fn call_connect(&self, wallet_name: &str) -> WalletResult<WalletAccount> {
+        let context = CallContext::new(wallet_name, STANDARD_CONNECT_IDENTIFIER);
+
+        let outcome = context.map(
+            CallContext::REQUEST,
+            self.0.callback.call0(&JsValue::from_bool(false)),
+        )?;
 
         let outcome = js_sys::Promise::resolve(&outcome);
 
-        wasm_bindgen_futures::JsFuture::from(outcome)
-            .await
-            .map(|success| {
-                let get_accounts = Reflection::new(success)?.reflect_js_array("accounts")?;
-
-                let wallet_account = get_accounts
-                    .into_iter()
-                    .map(|raw_account| WalletAccount::parse(Reflection::new(raw_account)?))
-                    .collect::<WalletResult<Vec<WalletAccount>>>()
-                    .map(|mut accounts| {
-                        if accounts.is_empty() {
-                            Err(WalletError::ConnectHasNoAccounts)
-                        } else {
-                            Ok(accounts.remove(0))
-                        }
-                    })??;
-
-                Ok(wallet_account)
-            })
-            .map_err(|error| {
-                let value: WalletError = error.into();
-
-                WalletError::WalletConnectError(value.to_string())
-            })?
+        let success = context.map(
+            CallContext::RESPONSE,
+            wasm_bindgen_futures::JsFuture::from(outcome).await,
+        )?;
+
+        context.map(CallContext::PARSE_RESPONSE, Self::parse_response(success))
+    }
+
+    fn parse_response(success: JsValue) -> WalletResult<WalletAccount> {
+        let get_accounts = Reflection::new(success)?.reflect_js_array("accounts")?;
+
+        let mut accounts = get_accounts
+            .into_iter()
+            .map(|raw_account| WalletAccount::parse(Reflection::new(raw_account)?))
+            .collect::<WalletResult<Vec<WalletAccount>>>()?;
+
+        if accounts.is_empty() {
+            Err(WalletError::ConnectHasNoAccounts)
+        } else {
+            Ok(accounts.remove(0))
+        }
     }
 }
@@ -1,6 +1,9 @@
 use web_sys::{js_sys, wasm_bindgen::JsValue};
 
-use crate::{Reflection, SemverVersion, StandardFunction, WalletError, WalletResult};
+use crate::{
+    CallContext, Reflection, SemverVersion, StandardFunction, WalletResult,
+    STANDARD_DISCONNECT_IDENTIFIER,
+};
 
 /// `standard:disconnect` struct containing the `version` and `callback`
 /// in the field [StandardFunction]
@@ -20,15 +23,20 @@ impl Disconnect {
 
     /// Calling this method disconnects the wallet by internally calling the
     /// callback function
-    pub(crate) async fn call_disconnect(&self) -> WalletResult<()> {
-        let outcome = self.0.callback.call0(&JsValue::null())?;
+    pub(crate) async fn call_disconnect(&self, wallet_name: &str) -> WalletResult<()> {
+        let context = CallContext::new(wallet_name, STANDARD_DISCONNECT_IDENTIFIER);
+
+        let outcome = context.map(
+            CallContext::REQUEST,
+            self.0.callback.call0(&JsValue::null()),
+        )?;
 
         let outcome = js_sys::Promise::resolve(&outcome);
 
-        if let Some(error) = wasm_bindgen_futures::JsFuture::from(outcome).await.err() {
-            let value: WalletError = error.into();
-            return Err(WalletError::WalletDisconnectError(value.to_string()));
-        }
+        context.map(
+            CallContext::RESPONSE,
+            wasm_bindgen_futures::JsFuture::from(outcome).await,
+        )?;
 
         Ok(())
     }
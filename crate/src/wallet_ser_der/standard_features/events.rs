@@ -4,8 +4,9 @@ use async_channel::Receiver;
 use web_sys::wasm_bindgen::{prelude::Closure, JsValue};
 
 use crate::{
-    ConnectionInfoInner, Reflection, SemverVersion, StandardFunction, WalletAccount, WalletError,
-    WalletEvent, WalletEventSender, WalletResult,
+    CallContext, ConnectionInfoInner, Features, Reflection, SemverVersion, StandardFunction,
+    Wallet, WalletAccount, WalletEvent, WalletEventSender, WalletResult, WalletStorage,
+    STANDARD_EVENTS_IDENTIFIER,
 };
 
 /// `standard:events` struct containing the `version` and `callback`
@@ -27,30 +28,80 @@ impl StandardEvents {
     pub(crate) async fn call_on_event(
         &self,
         connection_info: ConnectionInfoInner,
+        storage: Option<WalletStorage>,
         wallet_name: String,
         sender: WalletEventSender,
         stop_signal: Receiver<()>,
     ) -> WalletResult<()> {
         let sender2 = sender.clone();
+        let listener_wallet_name = wallet_name.clone();
 
         let on_account_change = Closure::wrap(Box::new(move |value: JsValue| {
             let wallet_name = wallet_name.clone();
-            web_sys::console::log_3(
-                &"CALLED ON EV for ".into(),
-                &wallet_name.clone().into(),
-                &value,
+            wallet_log!(
+                trace,
+                "`standard:events` change emitted by `{wallet_name}` wallet: {value:?}"
             );
 
             let connection_info_inner = connection_info.clone();
+            let storage = storage.clone();
             let sender_inner = sender2.clone();
 
             wasm_bindgen_futures::spawn_local(async move {
-                let reflect_accounts =
-                    send_wallet_event_error()(Reflection::new(value), sender_inner.clone())
-                        .await
-                        .unwrap(); // Never fails
+                let context = CallContext::new(&wallet_name, STANDARD_EVENTS_IDENTIFIER);
+
+                let change = send_wallet_event_error()(
+                    context.map(CallContext::PARSE_RESPONSE, Reflection::new(value)),
+                    sender_inner.clone(),
+                )
+                .await
+                .unwrap(); // Never fails
+
+                // A change event contains at least one of the `chains`, `features`
+                // or `accounts` properties
+                if change.reflect_inner("chains").is_ok() {
+                    if let Ok((chains, supported_chains)) = send_wallet_event_error()(
+                        context.map(CallContext::PARSE_RESPONSE, Wallet::parse_chains(&change)),
+                        sender_inner.clone(),
+                    )
+                    .await
+                    {
+                        connection_info_inner.write().await.update_chains(
+                            &wallet_name,
+                            chains,
+                            supported_chains,
+                            storage.as_ref(),
+                            sender_inner.clone(),
+                        );
+                    }
+                }
+
+                if change.reflect_inner("features").is_ok() {
+                    if let Ok((features, supported_features)) = send_wallet_event_error()(
+                        context.map(CallContext::PARSE_RESPONSE, Features::parse(&change)),
+                        sender_inner.clone(),
+                    )
+                    .await
+                    {
+                        connection_info_inner.write().await.update_features(
+                            &wallet_name,
+                            features,
+                            supported_features,
+                            storage.as_ref(),
+                            sender_inner.clone(),
+                        );
+                    }
+                }
+
+                if change.reflect_inner("accounts").is_err() {
+                    return;
+                }
+
                 let mut get_accounts = send_wallet_event_error()(
-                    reflect_accounts.reflect_js_array("accounts"),
+                    context.map(
+                        CallContext::PARSE_RESPONSE,
+                        change.reflect_js_array("accounts"),
+                    ),
                     sender_inner.clone(),
                 )
                 .await
@@ -59,21 +110,27 @@ impl StandardEvents {
 
                 let processed_wallet_account = if !get_accounts.is_empty() {
                     let first_account = send_wallet_event_error()(
-                        Reflection::new(get_accounts.remove(0)),
+                        context.map(
+                            CallContext::PARSE_RESPONSE,
+                            Reflection::new(get_accounts.remove(0)),
+                        ),
                         sender_inner.clone(),
                     )
                     .await
                     .unwrap(); // Never fails
 
                     let account_processing = send_wallet_event_error()(
-                        WalletAccount::parse(first_account),
+                        context.map(
+                            CallContext::PARSE_RESPONSE,
+                            WalletAccount::parse(first_account),
+                        ),
                         sender_inner.clone(),
                     )
                     .await
                     .unwrap(); //Never fails
-                    web_sys::console::error_2(
-                        &"PRE ACCOUNT PROCESSING".into(),
-                        &format!("{account_processing:?}").into(),
+                    wallet_log!(
+                        debug,
+                        "Processed account from `{wallet_name}` wallet change event: {account_processing:?}"
                     );
 
                     Some(account_processing)
@@ -95,18 +152,17 @@ impl StandardEvents {
         let on_event_fn = self.0.callback.clone();
 
         wasm_bindgen_futures::spawn_local(async move {
+            let context = CallContext::new(&listener_wallet_name, STANDARD_EVENTS_IDENTIFIER);
+
             while (stop_signal.recv().await).is_ok() {
-                let invoke_outcome = on_event_fn
-                    .call2(
+                let invoke_outcome = context.map(
+                    CallContext::REQUEST,
+                    on_event_fn.call2(
                         &JsValue::null(),
                         &"change".into(),
                         &on_account_change_fn.clone().into(),
-                    )
-                    .map_err(|error| {
-                        let into_error: WalletError = error.into();
-
-                        into_error
-                    });
+                    ),
+                );
 
                 send_wallet_event_error()(invoke_outcome, sender.clone())
                     .await
@@ -118,12 +174,11 @@ impl StandardEvents {
     }
 }
 
-pub(crate) async fn send_wallet_event(wallet_event: WalletEvent, sender: WalletEventSender) {
-    if let Err(error) = sender.clone().send(wallet_event).await {
-        web_sys::console::log_2(
-            &"BACKGROUND TASK ERROR: [standard:events]on() > ".into(),
-            &format!("{error:?}").into(),
-        );
+pub(crate) fn send_wallet_event(wallet_event: WalletEvent, sender: WalletEventSender) {
+    wallet_log!(debug, "Broadcasting wallet event: {wallet_event}");
+
+    if sender.send(wallet_event) == 0 {
+        wallet_log!(trace, "The wallet event has no subscribers");
     }
 }
 
@@ -139,20 +194,12 @@ where
             match outcome {
                 Ok(value) => Ok(value),
                 Err(error) => {
-                    web_sys::console::log_2(
-                        &"BACKGROUND TASK ERROR: [standard:events]on() > ".into(),
-                        &format!("{error:?}").into(),
+                    wallet_log!(
+                        error,
+                        "Background task error in `standard:events` listener: {error}"
                     );
 
-                    if let Err(channel_error) = sender
-                        .send(WalletEvent::BackgroundTaskError(error.clone()))
-                        .await
-                    {
-                        web_sys::console::log_2(
-                            &"Encountered error while sending a wallet event: ".into(),
-                            &format!("{channel_error:?}").into(),
-                        );
-                    }
+                    send_wallet_event(WalletEvent::BackgroundTaskError(error), sender);
 
                     Err(())
                 }
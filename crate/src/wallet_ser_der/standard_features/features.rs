@@ -1,9 +1,10 @@
 use crate::{
-    Connect, Disconnect, FeatureSupport, Reflection, SemverVersion, SignIn, SignMessage,
-    SignTransaction, StandardEvents, WalletError, WalletResult,
+    Connect, Disconnect, FeatureSupport, Reflection, SemverRequirement, SemverVersion, SignIn,
+    SignMessage, SignTransaction, StandardEvents, WalletError, WalletResult,
     SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER, SOLANA_SIGN_IN_IDENTIFIER,
     SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
     STANDARD_CONNECT_IDENTIFIER, STANDARD_DISCONNECT_IDENTIFIER, STANDARD_EVENTS_IDENTIFIER,
+    SUPPORTED_FEATURE_VERSIONS,
 };
 
 /// All the features of `standard` and `solana` events as specified
@@ -31,7 +32,9 @@ pub struct Features {
 }
 
 impl Features {
-    /// Parse all the features from a wallet described as a [wasm_bindgen::JsValue]
+    /// Parse all the features from a wallet described as a [wasm_bindgen::JsValue].
+    /// Features whose version does not match [SUPPORTED_FEATURE_VERSIONS] are skipped
+    /// and reported in the [crate::WalletDiagnostics] of the wallet
     pub(crate) fn parse(reflection: &Reflection) -> WalletResult<(Self, FeatureSupport)> {
         let features_keys = reflection.object_to_vec_string("features")?;
         let features_object = Reflection::new_from_str(reflection.get_inner(), "features")?;
@@ -40,51 +43,113 @@ impl Features {
         let mut supported_features = FeatureSupport::default();
 
         features_keys.into_iter().try_for_each(|feature| {
-            let inner_object = features_object.reflect_inner(&feature)?;
-            let inner_object = Reflection::new(inner_object)?;
-
-            if feature.starts_with("standard:") || feature.starts_with("solana:") {
-                let version = SemverVersion::from_jsvalue(&inner_object)?;
-
-                if feature == STANDARD_CONNECT_IDENTIFIER {
-                    features.connect = Connect::new(&inner_object, version)?;
-                    supported_features.connect = true;
-                } else if feature == STANDARD_DISCONNECT_IDENTIFIER {
-                    features.disconnect = Disconnect::new(&inner_object, version)?;
-                    supported_features.disconnect = true;
-                } else if feature == STANDARD_EVENTS_IDENTIFIER {
-                    features.events = StandardEvents::new(&inner_object, version)?;
-                    supported_features.events = true;
-                } else if feature == SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER {
-                    features.sign_and_send_tx =
-                        SignTransaction::new_sign_and_send_tx(&inner_object, version)?;
-                    supported_features.sign_and_send_tx = true;
-                } else if feature == SOLANA_SIGN_TRANSACTION_IDENTIFIER {
-                    features.sign_tx = SignTransaction::new_sign_tx(&inner_object, version)?;
-                    supported_features.sign_tx = true;
-                } else if feature == SOLANA_SIGN_MESSAGE_IDENTIFIER {
-                    features.sign_message = SignMessage::new(&inner_object, version)?;
-                    supported_features.sign_message = true;
-                } else if feature == SOLANA_SIGN_IN_IDENTIFIER {
-                    features
-                        .sign_in
-                        .replace(SignIn::new(&inner_object, version)?);
-                    supported_features.sign_in = true;
-                } else {
-                    return Err(WalletError::UnsupportedWalletFeature(feature));
+            match features.parse_feature(&features_object, feature, &mut supported_features) {
+                Err(error @ WalletError::UnsupportedFeatureVersion { .. }) => {
+                    wallet_log!(warn, "Skipping the feature of the wallet: {error}");
+
+                    Ok(())
                 }
-            } else {
-                features.extensions.push(feature);
+                outcome => outcome,
             }
-
-            Ok(())
         })?;
 
         Ok((features, supported_features))
     }
 
+    /// Parse a single feature described by the key `feature` from the `features` object
+    /// of a wallet, updating [Self] and the [FeatureSupport] if the feature is supported
+    pub(crate) fn parse_feature(
+        &mut self,
+        features_object: &Reflection,
+        feature: String,
+        supported_features: &mut FeatureSupport,
+    ) -> WalletResult<()> {
+        let inner_object = features_object.reflect_inner(&feature)?;
+        let inner_object = Reflection::new(inner_object)?;
+
+        if feature.starts_with("standard:") || feature.starts_with("solana:") {
+            let version = SemverVersion::from_jsvalue(&inner_object)?;
+            Self::check_version(&feature, &version)?;
+
+            if feature == STANDARD_CONNECT_IDENTIFIER {
+                self.connect = Connect::new(&inner_object, version)?;
+                supported_features.connect = true;
+            } else if feature == STANDARD_DISCONNECT_IDENTIFIER {
+                self.disconnect = Disconnect::new(&inner_object, version)?;
+                supported_features.disconnect = true;
+            } else if feature == STANDARD_EVENTS_IDENTIFIER {
+                self.events = StandardEvents::new(&inner_object, version)?;
+                supported_features.events = true;
+            } else if feature == SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER {
+                self.sign_and_send_tx =
+                    SignTransaction::new_sign_and_send_tx(&inner_object, version)?;
+                supported_features.sign_and_send_tx = true;
+            } else if feature == SOLANA_SIGN_TRANSACTION_IDENTIFIER {
+                self.sign_tx = SignTransaction::new_sign_tx(&inner_object, version)?;
+                supported_features.sign_tx = true;
+            } else if feature == SOLANA_SIGN_MESSAGE_IDENTIFIER {
+                self.sign_message = SignMessage::new(&inner_object, version)?;
+                supported_features.sign_message = true;
+            } else if feature == SOLANA_SIGN_IN_IDENTIFIER {
+                self.sign_in.replace(SignIn::new(&inner_object, version)?);
+                supported_features.sign_in = true;
+            } else {
+                return Err(WalletError::UnsupportedWalletFeature(feature));
+            }
+        } else {
+            self.extensions.push(feature);
+        }
+
+        Ok(())
+    }
+
+    /// Check that the `version` of a `standard` or `solana` feature
+    /// matches [SUPPORTED_FEATURE_VERSIONS]
+    fn check_version(feature: &str, version: &SemverVersion) -> WalletResult<()> {
+        let requirement = SemverRequirement::parse(SUPPORTED_FEATURE_VERSIONS)?;
+
+        if version.matches(&requirement) {
+            Ok(())
+        } else {
+            Err(WalletError::UnsupportedFeatureVersion {
+                feature: feature.to_string(),
+                version: version.to_string(),
+                requirement: requirement.to_string(),
+            })
+        }
+    }
+
     /// Get all extensions on the wallet
     pub fn extensions(&self) -> &[String] {
         &self.extensions
     }
 }
+
+#[cfg(test)]
+mod features_tests {
+    use super::*;
+
+    #[test]
+    fn feature_versions() {
+        let check = |version: &str| {
+            Features::check_version(
+                SOLANA_SIGN_IN_IDENTIFIER,
+                &SemverVersion::parse(version).unwrap(),
+            )
+        };
+
+        assert!(check("1.0.0").is_ok());
+        assert!(check("1.2.3+build.1").is_ok());
+        assert_eq!(
+            check("2.0.0"),
+            Err(WalletError::UnsupportedFeatureVersion {
+                feature: SOLANA_SIGN_IN_IDENTIFIER.to_string(),
+                version: "2.0.0".to_string(),
+                requirement: "^1".to_string(),
+            })
+        );
+        assert!(check("2.1.0").unwrap_err().is_unsupported());
+        assert!(check("0.9.0").is_err());
+        assert!(check("1.0.0-beta.1").is_err());
+    }
+}
@@ -1,10 +1,12 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
 use ed25519_dalek::{Signature, VerifyingKey};
 use web_sys::{js_sys, wasm_bindgen::JsValue};
 
 use core::str;
 
 use crate::{
-    Reflection, SemverVersion, StandardFunction, Utils, WalletAccount, WalletError, WalletResult,
+    CallContext, Reflection, SemverVersion, StandardFunction, Utils, WalletAccount, WalletError,
+    WalletResult, SOLANA_SIGN_MESSAGE_IDENTIFIER,
 };
 
 /// `solana:signMessage` containing the `version` and `callback` within
@@ -24,25 +26,59 @@ impl SignMessage {
     }
 
     /// Internal callback to request a browser wallet to sign a message
-    pub(crate) async fn call_sign_message<'a>(
+    pub(crate) async fn call_sign_message(
         &self,
+        wallet_name: &str,
         wallet_account: &WalletAccount,
-        message: &'a [u8],
-    ) -> WalletResult<SignedMessageOutput<'a>> {
+        message: &[u8],
+    ) -> WalletResult<SignedMessageOutput> {
+        let context = CallContext::new(wallet_name, SOLANA_SIGN_MESSAGE_IDENTIFIER);
+
+        let message_object = context.map(
+            CallContext::REQUEST,
+            Self::request_object(wallet_account, message),
+        )?;
+
+        // Call the callback with message and account
+        let outcome = context.map(
+            CallContext::REQUEST,
+            self.0.callback.call1(&JsValue::null(), &message_object),
+        )?;
+
+        let outcome = js_sys::Promise::resolve(&outcome);
+        let signed_message_result = context.map(
+            CallContext::RESPONSE,
+            wasm_bindgen_futures::JsFuture::from(outcome).await,
+        )?;
+
+        let (signed_message, signature) = context.map(
+            CallContext::PARSE_RESPONSE,
+            Self::parse_response(signed_message_result),
+        )?;
+
+        context.map(
+            CallContext::VERIFY_RESPONSE,
+            Self::verify_response(wallet_account, message, &signed_message, signature),
+        )?;
+
+        Ok(SignedMessageOutput {
+            message: signed_message,
+            public_key: wallet_account.public_key,
+            signature: signature.to_bytes(),
+        })
+    }
+
+    fn request_object(wallet_account: &WalletAccount, message: &[u8]) -> WalletResult<JsValue> {
         let message_value: js_sys::Uint8Array = message.into();
 
         let mut message_object = Reflection::new_object();
         message_object.set_object(&"account".into(), &wallet_account.js_value)?;
         message_object.set_object(&"message".into(), &message_value)?;
 
-        // Call the callback with message and account
-        let outcome = self
-            .0
-            .callback
-            .call1(&JsValue::null(), message_object.get_inner())?;
+        Ok(message_object.take())
+    }
 
-        let outcome = js_sys::Promise::resolve(&outcome);
-        let signed_message_result = wasm_bindgen_futures::JsFuture::from(outcome).await?;
+    fn parse_response(signed_message_result: JsValue) -> WalletResult<(Vec<u8>, Signature)> {
         let incase_of_error = Err(WalletError::InternalError(format!(
             "solana:signedMessage -> SignedMessageOutput: Casting `{signed_message_result:?}` did not yield a Uini8Array"
         )));
@@ -66,44 +102,81 @@ impl SignMessage {
                 .or(incase_of_error)?
                 .to_vec();
 
-            if signed_message != message {
-                return Err(WalletError::SignedMessageMismatch);
-            }
-
             let signature = Utils::jsvalue_to_signature(
                 signature_value,
                 "solana::signMessage -> SignedMessageOutput::signature",
             )?;
 
-            let public_key = Utils::public_key(wallet_account.public_key)?;
-
-            Utils::verify_signature(public_key, message, signature)?;
-
-            Ok(SignedMessageOutput {
-                message,
-                public_key: wallet_account.public_key,
-                signature: signature.to_bytes(),
-            })
+            Ok((signed_message, signature))
         } else {
             Err(WalletError::ReceivedAnEmptySignedMessagesArray)
         }
     }
+
+    fn verify_response(
+        wallet_account: &WalletAccount,
+        message: &[u8],
+        signed_message: &[u8],
+        signature: Signature,
+    ) -> WalletResult<()> {
+        if signed_message != message {
+            return Err(WalletError::SignedMessageMismatch);
+        }
+
+        let public_key = Utils::public_key(wallet_account.public_key)?;
+
+        Utils::verify_signature(public_key, message, signature)
+    }
 }
 
-/// The output of a signed message
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
-pub struct SignedMessageOutput<'a> {
-    message: &'a [u8],
+/// The output of a signed message which owns the signed bytes
+/// so it can be stored or sent to another task.
+/// The message can be any bytes, not only UTF-8 text.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SignedMessageOutput {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::base64_bytes"))]
+    message: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::byte_array"))]
     public_key: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::byte_array"))]
     signature: [u8; 64],
 }
 
-impl SignedMessageOutput<'_> {
-    /// Get the message as a [UTF-8 str](core::str)
+impl SignedMessageOutput {
+    /// Instantiate [Self] from a message and the public key and signature
+    /// that were used to sign the message
+    pub fn new(message: &[u8], public_key: [u8; 32], signature: [u8; 64]) -> Self {
+        Self {
+            message: message.to_vec(),
+            public_key,
+            signature,
+        }
+    }
+
+    /// Get the message as a [UTF-8 str](core::str).
+    /// Returns an empty string if the message is not UTF-8 encoded
+    #[deprecated(
+        note = "Use `message_str()` or `message_bytes()` which support messages that are not UTF-8 encoded"
+    )]
     pub fn message(&self) -> &str {
-        //Should never fail since verified message is always UTF-8 Format hence `.unwrap()` is used.
-        // This is verified to be the input message where the input message is always UTF-8 encoded
-        str::from_utf8(self.message).unwrap()
+        self.message_str().unwrap_or_default()
+    }
+
+    /// Get the bytes of the signed message
+    pub fn message_bytes(&self) -> &[u8] {
+        self.message.as_slice()
+    }
+
+    /// Get the message as a [UTF-8 str](core::str), returning an error
+    /// if the message is not UTF-8 encoded, like when signing a hash
+    pub fn message_str(&self) -> WalletResult<&str> {
+        str::from_utf8(&self.message)
+            .map_err(|error| WalletError::InvalidUtf8Message(error.to_string()))
     }
 
     /// Get the public key as an [Ed25519 Public Key](VerifyingKey)
@@ -117,7 +190,7 @@ impl SignedMessageOutput<'_> {
     }
 
     /// Get the [Ed25519 Signature](Signature) that was generated when
-    /// the [Ed25519 Public Key](VerifyingKey) signed the UTF-8 encoded message
+    /// the [Ed25519 Public Key](VerifyingKey) signed the message
     pub fn signature(&self) -> Signature {
         Utils::signature(self.signature)
     }
@@ -126,14 +199,149 @@ impl SignedMessageOutput<'_> {
     pub fn base58_signature(&self) -> WalletResult<String> {
         Ok(Utils::base58_signature(self.signature()))
     }
+
+    /// Export a portable [SignedMessageProof] that can be verified without the wallet
+    pub fn proof(&self) -> WalletResult<SignedMessageProof> {
+        Ok(SignedMessageProof {
+            address: self.address()?,
+            message: BASE64_STANDARD.encode(&self.message),
+            signature: self.base58_signature()?,
+        })
+    }
 }
 
-impl Default for SignedMessageOutput<'_> {
+impl Default for SignedMessageOutput {
     fn default() -> Self {
         Self {
-            message: &[],
+            message: Vec::default(),
             public_key: [0u8; 32],
             signature: [0u8; 64],
         }
     }
 }
+
+/// A portable proof that an address signed a message, containing the Base58 address,
+/// the base64 encoded message and the Base58 signature.
+/// It can be verified natively, like in a backend server, using [Self::verify].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SignedMessageProof {
+    /// The Base58 address of the signer
+    pub address: String,
+    /// The base64 encoded message
+    pub message: String,
+    /// The Base58 encoded Ed25519 signature
+    pub signature: String,
+}
+
+impl SignedMessageProof {
+    /// Decode the message bytes
+    pub fn message_bytes(&self) -> WalletResult<Vec<u8>> {
+        BASE64_STANDARD
+            .decode(&self.message)
+            .map_err(|error| WalletError::InvalidSignedMessageProof(error.to_string()))
+    }
+
+    /// Verify the signature of the message by the address, returning the decoded
+    /// [SignedMessageOutput] if the signature is valid
+    pub fn verify(&self) -> WalletResult<SignedMessageOutput> {
+        let mut public_key = [0u8; 32];
+        match bs58::decode(&self.address).onto(&mut public_key) {
+            Ok(32) => {}
+            _ => return Err(WalletError::InvalidBase58Address),
+        }
+
+        let mut signature = [0u8; 64];
+        match bs58::decode(&self.signature).onto(&mut signature) {
+            Ok(64) => {}
+            _ => return Err(WalletError::InvalidEd25519SignatureBytes),
+        }
+
+        let message = self.message_bytes()?;
+
+        Utils::verify_signature(
+            Utils::public_key(public_key)?,
+            &message,
+            Utils::signature(signature),
+        )?;
+
+        Ok(SignedMessageOutput {
+            message,
+            public_key,
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod signed_message_tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    fn signed(message: &[u8]) -> SignedMessageOutput {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+
+        SignedMessageOutput::new(
+            message,
+            signing_key.verifying_key().to_bytes(),
+            signing_key.sign(message).to_bytes(),
+        )
+    }
+
+    #[test]
+    fn binary_message() {
+        let hash = blake3::hash(b"SOLANA ROCKS!!!");
+        let output = signed(hash.as_bytes());
+
+        assert_eq!(output.message_bytes(), hash.as_bytes());
+        assert!(matches!(
+            output.message_str(),
+            Err(WalletError::InvalidUtf8Message(_))
+        ));
+        assert_eq!(signed(b"Hello").message_str().unwrap(), "Hello");
+
+        // The output owns the message so it can outlive the input
+        let output = {
+            let message = b"Temporary".to_vec();
+            signed(&message)
+        };
+        assert_eq!(output.message_bytes(), b"Temporary");
+    }
+
+    #[test]
+    fn proof() {
+        let output = signed(&[0, 159, 146, 150]);
+        let proof = output.proof().unwrap();
+
+        assert_eq!(proof.address, output.address().unwrap());
+        assert_eq!(proof.message, "AJ+Slg==");
+        assert_eq!(proof.verify().unwrap(), output);
+
+        let mut tampered = proof.clone();
+        tampered.message = BASE64_STANDARD.encode([1, 159, 146, 150]);
+        assert_eq!(tampered.verify(), Err(WalletError::InvalidSignature));
+
+        let mut invalid = proof.clone();
+        invalid.message = "not base64!".to_string();
+        assert!(matches!(
+            invalid.verify(),
+            Err(WalletError::InvalidSignedMessageProof(_))
+        ));
+
+        let mut invalid = proof.clone();
+        invalid.address = "not base58".to_string();
+        assert_eq!(invalid.verify(), Err(WalletError::InvalidBase58Address));
+
+        let mut invalid = proof;
+        invalid.signature = bs58::encode([1u8; 32]).into_string();
+        assert_eq!(
+            invalid.verify(),
+            Err(WalletError::InvalidEd25519SignatureBytes)
+        );
+    }
+}
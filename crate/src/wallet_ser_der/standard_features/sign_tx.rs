@@ -7,7 +7,8 @@ use web_sys::{
 use core::hash::Hash;
 
 use crate::{
-    Cluster, Commitment, Reflection, SemverVersion, Utils, WalletAccount, WalletError, WalletResult,
+    CallContext, Cluster, Commitment, Reflection, SemverVersion, Utils, WalletAccount, WalletError,
+    WalletResult, SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
 };
 
 /// Used in `solana:SignTransaction` and `solana:SignAndSendTransaction`.
@@ -95,48 +96,91 @@ impl SignTransaction {
 
     pub(crate) async fn call_sign_tx(
         &self,
+        wallet_name: &str,
         wallet_account: &WalletAccount,
         transaction_bytes: &[u8],
         cluster: Option<Cluster>,
     ) -> WalletResult<Vec<Vec<u8>>> {
-        let tx_bytes_value: js_sys::Uint8Array = transaction_bytes.into();
+        let context = CallContext::new(wallet_name, SOLANA_SIGN_TRANSACTION_IDENTIFIER);
 
-        let mut tx_object = Reflection::new_object();
-        tx_object.set_object(&"account".into(), &wallet_account.js_value)?;
-        tx_object.set_object(&"transaction".into(), &tx_bytes_value)?;
-        if let Some(cluster) = cluster {
-            tx_object.set_object(&"chain".into(), &cluster.chain().into())?;
-        }
+        let tx_object = context.map(
+            CallContext::REQUEST,
+            Self::request_object(wallet_account, transaction_bytes, cluster),
+        )?;
 
-        let outcome = self.callback.call1(&JsValue::null(), &tx_object.take())?;
+        let outcome = context.map(
+            CallContext::REQUEST,
+            self.callback.call1(&JsValue::null(), &tx_object.take()),
+        )?;
 
         let outcome = js_sys::Promise::resolve(&outcome);
 
-        let success = wasm_bindgen_futures::JsFuture::from(outcome).await?;
-        Reflection::new(success)?.get_bytes_from_vec("signedTransaction")
+        let success = context.map(
+            CallContext::RESPONSE,
+            wasm_bindgen_futures::JsFuture::from(outcome).await,
+        )?;
+
+        context.map(
+            CallContext::PARSE_RESPONSE,
+            Reflection::new(success)
+                .and_then(|success| success.get_bytes_from_vec("signedTransaction")),
+        )
     }
 
     pub(crate) async fn call_sign_and_send_transaction(
         &self,
+        wallet_name: &str,
         wallet_account: &WalletAccount,
         transaction_bytes: &[u8],
         cluster: Cluster,
         options: SendOptions,
     ) -> WalletResult<Signature> {
+        let context = CallContext::new(wallet_name, SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER);
+
+        let tx_object = context.map(
+            CallContext::REQUEST,
+            Self::request_object(wallet_account, transaction_bytes, Some(cluster)).and_then(
+                |mut tx_object| {
+                    tx_object.set_object(&"options".into(), &options.to_object()?)?;
+
+                    Ok(tx_object)
+                },
+            ),
+        )?;
+
+        let outcome = context.map(
+            CallContext::REQUEST,
+            self.callback.call1(&JsValue::null(), &tx_object.take()),
+        )?;
+
+        let outcome = js_sys::Promise::resolve(&outcome);
+
+        let success = context.map(
+            CallContext::RESPONSE,
+            wasm_bindgen_futures::JsFuture::from(outcome).await,
+        )?;
+
+        context.map(CallContext::PARSE_RESPONSE, Self::parse_signature(success))
+    }
+
+    fn request_object(
+        wallet_account: &WalletAccount,
+        transaction_bytes: &[u8],
+        cluster: Option<Cluster>,
+    ) -> WalletResult<Reflection> {
         let tx_bytes_value: js_sys::Uint8Array = transaction_bytes.into();
 
         let mut tx_object = Reflection::new_object();
         tx_object.set_object(&"account".into(), &wallet_account.js_value)?;
         tx_object.set_object(&"transaction".into(), &tx_bytes_value)?;
-        tx_object.set_object(&"chain".into(), &cluster.chain().into())?;
-        tx_object.set_object(&"options".into(), &options.to_object()?)?;
-
-        let outcome = self.callback.call1(&JsValue::null(), &tx_object.take())?;
-
-        let outcome = js_sys::Promise::resolve(&outcome);
+        if let Some(cluster) = cluster {
+            tx_object.set_object(&"chain".into(), &cluster.chain().into())?;
+        }
 
-        let success = wasm_bindgen_futures::JsFuture::from(outcome).await?;
+        Ok(tx_object)
+    }
 
+    fn parse_signature(success: JsValue) -> WalletResult<Signature> {
         Reflection::new(success)?
             .get_bytes_from_vec("signature")?
             .first()
@@ -150,7 +194,7 @@ impl SignTransaction {
 
 impl PartialOrd for SignTransaction {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.version.cmp(&other.version))
+        Some(self.cmp(other))
     }
 }
 
@@ -1,8 +1,8 @@
 use web_sys::{js_sys, wasm_bindgen::JsValue};
 
 use crate::{
-    Reflection, SemverVersion, SignInOutput, SigninInput, StandardFunction, WalletAccount,
-    WalletError, WalletResult,
+    CallContext, Reflection, SemverVersion, SignInOutput, SigninInput, StandardFunction,
+    WalletAccount, WalletError, WalletResult, SOLANA_SIGN_IN_IDENTIFIER,
 };
 
 /// A `solana:signin` struct containing the `version` and `callback`
@@ -20,17 +20,41 @@ impl SignIn {
 
     pub(crate) async fn call_signin(
         &self,
+        wallet_name: &str,
         signin_input: &SigninInput,
         public_key: [u8; 32],
     ) -> WalletResult<SignInOutput> {
-        let outcome = self
-            .0
-            .callback
-            .call1(&JsValue::null(), &signin_input.get_object()?)?;
+        let context = CallContext::new(wallet_name, SOLANA_SIGN_IN_IDENTIFIER);
+
+        let signin_object = context.map(CallContext::REQUEST, signin_input.get_object())?;
+        let outcome = context.map(
+            CallContext::REQUEST,
+            self.0.callback.call1(&JsValue::null(), &signin_object),
+        )?;
 
         let outcome = js_sys::Promise::resolve(&outcome);
 
-        let value = wasm_bindgen_futures::JsFuture::from(outcome).await?;
+        let value = context.map(
+            CallContext::RESPONSE,
+            wasm_bindgen_futures::JsFuture::from(outcome).await,
+        )?;
+
+        let output = context.map(
+            CallContext::PARSE_RESPONSE,
+            Self::parse_response(value, public_key),
+        )?;
+
+        context.map(
+            CallContext::VERIFY_RESPONSE,
+            signin_input.check_eq(&output.message).and_then(|_| {
+                SigninInput::verify(public_key, output.message.as_bytes(), output.signature)
+            }),
+        )?;
+
+        Ok(output)
+    }
+
+    fn parse_response(value: JsValue, public_key: [u8; 32]) -> WalletResult<SignInOutput> {
         let output_array = Reflection::new(value)?.get_array()?;
 
         let first_index = Reflection::new(output_array.get(0))?;
@@ -46,16 +70,12 @@ impl SignIn {
                 stack: "INTERNAL_ERROR".to_string(),
             })?;
 
-        signin_input.check_eq(message)?;
-
         let signature_value = first_index.reflect_inner("signature")?;
         let signature_bytes: [u8; 64] = Reflection::new(signature_value)?
             .into_bytes()?
             .try_into()
             .or(Err(WalletError::InvalidEd25519SignatureBytes))?;
 
-        SigninInput::verify(public_key, &message_bytes, signature_bytes)?;
-
         Ok(SignInOutput {
             account,
             message: message.to_string(),
@@ -41,9 +41,41 @@ impl StandardFunction {
     }
 }
 
+/// Attaches the wallet name, the wallet standard feature identifier and the
+/// operation that failed to the errors of a `call_*` request to a browser wallet
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CallContext<'a> {
+    wallet: &'a str,
+    feature: &'a str,
+}
+
+impl<'a> CallContext<'a> {
+    /// Operation that builds the request and calls the callback of the wallet
+    pub(crate) const REQUEST: &'static str = "request";
+    /// Operation that awaits the response of the wallet, this fails if the wallet rejects the request
+    pub(crate) const RESPONSE: &'static str = "response";
+    /// Operation that parses the response of the wallet
+    pub(crate) const PARSE_RESPONSE: &'static str = "parse response";
+    /// Operation that verifies the response of the wallet
+    pub(crate) const VERIFY_RESPONSE: &'static str = "verify response";
+
+    pub(crate) fn new(wallet: &'a str, feature: &'a str) -> Self {
+        Self { wallet, feature }
+    }
+
+    /// Wrap the error of the `outcome` of the `operation` in [WalletError::Context]
+    pub(crate) fn map<T, E: Into<WalletError>>(
+        &self,
+        operation: &str,
+        outcome: Result<T, E>,
+    ) -> WalletResult<T> {
+        outcome.map_err(|error| error.into().context(self.wallet, self.feature, operation))
+    }
+}
+
 impl PartialOrd for StandardFunction {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.version.cmp(&other.version))
+        Some(self.cmp(other))
     }
 }
 
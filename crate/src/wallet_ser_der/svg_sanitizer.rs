@@ -0,0 +1,440 @@
+use crate::{WalletError, WalletResult};
+
+/// Elements that are removed together with all their children
+const DROPPED_ELEMENTS: &[&str] = &[
+    "script",
+    "style",
+    "foreignobject",
+    "iframe",
+    "object",
+    "embed",
+    "handler",
+    "listener",
+];
+
+/// Image data URIs that are allowed in `href` attributes and CSS `url()` references
+const SAFE_DATA_URI_PREFIXES: &[&str] = &[
+    "data:image/png;base64,",
+    "data:image/gif;base64,",
+    "data:image/webp;base64,",
+    "data:image/jpeg;base64,",
+];
+
+/// Attributes of animation elements naming the animated attribute or its values
+const ANIMATION_VALUE_ATTRIBUTES: &[&str] = &["attributeName", "to", "values", "from", "by"];
+
+/// A sanitiser for SVG documents used as wallet icons.
+/// It strips:
+/// - `<script>`, `<style>`, `<foreignObject>`, `<iframe>`, `<object>`, `<embed>`,
+///   `<handler>` and `<listener>` elements including their children.
+///   Stylesheets are dropped instead of checked since comments, CDATA sections and
+///   CSS escapes can split or hide an `@import` from any check of the text
+/// - event handler attributes like `onload` and `onclick`
+/// - `href`, `xlink:href` and `src` attributes referencing anything other than
+///   a fragment (`#id`) or an embedded PNG, GIF, WebP or JPEG data URI
+/// - CSS in `style` and presentation attributes that contains `@import`,
+///   `javascript:`, `expression()`, external `url()` references, CSS escapes or comments
+/// - `<set>` and `<animate>` elements targeting `href` attributes or using
+///   character references in their `attributeName`, `to`, `values`, `from` or `by` attributes
+/// - comments, processing instructions and `DOCTYPE` declarations
+///   (which may declare entities)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SvgSanitizer;
+
+impl SvgSanitizer {
+    /// Sanitise an SVG document returning the sanitised SVG.
+    /// An error is returned if the document is malformed or contains no `<svg>` element
+    pub fn sanitize(svg: &str) -> WalletResult<String> {
+        let mut output = String::with_capacity(svg.len());
+        let mut rest = svg;
+        // The name and nesting depth of an element being dropped
+        let mut dropping = Option::<(String, usize)>::None;
+        let mut has_svg_element = false;
+
+        while !rest.is_empty() {
+            let text_end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..text_end];
+            rest = &rest[text_end..];
+
+            if dropping.is_none() {
+                output.push_str(text);
+            }
+
+            if rest.is_empty() {
+                break;
+            }
+
+            if rest.starts_with("<!--") {
+                rest = Self::skip_past(rest, "-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                let end = rest
+                    .find("]]>")
+                    .ok_or(Self::error("unterminated CDATA section"))?;
+                let cdata = &rest[..end + 3];
+
+                if dropping.is_none() {
+                    output.push_str(cdata);
+                }
+                rest = &rest[end + 3..];
+            } else if rest.starts_with("<!") {
+                rest = Self::skip_declaration(rest)?;
+            } else if rest.starts_with("<?") {
+                rest = Self::skip_past(rest, "?>")?;
+            } else if let Some(end_tag) = rest.strip_prefix("</") {
+                let end = end_tag
+                    .find('>')
+                    .ok_or(Self::error("unterminated end tag"))?;
+                let name = end_tag[..end].trim();
+                rest = &end_tag[end + 1..];
+
+                match dropping.as_mut() {
+                    Some((dropped_name, depth)) => {
+                        if dropped_name.eq_ignore_ascii_case(name) {
+                            *depth -= 1;
+
+                            if *depth == 0 {
+                                dropping.take();
+                            }
+                        }
+                    }
+                    None => {
+                        output.push_str("</");
+                        output.push_str(name);
+                        output.push('>');
+                    }
+                }
+            } else {
+                let (tag, remaining) = StartTag::parse(rest)?;
+                rest = remaining;
+
+                if let Some((dropped_name, depth)) = dropping.as_mut() {
+                    if !tag.self_closing && dropped_name.eq_ignore_ascii_case(tag.name) {
+                        *depth += 1;
+                    }
+                    continue;
+                }
+
+                let local_name = Self::local_name(tag.name);
+
+                if DROPPED_ELEMENTS.contains(&local_name.as_str())
+                    || Self::is_href_animation(&local_name, &tag)
+                {
+                    if !tag.self_closing {
+                        dropping.replace((tag.name.to_string(), 1));
+                    }
+                    continue;
+                }
+
+                if local_name == "svg" {
+                    has_svg_element = true;
+                }
+
+                tag.write_sanitized(&mut output);
+            }
+        }
+
+        if !has_svg_element {
+            return Err(Self::error("no `<svg>` element found"));
+        }
+
+        Ok(output)
+    }
+
+    // Lowercase element or attribute name without the namespace prefix
+    fn local_name(name: &str) -> String {
+        name.rsplit(':').next().unwrap_or(name).to_ascii_lowercase()
+    }
+
+    fn is_href_animation(local_name: &str, tag: &StartTag) -> bool {
+        matches!(
+            local_name,
+            "set" | "animate" | "animatemotion" | "animatetransform"
+        ) && tag.attributes.iter().any(|attribute| {
+            let Some(value) = attribute.value else {
+                return false;
+            };
+
+            // Character references can hide the targeted attribute or the assigned value
+            (ANIMATION_VALUE_ATTRIBUTES
+                .iter()
+                .any(|name| attribute.name.eq_ignore_ascii_case(name))
+                && value.contains('&'))
+                || (attribute.name.eq_ignore_ascii_case("attributeName")
+                    && value.trim().to_ascii_lowercase().ends_with("href"))
+        })
+    }
+
+    fn is_safe_reference(value: &str) -> bool {
+        let value = value.trim();
+
+        value.starts_with('#')
+            || SAFE_DATA_URI_PREFIXES
+                .iter()
+                .any(|prefix| value.to_ascii_lowercase().starts_with(prefix))
+    }
+
+    /// Checks CSS or presentation attribute values for unsafe content
+    fn is_unsafe_css(value: &str) -> bool {
+        let normalized = value
+            .chars()
+            .filter(|char| !char.is_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        // Character references can be used to obfuscate any of the checks below
+        if normalized.contains("&#") && normalized.contains('(') {
+            return true;
+        }
+
+        // CSS escapes like `u\72l(` and comments like `@im/**/port` hide the patterns below
+        if normalized.contains('\\') || normalized.contains("/*") {
+            return true;
+        }
+
+        if ["@import", "expression(", "javascript:", "behavior:"]
+            .iter()
+            .any(|pattern| normalized.contains(pattern))
+        {
+            return true;
+        }
+
+        normalized.split("url(").skip(1).any(|reference| {
+            let reference = reference.split(')').next().unwrap_or_default();
+            let reference = reference.trim_matches(|char| char == '"' || char == '\'');
+
+            !Self::is_safe_reference(reference)
+        })
+    }
+
+    fn skip_past<'a>(value: &'a str, pattern: &str) -> WalletResult<&'a str> {
+        value
+            .find(pattern)
+            .map(|end| &value[end + pattern.len()..])
+            .ok_or(Self::error("unterminated markup"))
+    }
+
+    // Skips declarations like `<!DOCTYPE svg [ <!ENTITY ...> ]>`
+    fn skip_declaration(value: &str) -> WalletResult<&str> {
+        let mut in_subset = false;
+
+        for (index, char) in value.char_indices() {
+            match char {
+                '[' => in_subset = true,
+                ']' => in_subset = false,
+                '>' if !in_subset => return Ok(&value[index + 1..]),
+                _ => (),
+            }
+        }
+
+        Err(Self::error("unterminated declaration"))
+    }
+
+    fn error(message: &str) -> WalletError {
+        WalletError::InvalidWalletIcon(format!("Malformed SVG, {message}"))
+    }
+}
+
+struct Attribute<'a> {
+    name: &'a str,
+    value: Option<&'a str>,
+    quote: char,
+}
+
+impl Attribute<'_> {
+    fn is_safe(&self) -> bool {
+        let local_name = SvgSanitizer::local_name(self.name);
+
+        if local_name.starts_with("on") {
+            return false;
+        }
+
+        let value = match self.value {
+            Some(value) => value,
+            None => return true,
+        };
+
+        if matches!(local_name.as_str(), "href" | "src") {
+            SvgSanitizer::is_safe_reference(value)
+        } else {
+            !SvgSanitizer::is_unsafe_css(value)
+        }
+    }
+}
+
+struct StartTag<'a> {
+    name: &'a str,
+    attributes: Vec<Attribute<'a>>,
+    self_closing: bool,
+}
+
+impl<'a> StartTag<'a> {
+    // Parses a start tag from `<` returning the tag and the remaining input
+    fn parse(value: &'a str) -> WalletResult<(Self, &'a str)> {
+        let unterminated = || SvgSanitizer::error("unterminated start tag");
+
+        let mut rest = &value[1..];
+        let name_end = rest
+            .find(|char: char| char.is_whitespace() || char == '/' || char == '>')
+            .ok_or_else(unterminated)?;
+        let name = &rest[..name_end];
+
+        if name.is_empty() {
+            return Err(SvgSanitizer::error("start tag without a name"));
+        }
+
+        rest = &rest[name_end..];
+        let mut attributes = Vec::<Attribute>::new();
+
+        loop {
+            rest = rest.trim_start();
+
+            if let Some(remaining) = rest.strip_prefix("/>") {
+                return Ok((
+                    Self {
+                        name,
+                        attributes,
+                        self_closing: true,
+                    },
+                    remaining,
+                ));
+            }
+
+            if let Some(remaining) = rest.strip_prefix('>') {
+                return Ok((
+                    Self {
+                        name,
+                        attributes,
+                        self_closing: false,
+                    },
+                    remaining,
+                ));
+            }
+
+            let attribute_end = rest
+                .find(|char: char| {
+                    char.is_whitespace() || char == '=' || char == '/' || char == '>'
+                })
+                .ok_or_else(unterminated)?;
+
+            if attribute_end == 0 {
+                return Err(SvgSanitizer::error("invalid attribute"));
+            }
+
+            let attribute_name = &rest[..attribute_end];
+            rest = rest[attribute_end..].trim_start();
+
+            let (attribute_value, quote) = if let Some(remaining) = rest.strip_prefix('=') {
+                rest = remaining.trim_start();
+                let quote = rest.chars().next().ok_or_else(unterminated)?;
+
+                if quote == '"' || quote == '\'' {
+                    let value_end = rest[1..].find(quote).ok_or_else(unterminated)?;
+                    let attribute_value = &rest[1..value_end + 1];
+                    rest = &rest[value_end + 2..];
+
+                    (Some(attribute_value), quote)
+                } else {
+                    let value_end = rest
+                        .find(|char: char| char.is_whitespace() || char == '>')
+                        .ok_or_else(unterminated)?;
+                    let attribute_value = &rest[..value_end];
+                    rest = &rest[value_end..];
+
+                    // Unquoted values are written back with double quotes
+                    if attribute_value.contains('"') {
+                        return Err(SvgSanitizer::error("invalid unquoted attribute"));
+                    }
+
+                    (Some(attribute_value), '"')
+                }
+            } else {
+                (None, '"')
+            };
+
+            attributes.push(Attribute {
+                name: attribute_name,
+                value: attribute_value,
+                quote,
+            });
+        }
+    }
+
+    fn write_sanitized(&self, output: &mut String) {
+        output.push('<');
+        output.push_str(self.name);
+
+        self.attributes
+            .iter()
+            .filter(|attribute| attribute.is_safe())
+            .for_each(|attribute| {
+                output.push(' ');
+                output.push_str(attribute.name);
+
+                if let Some(value) = attribute.value {
+                    output.push('=');
+                    output.push(attribute.quote);
+                    output.push_str(value);
+                    output.push(attribute.quote);
+                }
+            });
+
+        if self.self_closing {
+            output.push_str("/>");
+        } else {
+            output.push('>');
+        }
+    }
+}
+
+#[cfg(test)]
+mod svg_sanitizer_tests {
+    use super::*;
+
+    #[test]
+    fn keeps_safe_svg() {
+        let svg = r##"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><defs><linearGradient id="g"/></defs><rect width="10" height="10" fill="url(#g)"/><use href="#g"/></svg>"##;
+
+        assert_eq!(
+            SvgSanitizer::sanitize(svg).unwrap(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><defs><linearGradient id="g"/></defs><rect width="10" height="10" fill="url(#g)"/><use href="#g"/></svg>"##
+        );
+    }
+
+    #[test]
+    fn strips_unsafe_content() {
+        let svg = r#"<!DOCTYPE svg [<!ENTITY x "y">]><!-- comment --><svg onload="alert(1)" xmlns="http://www.w3.org/2000/svg"><script>alert(<b>1</b>)</script><script/><foreignObject><div><foreignObject/></div></foreignObject><image xlink:href="https://evil.example/a.png" width="1"/><a href="javascript:alert(1)"><circle r="1" ONCLICK='x()'/></a><rect style="fill:url(https://evil.example/p)" fill="red"/><style>@import url(https://evil.example/s.css);</style><set attributeName="href" to="javascript:alert(1)"/><animate attributeName="xlink:href"></animate><set attributeName="hr&#101;f" to="javascript:alert(1)"/><animate attributeName="opacity" values="&#x31;;0"/><set attributeName="fill" to="red"/><image href="data:image/png;base64,iVBORw0KGgo="/></svg>"#;
+
+        assert_eq!(
+            SvgSanitizer::sanitize(svg).unwrap(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><image width="1"/><a><circle r="1"/></a><rect fill="red"/><set attributeName="fill" to="red"/><image href="data:image/png;base64,iVBORw0KGgo="/></svg>"#
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_svg() {
+        assert!(SvgSanitizer::sanitize("<div></div>").is_err());
+        assert!(SvgSanitizer::sanitize("<svg").is_err());
+        assert!(SvgSanitizer::sanitize(r#"<svg a="1></svg>"#).is_err());
+        assert!(SvgSanitizer::sanitize("<svg><!-- </svg>").is_err());
+    }
+
+    #[test]
+    fn drops_stylesheets() {
+        let svg = r#"<svg><style>@im<!---->port "https://evil.example/x.css";</style><style><![CDATA[@\69mport "https://evil.example/x.css";]]></style><style/><circle r="1"/></svg>"#;
+
+        assert_eq!(
+            SvgSanitizer::sanitize(svg).unwrap(),
+            r#"<svg><circle r="1"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn strips_obfuscated_css_attributes() {
+        let svg = r#"<svg><rect style="fill:u\72l(https://evil.example/p)" fill="red"/><rect fill="u/**/rl(#g)"/><rect fill="url(#g)"/></svg>"#;
+
+        assert_eq!(
+            SvgSanitizer::sanitize(svg).unwrap(),
+            r#"<svg><rect fill="red"/><rect/><rect fill="url(#g)"/></svg>"#
+        );
+    }
+}
@@ -1,31 +1,63 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cmp::Ordering};
 
 use crate::{Reflection, WalletError, WalletResult};
 
-/// Semver Versioning struct
-#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Semver Versioning struct following the [SemVer 2.0](https://semver.org/spec/v2.0.0.html)
+/// specification including pre-release and build metadata.
+///
+/// Ordering follows SemVer precedence, build metadata is only used as a tie-breaker
+/// so that the ordering stays consistent with [PartialEq].
+/// Use [SemverVersion::cmp_precedence] to compare versions while ignoring build metadata.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
 pub struct SemverVersion {
-    major: u8,
-    minor: u8,
-    patch: u8,
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Vec<PreReleaseIdentifier>,
+    build: Vec<String>,
 }
 
 impl SemverVersion {
+    /// Instantiate a new version with no pre-release or build metadata
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            ..Default::default()
+        }
+    }
+
     /// The major version
-    pub fn major(&self) -> u8 {
+    pub fn major(&self) -> u64 {
         self.major
     }
 
     /// The minor version
-    pub fn minor(&self) -> u8 {
+    pub fn minor(&self) -> u64 {
         self.minor
     }
 
     /// The patch version
-    pub fn patch(&self) -> u8 {
+    pub fn patch(&self) -> u64 {
         self.patch
     }
 
+    /// The pre-release identifiers, eg `["beta", 1]` for `1.0.0-beta.1`
+    pub fn pre_release(&self) -> &[PreReleaseIdentifier] {
+        self.pre.as_slice()
+    }
+
+    /// The build metadata identifiers, eg `["build", "5"]` for `1.0.0+build.5`
+    pub fn build(&self) -> &[String] {
+        self.build.as_slice()
+    }
+
+    /// Checks if the version has pre-release identifiers
+    pub fn is_pre_release(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
     /// Parse the version from a [JsValue]
     pub(crate) fn from_jsvalue(reflection: &Reflection) -> WalletResult<Self> {
         let version = reflection
@@ -41,51 +73,639 @@ impl SemverVersion {
 
     /// Parse a semver versioned string  into [Self]
     pub fn parse(version: &str) -> WalletResult<Self> {
-        let chunks = version.split(".").collect::<Vec<&str>>();
+        let invalid_version = || WalletError::InvalidWalletVersion(version.to_string());
+
+        let (version_core, build) = match version.split_once('+') {
+            Some((version_core, build)) => (version_core, Some(build)),
+            None => (version, None),
+        };
+
+        let (version_core, pre) = match version_core.split_once('-') {
+            Some((version_core, pre)) => (version_core, Some(pre)),
+            None => (version_core, None),
+        };
+
+        let chunks = version_core.split(".").collect::<Vec<&str>>();
 
         if chunks.len() != 3 {
-            return Err(WalletError::InvalidWalletVersion(version.to_string()));
+            return Err(invalid_version());
         }
 
         let version_chunks = chunks
             .iter()
-            .map(|chunk| {
-                chunk
-                    .parse::<u8>()
-                    .map_err(|_| WalletError::InvalidSemVerNumber(chunk.to_string()))
+            .map(|chunk| Self::parse_number(chunk))
+            .collect::<WalletResult<Vec<u64>>>()?;
+
+        let pre = pre
+            .map(|pre| {
+                Self::identifiers(pre)
+                    .ok_or_else(invalid_version)?
+                    .map(PreReleaseIdentifier::parse)
+                    .collect::<WalletResult<Vec<PreReleaseIdentifier>>>()
             })
-            .collect::<WalletResult<Vec<u8>>>()?;
+            .transpose()?
+            .unwrap_or_default();
+
+        let build = build
+            .map(|build| {
+                Ok::<_, WalletError>(
+                    Self::identifiers(build)
+                        .ok_or_else(invalid_version)?
+                        .map(|identifier| identifier.to_string())
+                        .collect::<Vec<String>>(),
+                )
+            })
+            .transpose()?
+            .unwrap_or_default();
 
         Ok(Self {
             major: version_chunks[0],
             minor: version_chunks[1],
             patch: version_chunks[2],
+            pre,
+            build,
         })
     }
 
-    /// Get the string version of [Self] in the format `major.minor.patch`
-    pub fn stringify_version(&self) -> Cow<str> {
-        Cow::Borrowed("")
-            + Cow::Owned(self.major.to_string())
-            + "."
-            + Cow::Owned(self.minor.to_string())
-            + "."
-            + Cow::Owned(self.minor.to_string())
+    // Splits dot separated identifiers making sure each is non-empty and
+    // only contains ASCII alphanumerics and hyphens
+    fn identifiers(value: &str) -> Option<impl Iterator<Item = &str>> {
+        let is_valid = value.split('.').all(|identifier| {
+            !identifier.is_empty()
+                && identifier
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || char == '-')
+        });
+
+        is_valid.then(|| value.split('.'))
+    }
+
+    fn parse_number(chunk: &str) -> WalletResult<u64> {
+        let is_numeric = !chunk.is_empty() && chunk.chars().all(|char| char.is_ascii_digit());
+
+        if !is_numeric || (chunk.len() > 1 && chunk.starts_with('0')) {
+            return Err(WalletError::InvalidSemVerNumber(chunk.to_string()));
+        }
+
+        chunk
+            .parse::<u64>()
+            .map_err(|_| WalletError::InvalidSemVerNumber(chunk.to_string()))
+    }
+
+    /// Compare two versions by SemVer precedence. Build metadata is ignored
+    /// so `1.0.0+a` and `1.0.0+b` have the same precedence.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.patch.cmp(&other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                // A version without pre-release identifiers has higher precedence
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+
+    /// Check whether the version satisfies a [SemverRequirement]
+    pub fn matches(&self, requirement: &SemverRequirement) -> bool {
+        requirement.matches(self)
+    }
+
+    /// Get the string version of [Self] in the format `major.minor.patch[-pre][+build]`
+    pub fn stringify_version(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+impl PartialOrd for SemverVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemverVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
     }
 }
 
 impl core::fmt::Debug for SemverVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "SemverVersion({}.{}.{})",
-            self.major, self.minor, self.patch
-        )
+        write!(f, "SemverVersion({self})")
     }
 }
 
 impl core::fmt::Display for SemverVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
+        if !self.pre.is_empty() {
+            let pre = self
+                .pre
+                .iter()
+                .map(|identifier| identifier.to_string())
+                .collect::<Vec<String>>();
+
+            write!(f, "-{}", pre.join("."))?;
+        }
+
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for SemverVersion {
+    type Error = WalletError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+/// A dot separated pre-release identifier of a [SemverVersion].
+/// Numeric identifiers always have lower precedence than alphanumeric identifiers.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PreReleaseIdentifier {
+    /// An identifier made up of only digits, eg `1` in `1.0.0-beta.1`
+    Numeric(u64),
+    /// An identifier containing a letter or hyphen, eg `beta` in `1.0.0-beta.1`
+    AlphaNumeric(String),
+}
+
+impl PreReleaseIdentifier {
+    fn parse(identifier: &str) -> WalletResult<Self> {
+        if identifier.chars().all(|char| char.is_ascii_digit()) {
+            SemverVersion::parse_number(identifier).map(Self::Numeric)
+        } else {
+            Ok(Self::AlphaNumeric(identifier.to_string()))
+        }
+    }
+}
+
+impl core::fmt::Display for PreReleaseIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Numeric(value) => write!(f, "{value}"),
+            Self::AlphaNumeric(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// A version requirement used to check whether a [SemverVersion] falls within a range.
+/// It is made up of comma or space separated comparators that must all match, eg.
+/// `^1.0`, `~1.2.3`, `>=1.0.0, <2.0.0`, `=1.0.0-beta.1`, `1.x` or `*`.
+///
+/// Comparators without an operator default to `^` similar to Cargo.
+/// A pre-release version only matches if a comparator has a pre-release
+/// on the same `major.minor.patch`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SemverRequirement(Vec<Comparator>);
+
+impl SemverRequirement {
+    /// Parse a requirement string into [Self]
+    pub fn parse(requirement: &str) -> WalletResult<Self> {
+        let invalid_requirement = || WalletError::InvalidSemVerRequirement(requirement.to_string());
+
+        let mut comparators = Vec::<Comparator>::new();
+        let mut pending_op = Option::<ComparatorOp>::None;
+
+        for token in requirement
+            .split(|char: char| char == ',' || char.is_whitespace())
+            .filter(|token| !token.is_empty())
+        {
+            let (op, version) = ComparatorOp::split(token);
+
+            if version.is_empty() {
+                // An operator separated from its version by whitespace eg `>= 1.0.0`
+                if pending_op
+                    .replace(op.ok_or_else(invalid_requirement)?)
+                    .is_some()
+                {
+                    return Err(invalid_requirement());
+                }
+                continue;
+            }
+
+            let op = match (pending_op.take(), op) {
+                (Some(_), Some(_)) => return Err(invalid_requirement()),
+                (Some(op), None) | (None, Some(op)) => op,
+                (None, None) => ComparatorOp::Caret,
+            };
+
+            comparators.push(Comparator::parse(op, version).ok_or_else(invalid_requirement)?);
+        }
+
+        if pending_op.is_some() || comparators.is_empty() {
+            return Err(invalid_requirement());
+        }
+
+        Ok(Self(comparators))
+    }
+
+    /// Check whether the version satisfies every comparator of the requirement
+    pub fn matches(&self, version: &SemverVersion) -> bool {
+        let comparators_match = self.0.iter().all(|comparator| comparator.matches(version));
+
+        if !comparators_match {
+            return false;
+        }
+
+        !version.is_pre_release()
+            || self.0.iter().any(|comparator| {
+                comparator.pre.is_some()
+                    && comparator.major == version.major
+                    && comparator.minor == Some(version.minor)
+                    && comparator.patch == Some(version.patch)
+            })
+    }
+}
+
+impl TryFrom<&str> for SemverRequirement {
+    type Error = WalletError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl core::fmt::Display for SemverRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let comparators = self
+            .0
+            .iter()
+            .map(|comparator| comparator.to_string())
+            .collect::<Vec<String>>();
+
+        write!(f, "{}", comparators.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum ComparatorOp {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+    Wildcard,
+}
+
+impl ComparatorOp {
+    fn split(token: &str) -> (Option<Self>, &str) {
+        [
+            (">=", Self::GreaterEq),
+            ("<=", Self::LessEq),
+            ("=", Self::Exact),
+            (">", Self::Greater),
+            ("<", Self::Less),
+            ("~", Self::Tilde),
+            ("^", Self::Caret),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (Some(op), rest)))
+        .unwrap_or((None, token))
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Exact => "=",
+            Self::Greater => ">",
+            Self::GreaterEq => ">=",
+            Self::Less => "<",
+            Self::LessEq => "<=",
+            Self::Tilde => "~",
+            Self::Caret => "^",
+            Self::Wildcard => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Comparator {
+    op: ComparatorOp,
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Option<Vec<PreReleaseIdentifier>>,
+}
+
+impl Comparator {
+    fn parse(op: ComparatorOp, version: &str) -> Option<Self> {
+        let is_wildcard = |chunk: &str| matches!(chunk, "*" | "x" | "X");
+
+        if is_wildcard(version) {
+            return Some(Self {
+                op: ComparatorOp::Wildcard,
+                major: 0,
+                minor: None,
+                patch: None,
+                pre: None,
+            });
+        }
+
+        // The build metadata is stripped first since it can contain `-`
+        let version = version_core_without_build(version);
+        let (version_core, pre) = match version.split_once('-') {
+            Some((version_core, pre)) => (version_core, Some(pre)),
+            None => (version, None),
+        };
+
+        let mut chunks = version_core.split('.');
+        let mut parts = [Option::<u64>::None; 3];
+        let mut has_wildcard = false;
+
+        for part in parts.iter_mut() {
+            match chunks.next() {
+                Some(chunk) if is_wildcard(chunk) => has_wildcard = true,
+                Some(chunk) => {
+                    // A number after a wildcard such as `1.x.3` is invalid
+                    if has_wildcard {
+                        return None;
+                    }
+                    part.replace(SemverVersion::parse_number(chunk).ok()?);
+                }
+                None => break,
+            }
+        }
+
+        if chunks.next().is_some() {
+            return None;
+        }
+
+        let pre = pre
+            .map(|pre| {
+                SemverVersion::identifiers(pre)?
+                    .map(|identifier| PreReleaseIdentifier::parse(identifier).ok())
+                    .collect::<Option<Vec<PreReleaseIdentifier>>>()
+            })
+            .map(|pre| pre.ok_or(()))
+            .transpose()
+            .ok()?;
+
+        // A pre-release requires a full `major.minor.patch`
+        if pre.is_some() && parts[2].is_none() {
+            return None;
+        }
+
+        let op = if has_wildcard && op == ComparatorOp::Caret {
+            ComparatorOp::Exact
+        } else {
+            op
+        };
+
+        Some(Self {
+            op,
+            major: parts[0]?,
+            minor: parts[1],
+            patch: parts[2],
+            pre,
+        })
+    }
+
+    fn lower(&self) -> SemverVersion {
+        SemverVersion {
+            major: self.major,
+            minor: self.minor.unwrap_or_default(),
+            patch: self.patch.unwrap_or_default(),
+            pre: self.pre.clone().unwrap_or_default(),
+            build: Vec::default(),
+        }
+    }
+
+    fn matches(&self, version: &SemverVersion) -> bool {
+        let lower = self.lower();
+        let cmp = version.cmp_precedence(&lower);
+
+        // Compare only the components specified by the comparator
+        let partial_eq = version.major == self.major
+            && self.minor.is_none_or(|minor| version.minor == minor)
+            && self.patch.is_none_or(|patch| version.patch == patch);
+
+        match self.op {
+            ComparatorOp::Wildcard => true,
+            ComparatorOp::Exact => match self.patch {
+                Some(_) => cmp == Ordering::Equal,
+                None => partial_eq,
+            },
+            ComparatorOp::Greater => match (self.minor, self.patch) {
+                (Some(_), Some(_)) => cmp == Ordering::Greater,
+                (Some(minor), None) => (version.major, version.minor) > (self.major, minor),
+                _ => version.major > self.major,
+            },
+            ComparatorOp::GreaterEq => cmp != Ordering::Less,
+            ComparatorOp::Less => cmp == Ordering::Less,
+            ComparatorOp::LessEq => match (self.minor, self.patch) {
+                (Some(_), Some(_)) => cmp != Ordering::Greater,
+                (Some(minor), None) => (version.major, version.minor) <= (self.major, minor),
+                _ => version.major <= self.major,
+            },
+            ComparatorOp::Tilde => {
+                cmp != Ordering::Less
+                    && version.major == self.major
+                    && self.minor.is_none_or(|minor| version.minor == minor)
+            }
+            ComparatorOp::Caret => {
+                if cmp == Ordering::Less || version.major != self.major {
+                    return false;
+                }
+
+                match (self.major, self.minor, self.patch) {
+                    (0, Some(0), Some(patch)) => version.minor == 0 && version.patch == patch,
+                    (0, Some(minor), _) => version.minor == minor,
+                    _ => true,
+                }
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for Comparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.op == ComparatorOp::Wildcard {
+            return write!(f, "*");
+        }
+
+        write!(f, "{}{}", self.op.as_str(), self.major)?;
+
+        match self.minor {
+            Some(minor) => write!(f, ".{minor}")?,
+            None => return Ok(()),
+        }
+
+        match self.patch {
+            Some(patch) => write!(f, ".{patch}")?,
+            None => return Ok(()),
+        }
+
+        if let Some(pre) = self.pre.as_ref() {
+            let pre = pre
+                .iter()
+                .map(|identifier| identifier.to_string())
+                .collect::<Vec<String>>();
+
+            write!(f, "-{}", pre.join("."))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Build metadata is ignored when matching requirements
+fn version_core_without_build(value: &str) -> &str {
+    value.split_once('+').map(|(left, _)| left).unwrap_or(value)
+}
+
+#[cfg(test)]
+mod semver_tests {
+    use super::*;
+
+    fn version(value: &str) -> SemverVersion {
+        SemverVersion::parse(value).unwrap()
+    }
+
+    fn matches(requirement: &str, value: &str) -> bool {
+        SemverRequirement::parse(requirement)
+            .unwrap()
+            .matches(&version(value))
+    }
+
+    #[test]
+    fn parse() {
+        let parsed = version("1.0.0-beta.1+build.5");
+        assert_eq!(parsed.major(), 1);
+        assert_eq!(parsed.minor(), 0);
+        assert_eq!(parsed.patch(), 0);
+        assert_eq!(
+            parsed.pre_release(),
+            &[
+                PreReleaseIdentifier::AlphaNumeric("beta".to_string()),
+                PreReleaseIdentifier::Numeric(1)
+            ]
+        );
+        assert_eq!(parsed.build(), &["build".to_string(), "5".to_string()]);
+
+        assert_eq!(version("2.300.0").minor(), 300);
+        assert_eq!(version("1.0.0+build").build(), &["build".to_string()]);
+        assert_eq!(
+            version("1.0.0-x-y-z.--").pre_release(),
+            &[
+                PreReleaseIdentifier::AlphaNumeric("x-y-z".to_string()),
+                PreReleaseIdentifier::AlphaNumeric("--".to_string())
+            ]
+        );
+
+        for invalid in [
+            "1.0",
+            "1.0.0.0",
+            "01.0.0",
+            "1.0.0-",
+            "1.0.0+",
+            "1.0.0-beta..1",
+            "1.0.0-01",
+            "1.0.0-beta_1",
+            "v1.0.0",
+            "",
+        ] {
+            assert!(SemverVersion::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn stringify() {
+        for value in [
+            "1.2.3",
+            "1.0.0-alpha.1",
+            "1.0.0+20130313144700",
+            "1.0.0-rc.1+b",
+        ] {
+            assert_eq!(version(value).to_string(), value);
+            assert_eq!(version(value).stringify_version(), value);
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "2.0.0",
+            "2.1.0",
+            "2.1.1",
+        ];
+
+        ordered.windows(2).for_each(|pair| {
+            assert!(version(pair[0]) < version(pair[1]), "{pair:?}");
+        });
+
+        assert_eq!(
+            version("1.0.0+a").cmp_precedence(&version("1.0.0+b")),
+            Ordering::Equal
+        );
+        assert_ne!(version("1.0.0+a"), version("1.0.0+b"));
+    }
+
+    #[test]
+    fn requirements() {
+        assert!(matches("^1.0", "1.0.0"));
+        assert!(matches("^1.0", "1.9.3"));
+        assert!(!matches("^1.0", "2.0.0"));
+        assert!(!matches("^1.2", "1.1.0"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("1.2", "1.4.0"));
+
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("~1", "1.9.0"));
+
+        assert!(matches(">=1.0.0, <2.0.0", "1.5.0"));
+        assert!(matches(">= 1.0.0 < 2.0.0", "1.5.0"));
+        assert!(!matches(">=1.0.0, <2.0.0", "2.0.0"));
+        assert!(matches(">1.0", "1.1.0"));
+        assert!(!matches(">1.0", "1.0.5"));
+        assert!(matches("<=1.2", "1.2.9"));
+        assert!(matches("=1.2.3", "1.2.3"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+
+        assert!(matches("*", "3.4.5"));
+        assert!(matches("1.x", "1.8.0"));
+        assert!(!matches("1.x", "2.0.0"));
+        assert!(matches("1.2.*", "1.2.7"));
+
+        assert!(!matches("^1.0", "1.1.0-beta.1"));
+        assert!(matches(">=1.1.0-beta.1", "1.1.0-beta.2"));
+        assert!(!matches(">=1.1.0-beta.1", "1.2.0-beta.2"));
+        assert!(matches(">=1.1.0-beta.1", "1.2.0"));
+
+        assert!(version("1.3.0").matches(&"^1.0".try_into().unwrap()));
+
+        // Build metadata containing `-` is not a pre-release
+        assert!(matches(">=1.0.0+build-1", "1.0.0"));
+        assert!(matches(">=1.0.0+build-1", "1.2.0"));
+        assert!(!matches(">=1.0.0+build-1", "1.0.0-rc.1"));
+        assert!(matches(">=1.0.0-rc.1+build-1", "1.0.0-rc.2"));
+        assert_eq!(
+            SemverRequirement::parse(">=1.0.0+build-1").unwrap(),
+            SemverRequirement::parse(">=1.0.0").unwrap()
+        );
+
+        for invalid in ["", ">=", "1.x.3", "^a.b", ">= >= 1.0.0", "1.0-beta"] {
+            assert!(SemverRequirement::parse(invalid).is_err(), "{invalid}");
+        }
     }
 }
@@ -3,8 +3,11 @@ use ed25519_dalek::Signature;
 use web_sys::wasm_bindgen::JsValue;
 
 use crate::{
-    Cluster, ConnectionInfoInner, Features, Reflection, SemverVersion, WalletAccount, WalletError,
-    WalletEventSender, WalletIcon, WalletResult,
+    logging::wallet_request, CallContext, Cluster, ConnectionInfoInner, Features, Reflection,
+    SemverVersion, WalletAccount, WalletError, WalletEventSender, WalletIcon, WalletResult,
+    WalletStorage, SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER, SOLANA_SIGN_IN_IDENTIFIER,
+    SOLANA_SIGN_MESSAGE_IDENTIFIER, SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+    STANDARD_CONNECT_IDENTIFIER, STANDARD_DISCONNECT_IDENTIFIER,
 };
 
 use super::{
@@ -29,12 +32,22 @@ pub struct Wallet {
 impl Wallet {
     /// Send a request to connect to a browser wallet
     pub async fn connect(&self) -> WalletResult<WalletAccount> {
-        self.features.connect.call_connect().await
+        wallet_request(
+            &self.name,
+            STANDARD_CONNECT_IDENTIFIER,
+            self.features.connect.call_connect(&self.name),
+        )
+        .await
     }
 
     /// Send a request to the browser wallet to disconnect
     pub async fn disconnect(&self) -> WalletResult<()> {
-        self.features.disconnect.call_disconnect().await
+        wallet_request(
+            &self.name,
+            STANDARD_DISCONNECT_IDENTIFIER,
+            self.features.disconnect.call_disconnect(&self.name),
+        )
+        .await
     }
 
     /// Send a signin request to the browser wallet
@@ -43,24 +56,37 @@ impl Wallet {
         signin_input: &SigninInput,
         public_key: [u8; 32],
     ) -> WalletResult<SignInOutput> {
-        if let Some(fn_exists) = self.features.sign_in.as_ref() {
-            fn_exists.call_signin(signin_input, public_key).await
-        } else {
-            Err(WalletError::MissingSignInFunction)
-        }
+        wallet_request(&self.name, SOLANA_SIGN_IN_IDENTIFIER, async {
+            if let Some(fn_exists) = self.features.sign_in.as_ref() {
+                fn_exists
+                    .call_signin(&self.name, signin_input, public_key)
+                    .await
+            } else {
+                CallContext::new(&self.name, SOLANA_SIGN_IN_IDENTIFIER).map(
+                    CallContext::REQUEST,
+                    Err(WalletError::MissingSignInFunction),
+                )
+            }
+        })
+        .await
     }
 
     /// Send a sign message request to the browser wallet.
-    /// Message must be UTF-8 encoded
-    pub async fn sign_message<'a>(
+    /// The message can be any bytes, use [SignedMessageOutput::message_str]
+    /// to read back a UTF-8 message
+    pub async fn sign_message(
         &self,
-        message: &'a [u8],
+        message: &[u8],
         account: &WalletAccount,
-    ) -> WalletResult<SignedMessageOutput<'a>> {
-        self.features
-            .sign_message
-            .call_sign_message(account, message)
-            .await
+    ) -> WalletResult<SignedMessageOutput> {
+        wallet_request(
+            &self.name,
+            SOLANA_SIGN_MESSAGE_IDENTIFIER,
+            self.features
+                .sign_message
+                .call_sign_message(&self.name, account, message),
+        )
+        .await
     }
 
     /// Send a sign transaction request to the browser wallet.
@@ -71,10 +97,14 @@ impl Wallet {
         cluster: Option<Cluster>,
         account: &WalletAccount,
     ) -> WalletResult<Vec<Vec<u8>>> {
-        self.features
-            .sign_tx
-            .call_sign_tx(account, transaction_bytes, cluster)
-            .await
+        wallet_request(
+            &self.name,
+            SOLANA_SIGN_TRANSACTION_IDENTIFIER,
+            self.features
+                .sign_tx
+                .call_sign_tx(&self.name, account, transaction_bytes, cluster),
+        )
+        .await
     }
 
     /// Send a sign and send transaction request to the browser wallet.
@@ -85,10 +115,20 @@ impl Wallet {
         options: SendOptions,
         account: &WalletAccount,
     ) -> WalletResult<Signature> {
-        self.features
-            .sign_and_send_tx
-            .call_sign_and_send_transaction(account, transaction_bytes, cluster, options)
-            .await
+        wallet_request(
+            &self.name,
+            SOLANA_SIGN_AND_SEND_TRANSACTION_IDENTIFIER,
+            self.features
+                .sign_and_send_tx
+                .call_sign_and_send_transaction(
+                    &self.name,
+                    account,
+                    transaction_bytes,
+                    cluster,
+                    options,
+                ),
+        )
+        .await
     }
 
     /// Get the standard events [Function](web_sys::js_sys::Function) `[standard:events].on`
@@ -101,7 +141,29 @@ impl Wallet {
     ) -> WalletResult<()> {
         self.features
             .events
-            .call_on_event(connection_info, wallet_name, sender, signal_receiver)
+            .call_on_event(connection_info, None, wallet_name, sender, signal_receiver)
+            .await
+    }
+
+    /// Same as [Self::call_on_event] but also updates the chains and features
+    /// of the wallet registered in the `storage` when the wallet emits a change event
+    pub(crate) async fn call_on_event_with_storage(
+        &self,
+        connection_info: ConnectionInfoInner,
+        storage: WalletStorage,
+        wallet_name: String,
+        sender: WalletEventSender,
+        signal_receiver: Receiver<()>,
+    ) -> WalletResult<()> {
+        self.features
+            .events
+            .call_on_event(
+                connection_info,
+                Some(storage),
+                wallet_name,
+                sender,
+                signal_receiver,
+            )
             .await
     }
 
@@ -109,28 +171,7 @@ impl Wallet {
     pub fn from_jsvalue(value: JsValue) -> WalletResult<Self> {
         let reflection = Reflection::new(value)?;
 
-        let mut supported_chains = ChainSupport::default();
-
-        let chains_raw = reflection.vec_string_and_filter("chains", "solana:")?;
-        let chains = chains_raw
-            .into_iter()
-            .map(|chain_raw| {
-                let cluster = chain_raw.as_str().try_into();
-                if let Ok(cluster_inner) = &cluster {
-                    if cluster_inner == &Cluster::MainNet {
-                        supported_chains.mainnet = true;
-                    } else if cluster_inner == &Cluster::DevNet {
-                        supported_chains.devnet = true;
-                    } else if cluster_inner == &Cluster::TestNet {
-                        supported_chains.testnet = true;
-                    } else if cluster_inner == &Cluster::LocalNet {
-                        supported_chains.localnet = true;
-                    }
-                }
-
-                cluster
-            })
-            .collect::<WalletResult<Vec<Cluster>>>()?;
+        let (chains, supported_chains) = Self::parse_chains(&reflection)?;
 
         let name = reflection.string("name")?;
         let version = SemverVersion::parse(&reflection.string("version")?)?;
@@ -150,6 +191,56 @@ impl Wallet {
         })
     }
 
+    /// Parse the Solana chains from the `chains` property of a wallet
+    /// or of a `standard:events` change event
+    pub(crate) fn parse_chains(
+        reflection: &Reflection,
+    ) -> WalletResult<(Vec<Cluster>, ChainSupport)> {
+        let chains = reflection
+            .vec_string_and_filter("chains", "solana:")?
+            .into_iter()
+            .map(|chain_raw| chain_raw.as_str().try_into())
+            .collect::<WalletResult<Vec<Cluster>>>()?;
+        let supported_chains = ChainSupport::from_clusters(&chains);
+
+        Ok((chains, supported_chains))
+    }
+
+    /// Replace the chains supported by the wallet
+    pub(crate) fn set_chains(
+        &mut self,
+        chains: Vec<Cluster>,
+        supported_chains: ChainSupport,
+    ) -> &mut Self {
+        self.chains = chains;
+        self.supported_chains = supported_chains;
+
+        self
+    }
+
+    /// Replace the features supported by the wallet
+    pub(crate) fn set_features(
+        &mut self,
+        features: Features,
+        supported_features: FeatureSupport,
+    ) -> &mut Self {
+        self.features = features;
+        self.supported_features = supported_features;
+
+        self
+    }
+
+    /// Get the identifiers of all the features supported by the wallet
+    /// including the non-standard extensions
+    pub fn feature_identifiers(&self) -> Vec<String> {
+        self.supported_features
+            .identifiers()
+            .into_iter()
+            .map(|identifier| identifier.to_string())
+            .chain(self.features.extensions().iter().cloned())
+            .collect()
+    }
+
     fn get_accounts(reflection: &Reflection, key: &str) -> WalletResult<Vec<WalletAccount>> {
         let accounts_raw = reflection.reflect_inner(key)?;
 
@@ -78,21 +78,25 @@ impl WalletAccount {
     /// It displays the first 4 characters and the last for characters
     /// separated by ellipsis eg `FXdl...RGd4` .
     /// If the address is less than 8 characters, an error is thrown
-    pub fn shorten_address(&self) -> WalletResult<Cow<str>> {
+    pub fn shorten_address(&self) -> WalletResult<Cow<'_, str>> {
         Utils::shorten_base58(&self.address)
     }
 
     /// Same as [Self::shorten_address] but with a custom range
     /// instead of taking the first 4 character and the last 4 characters
     /// it uses a custom range.
-    pub fn custom_shorten_address(&self, take: usize) -> WalletResult<Cow<str>> {
+    pub fn custom_shorten_address(&self, take: usize) -> WalletResult<Cow<'_, str>> {
         Utils::custom_shorten_base58(&self.address, take)
     }
 
     /// Same as [Self::shorten_address] but with a custom range
     /// instead of taking the first 4 character and the last 4 characters
     /// it uses a custom range for first characters before ellipsis and last characters after ellipsis.
-    pub fn custom_shorten_address_rl(&self, left: usize, right: usize) -> WalletResult<Cow<str>> {
+    pub fn custom_shorten_address_rl(
+        &self,
+        left: usize,
+        right: usize,
+    ) -> WalletResult<Cow<'_, str>> {
         if self.address.len() < left + right {
             return Err(WalletError::InvalidBase58Address);
         }
@@ -110,6 +114,69 @@ impl WalletAccount {
         let chains = reflection.vec_string("chains")?;
         let features = reflection.vec_string("features")?;
 
+        let (supported_chains, supported_features) = Self::supported(&chains, &features)?;
+
+        let icon = WalletIcon::from_jsvalue(&reflection)?;
+
+        let label = match reflection.string("label") {
+            Ok(value) => Some(value),
+            Err(error) => match error {
+                WalletError::InternalError(_) => Option::None,
+                _ => {
+                    return Err(error);
+                }
+            },
+        };
+
+        Ok(Self {
+            address,
+            public_key,
+            chains,
+            features,
+            label,
+            icon,
+            supported_chains,
+            supported_features,
+            js_value: reflection.take(),
+        })
+    }
+
+    /// Build a [WalletAccount] without a JavaScript representation, validating
+    /// that the address is the Base58 encoding of the public key and
+    /// that the chains and features are supported
+    pub(crate) fn from_parts(
+        address: String,
+        public_key: [u8; 32],
+        chains: Vec<String>,
+        features: Vec<String>,
+        label: Option<String>,
+        icon: Option<WalletIcon>,
+    ) -> WalletResult<Self> {
+        if bs58::encode(public_key).into_string() != address {
+            return Err(WalletError::InvalidBase58Address);
+        }
+
+        let (supported_chains, supported_features) = Self::supported(&chains, &features)?;
+
+        Ok(Self {
+            address,
+            public_key,
+            chains,
+            features,
+            label,
+            icon,
+            js_value: JsValue::UNDEFINED,
+            supported_features,
+            supported_chains,
+        })
+    }
+
+    /// Compute the [ChainSupport] and [FeatureSupport] from the `chains` and `features`,
+    /// returning an error if a chain or feature is not supported
+    pub(crate) fn supported(
+        chains: &[String],
+        features: &[String],
+    ) -> WalletResult<(ChainSupport, FeatureSupport)> {
         let mut supported_chains = ChainSupport::default();
 
         chains.iter().try_for_each(|chain| {
@@ -152,29 +219,7 @@ impl WalletAccount {
             Ok(())
         })?;
 
-        let icon = WalletIcon::from_jsvalue(&reflection)?;
-
-        let label = match reflection.string("label") {
-            Ok(value) => Some(value),
-            Err(error) => match error {
-                WalletError::InternalError(_) => Option::None,
-                _ => {
-                    return Err(error);
-                }
-            },
-        };
-
-        Ok(Self {
-            address,
-            public_key,
-            chains,
-            features,
-            label,
-            icon,
-            supported_chains,
-            supported_features,
-            js_value: reflection.take(),
-        })
+        Ok((supported_chains, supported_features))
     }
 
     /// Checks if MainNet is supported
@@ -1,6 +1,11 @@
 use std::borrow::Cow;
 
-use crate::{Reflection, WalletError, WalletResult};
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+use crate::{Reflection, SvgSanitizer, WalletError, WalletResult};
+
+/// The default maximum size in bytes of a decoded wallet icon
+pub const MAX_WALLET_ICON_SIZE: usize = 256 * 1024;
 
 /// A data URI containing a base64-encoded SVG, WebP, PNG, or GIF image.
 #[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -10,10 +15,28 @@ pub struct WalletIcon(
 );
 
 impl WalletIcon {
-    /// Parse the wallet from a [wasm_bindgen::JsValue]
+    /// Instantiate a [WalletIcon] from a data URI, returning an error if the data URI
+    /// is invalid or the decoded icon exceeds [MAX_WALLET_ICON_SIZE]
+    pub fn new(data_uri: &str) -> WalletResult<Self> {
+        let icon = Self(Cow::Owned(data_uri.to_string()));
+        icon.parse()?;
+
+        Ok(icon)
+    }
+
+    /// Parse the wallet from a [wasm_bindgen::JsValue].
+    /// Icons that are not valid data URIs are discarded and SVG icons are sanitised
+    /// using [SvgSanitizer] before they are stored
     pub(crate) fn from_jsvalue(reflection: &Reflection) -> WalletResult<Option<WalletIcon>> {
         let icon = match reflection.string("icon") {
-            Ok(icon) => Option::Some(WalletIcon(Cow::Owned(icon))),
+            Ok(icon) => match WalletIcon(Cow::Owned(icon)).sanitize() {
+                Ok(icon) => Some(icon),
+                Err(error) => {
+                    wallet_log!(debug, "Discarding the icon of the wallet: {error}");
+
+                    Option::None
+                }
+            },
             Err(error) => match error {
                 WalletError::InternalError(_) => Option::None,
                 _ => {
@@ -24,6 +47,81 @@ impl WalletIcon {
 
         Ok(icon)
     }
+
+    /// Parse the data URI into the [MIME type](WalletIconMimeType) and decoded bytes
+    /// enforcing the [MAX_WALLET_ICON_SIZE] limit
+    pub fn parse(&self) -> WalletResult<WalletIconData> {
+        self.parse_with_limit(MAX_WALLET_ICON_SIZE)
+    }
+
+    /// Same as [Self::parse] but with a custom size limit in bytes for the decoded icon
+    pub fn parse_with_limit(&self, max_size: usize) -> WalletResult<WalletIconData> {
+        let data_uri = self
+            .0
+            .strip_prefix("data:")
+            .ok_or(WalletError::InvalidWalletIcon(
+                "The icon is not a data URI".to_string(),
+            ))?;
+
+        let (media_type, encoded) =
+            data_uri
+                .split_once(',')
+                .ok_or(WalletError::InvalidWalletIcon(
+                    "The data URI has no data".to_string(),
+                ))?;
+
+        let mime_type =
+            media_type
+                .strip_suffix(";base64")
+                .ok_or(WalletError::InvalidWalletIcon(
+                    "The data URI is not base64 encoded".to_string(),
+                ))?;
+        let mime_type = WalletIconMimeType::try_from(mime_type)?;
+
+        // Check the size before decoding to avoid allocating large buffers
+        let estimated_size = encoded.len() / 4 * 3;
+        if estimated_size > max_size + 3 {
+            return Err(WalletError::WalletIconTooLarge(estimated_size));
+        }
+
+        let bytes = BASE64_STANDARD
+            .decode(encoded)
+            .map_err(|error| WalletError::InvalidWalletIcon(error.to_string()))?;
+
+        if bytes.len() > max_size {
+            return Err(WalletError::WalletIconTooLarge(bytes.len()));
+        }
+
+        if !mime_type.is_valid(&bytes) {
+            return Err(WalletError::InvalidWalletIcon(format!(
+                "The data does not contain a valid `{}` image",
+                mime_type.as_str()
+            )));
+        }
+
+        Ok(WalletIconData { mime_type, bytes })
+    }
+
+    /// Get the [MIME type](WalletIconMimeType) of the icon
+    pub fn mime_type(&self) -> WalletResult<WalletIconMimeType> {
+        Ok(self.parse()?.mime_type)
+    }
+
+    /// Validate the icon and sanitise it if it is an SVG image using [SvgSanitizer].
+    /// The sanitised icon is safe to use as the source of an image in the DOM
+    pub fn sanitize(&self) -> WalletResult<Self> {
+        let mut data = self.parse()?;
+
+        if data.mime_type != WalletIconMimeType::Svg {
+            return Ok(self.clone());
+        }
+
+        let svg = core::str::from_utf8(&data.bytes)
+            .map_err(|error| WalletError::InvalidWalletIcon(error.to_string()))?;
+        data.bytes = SvgSanitizer::sanitize(svg)?.into_bytes();
+
+        Ok(Self(Cow::Owned(data.to_data_uri())))
+    }
 }
 
 impl core::fmt::Display for WalletIcon {
@@ -43,3 +141,166 @@ impl core::fmt::Debug for WalletIcon {
         write!(f, "{value}",)
     }
 }
+
+/// The MIME types supported for a [WalletIcon] as defined by the wallet standard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WalletIconMimeType {
+    /// `image/svg+xml`
+    Svg,
+    /// `image/webp`
+    Webp,
+    /// `image/png`
+    Png,
+    /// `image/gif`
+    Gif,
+}
+
+impl WalletIconMimeType {
+    /// Get the MIME type as a [str]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Svg => "image/svg+xml",
+            Self::Webp => "image/webp",
+            Self::Png => "image/png",
+            Self::Gif => "image/gif",
+        }
+    }
+
+    // Checks the magic bytes of raster images and that SVG images are UTF-8
+    fn is_valid(&self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Svg => core::str::from_utf8(bytes).is_ok_and(|svg| svg.contains("<svg")),
+            Self::Webp => bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP",
+            Self::Png => bytes.starts_with(b"\x89PNG\r\n\x1a\n"),
+            Self::Gif => bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a"),
+        }
+    }
+}
+
+impl TryFrom<&str> for WalletIconMimeType {
+    type Error = WalletError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mime_type = match value {
+            "image/svg+xml" => Self::Svg,
+            "image/webp" => Self::Webp,
+            "image/png" => Self::Png,
+            "image/gif" => Self::Gif,
+            _ => {
+                return Err(WalletError::UnsupportedWalletIconMimeType(
+                    value.to_string(),
+                ))
+            }
+        };
+
+        Ok(mime_type)
+    }
+}
+
+impl core::fmt::Display for WalletIconMimeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The decoded data of a [WalletIcon]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WalletIconData {
+    mime_type: WalletIconMimeType,
+    bytes: Vec<u8>,
+}
+
+impl WalletIconData {
+    /// The [MIME type](WalletIconMimeType) of the icon
+    pub fn mime_type(&self) -> WalletIconMimeType {
+        self.mime_type
+    }
+
+    /// The decoded bytes of the icon
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Encode the icon as a base64 data URI
+    pub fn to_data_uri(&self) -> String {
+        String::new()
+            + "data:"
+            + self.mime_type.as_str()
+            + ";base64,"
+            + BASE64_STANDARD.encode(&self.bytes).as_str()
+    }
+}
+
+impl core::fmt::Debug for WalletIconData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WalletIconData")
+            .field("mime_type", &self.mime_type)
+            .field("size", &self.bytes.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod wallet_icon_tests {
+    use super::*;
+
+    const PNG: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+    fn svg_icon(svg: &str) -> WalletIcon {
+        WalletIcon(Cow::Owned(
+            "data:image/svg+xml;base64,".to_string() + &BASE64_STANDARD.encode(svg),
+        ))
+    }
+
+    #[test]
+    fn parse() {
+        let data = WalletIcon::new(PNG).unwrap().parse().unwrap();
+        assert_eq!(data.mime_type(), WalletIconMimeType::Png);
+        assert_eq!(data.to_data_uri(), PNG);
+
+        let svg = svg_icon("<svg></svg>");
+        assert_eq!(svg.mime_type().unwrap(), WalletIconMimeType::Svg);
+        assert_eq!(svg.parse().unwrap().bytes(), b"<svg></svg>");
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert!(matches!(
+            WalletIcon::new("https://example.com/icon.png"),
+            Err(WalletError::InvalidWalletIcon(_))
+        ));
+        assert!(matches!(
+            WalletIcon::new("data:image/svg+xml,<svg></svg>"),
+            Err(WalletError::InvalidWalletIcon(_))
+        ));
+        assert!(matches!(
+            WalletIcon::new("data:image/jpeg;base64,/9j/4AAQ"),
+            Err(WalletError::UnsupportedWalletIconMimeType(_))
+        ));
+        assert!(matches!(
+            WalletIcon::new("data:image/png;base64,R0lGODlhAQABAAAAACw="),
+            Err(WalletError::InvalidWalletIcon(_))
+        ));
+        assert!(matches!(
+            WalletIcon::new("data:image/png;base64,not base64"),
+            Err(WalletError::InvalidWalletIcon(_))
+        ));
+        assert!(matches!(
+            WalletIcon::new(PNG).unwrap().parse_with_limit(16),
+            Err(WalletError::WalletIconTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn sanitize() {
+        let icon = svg_icon(r#"<svg onload="alert(1)"><script>alert(1)</script><rect/></svg>"#);
+
+        assert_eq!(
+            icon.sanitize().unwrap().parse().unwrap().bytes(),
+            b"<svg><rect/></svg>"
+        );
+
+        let png = WalletIcon::new(PNG).unwrap();
+        assert_eq!(png.sanitize().unwrap(), png);
+    }
+}
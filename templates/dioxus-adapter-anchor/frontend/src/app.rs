@@ -36,7 +36,7 @@ pub(crate) static ACTIVE_CONNECTION: GlobalSignal<ConnectionInfo> =
 
 #[component]
 pub(crate) fn App() -> Element {
-    let wallet_event_listener = WALLET_ADAPTER.read().events().clone();
+    let wallet_event_listener = WALLET_ADAPTER.read().events();
 
     let clusters = vec![
         AdapterCluster::devnet(),
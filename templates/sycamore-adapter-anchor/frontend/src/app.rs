@@ -61,8 +61,10 @@ fn App() -> View {
     let active_connection = use_context::<Signal<ConnectionInfo>>();
     let global_message = use_context::<Signal<GlobalMessage>>();
 
+    let wallet_events = adapter.get_clone().events();
+
     spawn_local(async move {
-        while let Ok(wallet_event) = adapter.get_clone().events().recv().await {
+        while let Ok(wallet_event) = wallet_events.recv().await {
             account_state.set(AccountState::default());
 
             let connection_info = (adapter.get_clone().connection_info().await).clone();
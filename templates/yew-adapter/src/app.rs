@@ -18,7 +18,7 @@ pub fn logo() -> String {
 pub fn app() -> Html {
     let adapter = WalletAdapter::init().unwrap();
 
-    let events = adapter.events().clone();
+    let events = adapter.events();
 
     let init_state = GlobalAppInfo::new(adapter);
 